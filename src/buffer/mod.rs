@@ -1,4 +1,5 @@
 
 mod leb128_buffer;
 
+#[allow(unused_imports)]
 pub use leb128_buffer::*;
@@ -1,189 +1,752 @@
 use std::env;
-use std::error;
-use std::fmt::{self, Display};
-use std::path::PathBuf;
-use clap::{self, Arg};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use clap::{self, Arg, SubCommand};
 use crate::{FungeDialect};
-use crate::interpreter::{FungeInterpreter, FungeSpaceAccessor, FungeDimension, FungeDim2, FungeDim3, SpaceAccessorDim2, SpaceAccessorDim3};
-use crate::io::{CodeLoader, CodeSource};
-use std::io::{stdin, stdout};
+use crate::error::RsfungeError;
+use crate::interpreter::{FungeInterpreter, FungeSpaceAccessor, FungeDimension, FungeDim2, FungeDim3, SpaceAccessorDim2, SpaceAccessorDim3, FungeValue, FungeClock, ResourceLimits};
+use crate::io::{CodeLoader, CodeSource, CodeBuffer, CharEncoding, SourceMap};
+use std::io::{stdin, stdout, Write};
 use std::rc::Rc;
 use crate::interpreter::fingerprint::{FingerprintRegistry};
-use crate::interpreter::fingerprint::standard::{create_null_fingerprint};
+use crate::interpreter::fingerprint::standard::{create_bool_fingerprint, create_fixp_fingerprint, create_fpdp_fingerprint, create_fpsp_fingerprint, create_hrti_fingerprint, create_modu_fingerprint, create_null_fingerprint, create_orth_fingerprint, create_refc_fingerprint, create_roma_fingerprint, create_strn_fingerprint, create_toys_fingerprint};
 use std::cell::RefCell;
 use crate::utils::humanize;
+use crate::coverage::{CoverageMap, CoverageReport, CoverageSource};
+use crate::compiler::{B93Program, B93Cfg, FungeCfg, emit_rust_program};
+use crate::lint::LintReport;
+use crate::interpreter::FungeAddress;
+use num_bigint::BigInt;
+use chrono::NaiveDateTime;
+use std::time::Duration;
 
-//pub type ArgumentError = GenericError<S>;#
-#[derive(Debug)]
-pub struct ArgError {
-	message: String,
-}
-impl ArgError {
-	pub fn new(message: String) -> Self {
-		ArgError {
-			message,
-		}
-	}
-}
-impl error::Error for ArgError {
-	
-}
-impl Display for ArgError {
-	fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-		write!(f, "{}", self.message)
-	}
+thread_local! {
+	/// Message and location of the last panic, recorded by the panic hook of `start`
+	static PANIC_MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
 }
 
-pub fn start() -> i32 {
+/// Runs rsfunge with the process' command line arguments and
+/// returns the exit code of the funge program.
+pub fn start() -> Result<i32, RsfungeError> {
 	// Handle cli
-	let run_options: RunOptions;
-	if let Ok(options) = parse_cli() {
-		run_options = options;
-	}
-	else {
-		return -1;
-	}
+	let run_options = match parse_cli()? {
+		Some(CliCommand::Run(options)) => options,
+		Some(CliCommand::Compile(options)) => return run_compiler(&options).map(|_| 0),
+		Some(CliCommand::Cfg(options)) => return run_cfg_export(&options).map(|_| 0),
+		Some(CliCommand::Lint(options)) => return run_linter(&options),
+		None => return Ok(0), // Help or version was printed
+	};
 	
-	// Run interpreter
-	let dialect_mode: FungeDialect = FungeDialect::Befunge98;
-	let res = match dialect_mode {
-		FungeDialect::Befunge93 => run_interpreter::<FungeDim2, SpaceAccessorDim2<i32>>(run_options),
-//		FungeDialect::Unefunge98 => run_interpreter::<FungeDim1, SpaceAccessorDim1<i32>>(run_options),
-		FungeDialect::Befunge98 => run_interpreter::<FungeDim2, SpaceAccessorDim2<i32>>(run_options),
-		FungeDialect::Trefunge98 => run_interpreter::<FungeDim3, SpaceAccessorDim3<i32>>(run_options),
-		_ => unimplemented!(),
+	// Record panics instead of printing them, they are reported once as internal errors
+	panic::set_hook(Box::new(|info| {
+		let message = info.payload().downcast_ref::<&str>().map(|s| s.to_string())
+			.or_else(|| info.payload().downcast_ref::<String>().cloned())
+			.unwrap_or_else(|| String::from("unknown panic"));
+		let message = match info.location() {
+			Some(location) => format!("{} at {}:{}:{}", message, location.file(), location.line(), location.column()),
+			None => message,
+		};
+		PANIC_MESSAGE.with(|m| *m.borrow_mut() = Some(message));
+	}));
+	
+	// Run interpreter with the selected cell type, turning panics into internal errors
+	let res = panic::catch_unwind(AssertUnwindSafe(|| {
+		match run_options.cell_type {
+			CellType::Int32 => run_with_cell_type::<i32>(&run_options),
+			CellType::Int64 => run_with_cell_type::<i64>(&run_options),
+			CellType::Int128 => run_with_cell_type::<i128>(&run_options),
+			CellType::Big => run_with_cell_type::<BigInt>(&run_options),
+		}
+	}));
+	let _ = panic::take_hook();
+	
+	let res = match res {
+		Ok(res) => res,
+		Err(_) => {
+			let message = PANIC_MESSAGE.with(|m| m.borrow_mut().take()).unwrap_or_else(|| String::from("unknown panic"));
+			Err(RsfungeError::Internal(message))
+		}
 	};
 	
-	// Exit with exit code
-	return res.0;
+	// Tell the seed with every error, so that an unexpected failure can be replayed
+	return res.map_err(|error| RsfungeError::Seeded(Box::new(error), run_options.rng_seed));
 }
 
-fn parse_cli() -> Result<RunOptions, impl error::Error> {
+fn run_with_cell_type<V: FungeValue>(run_options: &RunOptions) -> Result<i32, RsfungeError> {
+	let dialect = run_options.dialect_mode.resolve(&run_options.source_file);
+	return match dialect {
+		FungeDialect::Befunge93 => run_interpreter::<FungeDim2, V, SpaceAccessorDim2<V>>(run_options, dialect),
+		FungeDialect::Unefunge98 => Err(RsfungeError::Dialect(String::from("Unefunge-98 is not supported yet"))),
+		FungeDialect::Befunge98 => run_interpreter::<FungeDim2, V, SpaceAccessorDim2<V>>(run_options, dialect),
+		FungeDialect::Trefunge98 => run_interpreter::<FungeDim3, V, SpaceAccessorDim3<V>>(run_options, dialect),
+	};
+}
+
+/// Parses the command line arguments.
+/// Returns None if only the help or version was requested.
+fn parse_cli() -> Result<Option<CliCommand>, RsfungeError> {
 	// Construct cli
-	let dialect_list = humanize::format_humaized_list(vec!["TEST", "WORLD"].as_slice());
-	let dialect_help: String = format!("Which dialect of Funge to use ({})", dialect_list);
+	let dialect_list = humanize::format_humaized_list(vec!["b93", "u98", "b98", "t98"].as_slice());
+	let dialect_help: String = format!("Which dialect of Funge to use ({}), guessed from the file extension if not given", dialect_list);
 	
 	let cli = clap::App::new("rsfunge")
+		.setting(clap::AppSettings::SubcommandsNegateReqs)
 		.arg(Arg::with_name("dialect")
 			.short("d").long("dialect")
 			.help(&dialect_help)
 			.takes_value(true))
+		.arg(Arg::with_name("cell-bits")
+			.long("cell-bits")
+			.value_name("BITS")
+			.help("Width of funge cells (32, 64, 128 or big for arbitrary precision)")
+			.takes_value(true))
+		.arg(Arg::with_name("encoding")
+			.long("encoding")
+			.value_name("ENCODING")
+			.help("Encoding of the source file and of the character input and output (utf8, latin1, utf16 or binary). A byte order mark in the source file takes precedence over utf8 and utf16")
+			.takes_value(true))
+		.arg(Arg::with_name("seed")
+			.long("seed")
+			.value_name("N")
+			.help("Seed for the random number generator used by ? and fingerprints (defaults to $RSFUNGE_SEED or a random seed)")
+			.takes_value(true))
+		.arg(Arg::with_name("print-seed")
+			.long("print-seed")
+			.help("Print the seed of the random number generator to stderr, so the run can be replayed"))
+		.arg(Arg::with_name("fake-time")
+			.long("fake-time")
+			.value_name("DATETIME")
+			.help("Use a fixed date and time (like 2020-01-01T00:00:00) instead of the real time")
+			.takes_value(true))
+		.arg(Arg::with_name("tick-micros")
+			.long("tick-micros")
+			.value_name("N")
+			.requires("fake-time")
+			.help("Advance the fake time by N microseconds every executed instruction")
+			.takes_value(true))
+		.arg(Arg::with_name("max-ticks")
+			.long("max-ticks")
+			.value_name("N")
+			.help("Stop after N executed instructions (exit status 120)")
+			.takes_value(true))
+		.arg(Arg::with_name("max-time")
+			.long("max-time")
+			.value_name("SECONDS")
+			.help("Stop after the given wall clock time (exit status 121)")
+			.takes_value(true))
+		.arg(Arg::with_name("max-pages")
+			.long("max-pages")
+			.value_name("N")
+			.help("Stop when more than N funge space pages are allocated (exit status 122)")
+			.takes_value(true))
+		.arg(Arg::with_name("max-stack")
+			.long("max-stack")
+			.value_name("N")
+			.help("Stop when all stacks together hold more than N cells (exit status 123)")
+			.takes_value(true))
+		.arg(Arg::with_name("max-threads")
+			.long("max-threads")
+			.value_name("N")
+			.help("Stop when a program tries to run more than N threads at once (exit status 124)")
+			.takes_value(true))
+		.arg(Arg::with_name("coverage")
+			.long("coverage")
+			.value_name("FILE")
+			.help("Write a coverage report of the executed code to the given file (html if the file ends with .html, plain text otherwise)")
+			.takes_value(true))
+		.arg(Arg::with_name("coverage-data")
+			.long("coverage-data")
+			.value_name("FILE")
+			.help("Merge the coverage of this run into the given coverage data file, creating it if it doesn't exist")
+			.takes_value(true))
+		.arg(Arg::with_name("no-trace-cache")
+			.long("no-trace-cache")
+			.help("Interpret every instruction instead of executing hot loops from precompiled instruction runs"))
+		.arg(Arg::with_name("load")
+			.long("load")
+			.value_name("FILE@X,Y[,Z]")
+			.help("Load another source file on top of the program, with its top left corner at the given position (can be repeated)")
+			.takes_value(true)
+			.multiple(true)
+			.number_of_values(1))
 		.arg(Arg::with_name("source-file")
 			.index(1)
 			.empty_values(false)
-			.required(true));
+			.required(true))
+		.subcommand(SubCommand::with_name("compile")
+			.about("Translate a Befunge-93 program into a standalone Rust program")
+			.arg(Arg::with_name("output")
+				.short("o").long("output")
+				.value_name("FILE")
+				.help("Where to write the Rust program (defaults to the source file with the extension .rs)")
+				.takes_value(true))
+			.arg(Arg::with_name("seed")
+				.long("seed")
+				.value_name("N")
+				.help("Seed for the random number generator of the program (defaults to $RSFUNGE_SEED or a random seed), ? goes the same ways as in rsfunge with this seed")
+				.takes_value(true))
+			.arg(Arg::with_name("source-file")
+				.index(1)
+				.empty_values(false)
+				.required(true)))
+		.subcommand(SubCommand::with_name("cfg")
+			.about("Print the static control flow graph of a program")
+			.arg(Arg::with_name("dialect")
+				.short("d").long("dialect")
+				.help(&dialect_help)
+				.takes_value(true))
+			.arg(Arg::with_name("encoding")
+				.long("encoding")
+				.value_name("ENCODING")
+				.help("Encoding of the source file (utf8, latin1, utf16 or binary)")
+				.takes_value(true))
+			.arg(Arg::with_name("dot")
+				.long("dot")
+				.help("Print the graph in the Graphviz DOT language instead of as plain text"))
+			.arg(Arg::with_name("output")
+				.short("o").long("output")
+				.value_name("FILE")
+				.help("Write the graph to the given file instead of stdout")
+				.takes_value(true))
+			.arg(Arg::with_name("source-file")
+				.index(1)
+				.empty_values(false)
+				.required(true)))
+		.subcommand(SubCommand::with_name("lint")
+			.about("Check a program for unreachable code and common mistakes, exits with status 1 if anything is found")
+			.arg(Arg::with_name("dialect")
+				.short("d").long("dialect")
+				.help(&dialect_help)
+				.takes_value(true))
+			.arg(Arg::with_name("encoding")
+				.long("encoding")
+				.value_name("ENCODING")
+				.help("Encoding of the source file (utf8, latin1, utf16 or binary)")
+				.takes_value(true))
+			.arg(Arg::with_name("json")
+				.long("json")
+				.help("Print the findings as JSON"))
+			.arg(Arg::with_name("source-file")
+				.index(1)
+				.empty_values(false)
+				.required(true)));
 	
 	// Evalutate cli invocation
-	let eval_result = (|| -> Result<RunOptions, ArgError> {
+	let eval_result = (|| -> Result<Option<CliCommand>, RsfungeError> {
 		let cli_result = cli.get_matches_from_safe(env::args_os());
 		
-		let matches;
-		if let Ok(m) = cli_result {
-			matches = m;
+		let matches = match cli_result {
+			Ok(m) => m,
+			Err(e) => match e.kind {
+				clap::ErrorKind::HelpDisplayed | clap::ErrorKind::VersionDisplayed => {
+					let _ = writeln!(stdout(), "{}", e.message);
+					return Ok(None);
+				}
+				_ => return Err(RsfungeError::InvalidArguments(e.message.trim_start_matches("error: ").to_string())),
+			},
+		};
+		
+		// Handle compile subcommand
+		if let Some(compile_matches) = matches.subcommand_matches("compile") {
+			let source_file = PathBuf::from(compile_matches.value_of("source-file").unwrap());
+			let output_file = match compile_matches.value_of("output") {
+				Some(file) => PathBuf::from(file),
+				None => source_file.with_extension("rs"),
+			};
+			return Ok(Some(CliCommand::Compile(CompileOptions {
+				source_file,
+				output_file,
+				rng_seed: parse_seed_arg(compile_matches)?,
+			})));
 		}
-		else {
-			return Err(ArgError::new(String::from("Failed to parse")));
+		
+		// Handle cfg subcommand
+		if let Some(cfg_matches) = matches.subcommand_matches("cfg") {
+			return Ok(Some(CliCommand::Cfg(CfgOptions {
+				source_file: PathBuf::from(cfg_matches.value_of("source-file").unwrap()),
+				dialect_mode: parse_dialect_arg(cfg_matches, &dialect_list)?,
+				encoding: parse_encoding_arg(cfg_matches)?,
+				dot: cfg_matches.is_present("dot"),
+				output_file: cfg_matches.value_of("output").map(PathBuf::from),
+			})));
 		}
 		
-		// TODO: Parse rest of options
+		// Handle lint subcommand
+		if let Some(lint_matches) = matches.subcommand_matches("lint") {
+			return Ok(Some(CliCommand::Lint(LintOptions {
+				source_file: PathBuf::from(lint_matches.value_of("source-file").unwrap()),
+				dialect_mode: parse_dialect_arg(lint_matches, &dialect_list)?,
+				encoding: parse_encoding_arg(lint_matches)?,
+				json: lint_matches.is_present("json"),
+			})));
+		}
 		
 		// Parse dialect options
-		let dialect_mode: DialectOption = if let Some(dialect_name) = matches.value_of("dialect") {
-			match dialect_name {
-				"b93" | "befunge93" => DialectOption::Specific(FungeDialect::Befunge93),
-				"u98" | "unefunge98" => DialectOption::Specific(FungeDialect::Unefunge98),
-				"b98" | "befunge98" => DialectOption::Specific(FungeDialect::Befunge98),
-				"t98" | "trefunge98" => DialectOption::Specific(FungeDialect::Trefunge98),
-				other => {
-					return Err(ArgError::new(String::from(format!("Unknown funge dialect '{}'", other))));
-				}
+		let dialect_mode = parse_dialect_arg(&matches, &dialect_list)?;
+		
+		// Parse cell type
+		let cell_type = match matches.value_of("cell-bits") {
+			None | Some("32") => CellType::Int32,
+			Some("64") => CellType::Int64,
+			Some("128") => CellType::Int128,
+			Some("big") => CellType::Big,
+			Some(other) => {
+				return Err(RsfungeError::InvalidArguments(format!("Invalid cell width '{}', expected 32, 64, 128 or big", other)));
 			}
-		}
-		else {
-			DialectOption::Unknown
 		};
 		
+		// Parse encoding
+		let encoding = parse_encoding_arg(&matches)?;
+		
 		// Get source file path
 		let source_file = {
 			let file = matches.value_of("source-file");
 			
 			if file.is_none() {
-				return Err(ArgError::new(String::from("Source file must be specified")));
+				return Err(RsfungeError::InvalidArguments(String::from("Source file must be specified")));
 			}
 			PathBuf::from(file.unwrap())
 		};
 		
+		let rng_seed = parse_seed_arg(&matches)?;
+		let print_seed = matches.is_present("print-seed");
+		
+		// Parse clock options
+		let clock = if let Some(fake_time) = matches.value_of("fake-time") {
+			let start = NaiveDateTime::parse_from_str(fake_time, "%Y-%m-%dT%H:%M:%S")
+				.map_err(|_| RsfungeError::InvalidArguments(format!("Invalid fake time '{}', expected a date like 2020-01-01T00:00:00", fake_time)))?;
+			
+			if let Some(tick_micros) = matches.value_of("tick-micros") {
+				let invalid = || RsfungeError::InvalidArguments(format!("Invalid tick length '{}', expected a non-negative number of microseconds within the supported date range", tick_micros));
+				let tick_micros = tick_micros.parse::<i64>().map_err(|_| invalid())?;
+				FungeClock::new_ticking(start, tick_micros).ok_or_else(invalid)?
+			}
+			else {
+				FungeClock::Fixed(start)
+			}
+		}
+		else {
+			FungeClock::Real
+		};
+		
+		// Parse resource limits
+		let mut limits = ResourceLimits::new();
+		limits.max_instructions = parse_limit_arg(&matches, "max-ticks")?;
+		limits.max_pages = parse_limit_arg(&matches, "max-pages")?;
+		limits.max_stack_cells = parse_limit_arg(&matches, "max-stack")?;
+		limits.max_threads = parse_limit_arg(&matches, "max-threads")?;
+		if let Some(max_time) = matches.value_of("max-time") {
+			let seconds = max_time.parse::<f64>().ok().filter(|s| s.is_finite() && *s >= 0.0)
+				.ok_or_else(|| RsfungeError::InvalidArguments(format!("Invalid time limit '{}', expected a number of seconds", max_time)))?;
+			limits.max_wall_time = Some(Duration::from_secs_f64(seconds));
+		}
+		
+		// Parse overlays
+		let mut overlays = Vec::new();
+		for value in matches.values_of("load").into_iter().flatten() {
+			overlays.push(parse_overlay_arg(value)?);
+		}
+		
+		// Get coverage options
+		let coverage_report_file = matches.value_of("coverage").map(PathBuf::from);
+		let coverage_data_file = matches.value_of("coverage-data").map(PathBuf::from);
+		
+		let trace_cache = !matches.is_present("no-trace-cache");
+		
 		// Make options object
 		let options = RunOptions {
 			dialect_mode,
+			cell_type,
+			encoding,
 			source_file,
+			rng_seed,
+			print_seed,
+			clock,
+			limits,
+			overlays,
+			coverage_report_file,
+			coverage_data_file,
+			trace_cache,
 		};
-		return Ok(options);
+		return Ok(Some(CliCommand::Run(options)));
 	})();
 	
-	match eval_result {
-		// Handle validation error
-		Err(err) => {
-			// Print cli help
-			eprint!("{}", err.message);
-			
-			return Err(err); // TODO: Handle properly, i.e. wrap in own error with message
-		}
-		// Return valid RunOptions object
-		Ok(options) => {
-			return Ok(options);
-		}
-	}
+	return eval_result;
 }
 
-fn run_interpreter<N, A>(run_options: RunOptions) -> (i32,) where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
-	// Load inital code
-	let code_source = CodeSource::new(run_options.source_file, match run_options.dialect_mode {DialectOption::Specific(d) => Some(d), _ => None});
+/// Parses the dialect argument
+fn parse_dialect_arg(matches: &clap::ArgMatches, dialect_list: &str) -> Result<DialectOption, RsfungeError> {
+	return match matches.value_of("dialect") {
+		Some("b93") | Some("befunge93") => Ok(DialectOption::Specific(FungeDialect::Befunge93)),
+		Some("u98") | Some("unefunge98") => Ok(DialectOption::Specific(FungeDialect::Unefunge98)),
+		Some("b98") | Some("befunge98") => Ok(DialectOption::Specific(FungeDialect::Befunge98)),
+		Some("t98") | Some("trefunge98") => Ok(DialectOption::Specific(FungeDialect::Trefunge98)),
+		Some(other) => Err(RsfungeError::Dialect(format!("Unknown funge dialect '{}' (known dialects are {})", other, dialect_list))),
+		None => Ok(DialectOption::Unknown),
+	};
+}
+
+/// Parses the encoding argument, which defaults to utf8
+fn parse_encoding_arg(matches: &clap::ArgMatches) -> Result<CharEncoding, RsfungeError> {
+	return match matches.value_of("encoding") {
+		Some(name) => CharEncoding::from_name(name)
+			.ok_or_else(|| RsfungeError::InvalidArguments(format!("Unknown encoding '{}', expected utf8, latin1, utf16 or binary", name))),
+		None => Ok(CharEncoding::Utf8),
+	};
+}
+
+/// Parses the rng seed, falling back to the environment and then to a random seed
+fn parse_seed_arg(matches: &clap::ArgMatches) -> Result<u64, RsfungeError> {
+	let seed_arg = matches.value_of("seed").map(String::from).or_else(|| env::var("RSFUNGE_SEED").ok());
+	return match seed_arg {
+		Some(seed) => seed.trim().parse::<u64>().map_err(|_| RsfungeError::InvalidArguments(format!("Invalid seed '{}', expected an unsigned integer", seed))),
+		None => Ok(rand::random::<u64>()),
+	};
+}
+
+/// Parses the value of an optional numeric limit argument
+fn parse_limit_arg<T: std::str::FromStr>(matches: &clap::ArgMatches, name: &str) -> Result<Option<T>, RsfungeError> {
+	return match matches.value_of(name) {
+		Some(value) => value.parse::<T>().map(Some)
+			.map_err(|_| RsfungeError::InvalidArguments(format!("Invalid value '{}' for --{}, expected an unsigned integer", value, name))),
+		None => Ok(None),
+	};
+}
+
+/// Parses an overlay argument like `lib.b98@100,0`.
+/// Returns the path, the position and the number of given coordinates.
+fn parse_overlay_arg(value: &str) -> Result<(PathBuf, FungeAddress, u32), RsfungeError> {
+	let invalid = || RsfungeError::InvalidArguments(format!("Invalid value '{}' for --load, expected FILE@X,Y[,Z]", value));
 	
-	let mut loader = CodeLoader::new();
-	let code_buffer = loader.load_from_file(code_source.clone());
+	let (path, coords) = value.rsplit_once('@').ok_or_else(invalid)?;
+	if path.is_empty() {
+		return Err(invalid());
+	}
 	
-	if let Err(e) = code_buffer {
-		panic!("Failed to load code from file: \"{}\" ({})", code_source.get_path().display(), e);
+	let mut position = [0i32; 3];
+	let mut num_coords = 0;
+	for part in coords.split(',') {
+		if num_coords >= 3 {
+			return Err(invalid());
+		}
+		position[num_coords] = part.trim().parse::<i32>().map_err(|_| invalid())?;
+		num_coords += 1;
 	}
+	return Ok((PathBuf::from(path), FungeAddress::new_xyz(position[0], position[1], position[2]), num_coords as u32));
+}
+
+fn run_interpreter<N, V, A>(run_options: &RunOptions, dialect: FungeDialect) -> Result<i32, RsfungeError> where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
+	// Load inital code
+	let code_source = CodeSource::new(run_options.source_file.clone(), Some(dialect));
 	
-	// Get actual dialect
-	let actual_dialect: FungeDialect = if let DialectOption::Specific(d) = run_options.dialect_mode {d}
-	else {
-		// TODO: Implement dialect probing
-		unimplemented!();
-	};
+	let mut loader = CodeLoader::with_encoding(run_options.encoding);
+	let code_buffer = loader.load_from_file(code_source.clone()).map_err(|error| RsfungeError::Load {
+		path: run_options.source_file.clone(),
+		error,
+	})?;
 	
 	// Make fingerprint registry
 	let fingerprint_registry_ref = Rc::new(RefCell::new(FingerprintRegistry::new()));
 	
 	// Register standard fingerprints
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_bool_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_fixp_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_fpdp_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_fpsp_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_hrti_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_modu_fingerprint()));
 	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_null_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_orth_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_refc_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_roma_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_strn_fingerprint()));
+	fingerprint_registry_ref.borrow_mut().register_fingerprint(Rc::from(create_toys_fingerprint()));
 	
 	// Create interpreter
 	let charout = stdout();
 	let charin = stdin();
-	let mut interpreter: FungeInterpreter<N, A> = FungeInterpreter::new(actual_dialect, code_source, fingerprint_registry_ref, charout, charin);
+	let mut interpreter: FungeInterpreter<N, V, A> = FungeInterpreter::new(dialect, code_source.clone(), fingerprint_registry_ref, charout, charin);
+	
+	// Seed rng
+	interpreter.set_rng_seed(run_options.rng_seed);
+	if run_options.print_seed {
+		eprintln!("rng seed: {}", run_options.rng_seed);
+	}
+	
+	interpreter.set_encoding(run_options.encoding);
+	
+	// Set clock
+	interpreter.set_clock(run_options.clock.clone());
+	
+	// Set resource limits
+	interpreter.set_limits(run_options.limits.clone());
+	
+	interpreter.set_trace_cache_enabled(run_options.trace_cache);
+	
+	let coverage_enabled = run_options.coverage_report_file.is_some() || run_options.coverage_data_file.is_some();
+	if coverage_enabled {
+		interpreter.enable_coverage();
+	}
 	
 	// Load inital code into interpreter
-	interpreter.load_initial_code(&code_buffer.unwrap());
+	interpreter.load_initial_code(&code_buffer);
+	
+	// Load overlays on top of the initial code, keeping them for the coverage report
+	let mut loaded_files = vec![(code_source.get_path().display().to_string(), FungeAddress::new_value(0), code_buffer)];
+	for (path, position, num_coords) in run_options.overlays.iter() {
+		if *num_coords > dialect.dimensionality() {
+			return Err(RsfungeError::InvalidArguments(format!("The position of \"{}\" has more coordinates than the dialect has dimensions", path.display())));
+		}
+		
+		let overlay_source = CodeSource::new(path.clone(), Some(dialect));
+		let overlay_buffer = loader.load_from_file(overlay_source.clone()).map_err(|error| RsfungeError::Load {
+			path: path.clone(),
+			error,
+		})?;
+		
+		let location = interpreter.get_source_map().describe(position);
+		let overlaps = interpreter.load_overlay(&overlay_source, &overlay_buffer, *position);
+		if let Some((_, first)) = overlaps.first() {
+			eprintln!("rsfunge: warning: \"{}\" loaded at {} overwrites {} non-space cells, the first at {}",
+				path.display(), location, overlaps.len(), first);
+		}
+		loaded_files.push((path.display().to_string(), *position, overlay_buffer));
+	}
+	
+	// Read the coverage of previous runs up front, so that data of other source files fails before running
+	let mut coverage_sources = Vec::new();
+	let mut previous_coverage = None;
+	if let Some(data_file) = &run_options.coverage_data_file {
+		coverage_sources = read_coverage_sources(run_options)?;
+		previous_coverage = read_previous_coverage(data_file, &coverage_sources)?;
+	}
 	
 	// Transfer control to interpreter and start execution
-	interpreter.start_execution();
+	let result = interpreter.start_execution();
+	
+	// Write coverage, also if a limit stopped the execution
+	if let Some(coverage) = interpreter.get_coverage() {
+		write_coverage(run_options, &loaded_files, coverage, previous_coverage.as_ref(), &coverage_sources, interpreter.get_source_map())?;
+	}
+	result?;
 	
 	// Exit with exit code
-	let exit_code = if let Some(code) = interpreter.get_programmatic_exit_code() {code}
-	else {0};
+	let exit_code = interpreter.get_programmatic_exit_code().unwrap_or_default();
+	
+	return Ok(exit_code);
+}
+
+/// Translates a Befunge-93 program into a Rust program.
+fn run_compiler(options: &CompileOptions) -> Result<(), RsfungeError> {
+	let code_source = CodeSource::new(options.source_file.clone(), Some(FungeDialect::Befunge93));
+	let code_buffer = CodeLoader::new().load_from_file(code_source).map_err(|error| RsfungeError::Load {
+		path: options.source_file.clone(),
+		error,
+	})?;
+	
+	let program = B93Program::from_code(&code_buffer).map_err(RsfungeError::Compile)?;
+	let cfg = B93Cfg::build(&program);
+	if !cfg.is_static() {
+		eprintln!("rsfunge: note: \"{}\" may modify its own code, embedding an interpreter instead", options.source_file.display());
+	}
+	
+	let source_name = options.source_file.display().to_string();
+	let rust_code = emit_rust_program(&program, &cfg, &source_name, options.rng_seed);
+	std::fs::write(&options.output_file, rust_code).map_err(|error| RsfungeError::Io {path: options.output_file.clone(), error})?;
+	return Ok(());
+}
+
+/// Loads a program and builds its control flow graph.
+fn build_cfg(source_file: &Path, dialect: FungeDialect, encoding: CharEncoding) -> Result<(CodeSource, CodeBuffer, FungeCfg), RsfungeError> {
+	let code_source = CodeSource::new(source_file.to_path_buf(), Some(dialect));
+	let code_buffer = CodeLoader::with_encoding(encoding).load_from_file(code_source.clone()).map_err(|error| RsfungeError::Load {
+		path: source_file.to_path_buf(),
+		error,
+	})?;
 	
-	return (exit_code,);
+	let cfg = match dialect {
+		FungeDialect::Befunge93 | FungeDialect::Befunge98 => FungeCfg::build::<FungeDim2, SpaceAccessorDim2<i32>>(&code_buffer, dialect),
+		FungeDialect::Unefunge98 => return Err(RsfungeError::Dialect(String::from("Unefunge-98 is not supported yet"))),
+		FungeDialect::Trefunge98 => FungeCfg::build::<FungeDim3, SpaceAccessorDim3<i32>>(&code_buffer, dialect),
+	};
+	return Ok((code_source, code_buffer, cfg));
+}
+
+/// Builds the control flow graph of a program and prints it.
+fn run_cfg_export(options: &CfgOptions) -> Result<(), RsfungeError> {
+	let dialect = options.dialect_mode.resolve(&options.source_file);
+	let (_, _, cfg) = build_cfg(&options.source_file, dialect, options.encoding)?;
+	
+	let contents = if options.dot {
+		cfg.format_dot(&options.source_file.display().to_string())
+	}
+	else {
+		cfg.format_text()
+	};
+	
+	return match &options.output_file {
+		Some(file) => std::fs::write(file, contents).map_err(|error| RsfungeError::Io {path: file.clone(), error}),
+		None => {
+			let _ = stdout().write_all(contents.as_bytes());
+			Ok(())
+		}
+	};
+}
+
+/// Lints a program and prints the findings.
+/// Returns 1 if anything was found, 0 otherwise.
+fn run_linter(options: &LintOptions) -> Result<i32, RsfungeError> {
+	let dialect = options.dialect_mode.resolve(&options.source_file);
+	let (code_source, code_buffer, cfg) = build_cfg(&options.source_file, dialect, options.encoding)?;
+	
+	let report = LintReport::new(&code_buffer, &code_source, &cfg, dialect);
+	let contents = if options.json {
+		report.format_json()
+	}
+	else {
+		report.format_text()
+	};
+	let _ = stdout().write_all(contents.as_bytes());
+	
+	return Ok(if report.findings.is_empty() {0} else {1});
+}
+
+/// Identifies the source file and the overlays of a run in coverage data files.
+fn read_coverage_sources(run_options: &RunOptions) -> Result<Vec<CoverageSource>, RsfungeError> {
+	let paths = std::iter::once(&run_options.source_file).chain(run_options.overlays.iter().map(|(path, _, _)| path));
+	
+	let mut sources = Vec::new();
+	for path in paths {
+		let contents = std::fs::read(path).map_err(|error| RsfungeError::Io {path: path.clone(), error})?;
+		sources.push(CoverageSource::new(path.display().to_string(), &contents));
+	}
+	return Ok(sources);
+}
+
+/// Reads the coverage of previous runs from the data file if it exists.
+/// Fails if the coverage was recorded for other source files, or for other versions of them.
+fn read_previous_coverage(data_file: &Path, sources: &[CoverageSource]) -> Result<Option<CoverageMap>, RsfungeError> {
+	if !data_file.exists() {
+		return Ok(None);
+	}
+	
+	let (previous, previous_sources) = CoverageMap::read_data_file(data_file).map_err(|error| RsfungeError::Io {path: data_file.to_path_buf(), error})?;
+	if previous_sources != sources {
+		return Err(RsfungeError::InvalidArguments(format!("The coverage data in \"{}\" was recorded for other source files or other versions of them", data_file.display())));
+	}
+	return Ok(Some(previous));
+}
+
+/// Writes the coverage data and report of a run, the loaded files are given
+/// by their name, position and code, starting with the source file.
+fn write_coverage(run_options: &RunOptions, loaded_files: &[(String, FungeAddress, CodeBuffer)], coverage: &CoverageMap, previous_coverage: Option<&CoverageMap>, sources: &[CoverageSource], source_map: &SourceMap) -> Result<(), RsfungeError> {
+	let mut merged_coverage = CoverageMap::new();
+	merged_coverage.merge(coverage);
+	
+	// Merge with the coverage of previous runs
+	if let Some(data_file) = &run_options.coverage_data_file {
+		if let Some(previous) = previous_coverage {
+			merged_coverage.merge(previous);
+		}
+		
+		let comment = format!("seed: {}", run_options.rng_seed);
+		merged_coverage.write_data_file(data_file, sources, &comment).map_err(|error| RsfungeError::Io {path: data_file.clone(), error})?;
+	}
+	
+	// Write report
+	if let Some(report_file) = &run_options.coverage_report_file {
+		let (source_name, origin, code_buffer) = &loaded_files[0];
+		let mut report = CoverageReport::new(source_name, code_buffer, *origin, &merged_coverage)
+			.with_source_map(source_map)
+			.with_rng_seed(run_options.rng_seed);
+		for (name, position, overlay_buffer) in loaded_files[1..].iter() {
+			report = report.with_overlay(name, overlay_buffer, *position);
+		}
+		
+		let is_html = report_file.extension().is_some_and(|ext| ext == "html" || ext == "htm");
+		let contents = if is_html {
+			report.format_html()
+		}
+		else {
+			report.format_text()
+		};
+		
+		std::fs::write(report_file, contents).map_err(|error| RsfungeError::Io {path: report_file.clone(), error})?;
+	}
+	return Ok(());
+}
+
+/// What rsfunge was asked to do
+pub enum CliCommand {
+	Run(RunOptions),
+	Compile(CompileOptions),
+	Cfg(CfgOptions),
+	Lint(LintOptions),
+}
+
+pub struct CompileOptions {
+	source_file: PathBuf,
+	output_file: PathBuf,
+	rng_seed: u64,
+}
+
+pub struct CfgOptions {
+	source_file: PathBuf,
+	dialect_mode: DialectOption,
+	encoding: CharEncoding,
+	/// Print the graph in the DOT language
+	dot: bool,
+	output_file: Option<PathBuf>,
+}
+
+pub struct LintOptions {
+	source_file: PathBuf,
+	dialect_mode: DialectOption,
+	encoding: CharEncoding,
+	/// Print the findings as JSON
+	json: bool,
 }
 
 pub struct RunOptions {
 	source_file: PathBuf,
 	dialect_mode: DialectOption,
+	cell_type: CellType,
+	encoding: CharEncoding,
+	rng_seed: u64,
+	print_seed: bool,
+	clock: FungeClock,
+	limits: ResourceLimits,
+	/// Additional source files and the positions they're loaded at
+	overlays: Vec<(PathBuf, FungeAddress, u32)>,
+	coverage_report_file: Option<PathBuf>,
+	coverage_data_file: Option<PathBuf>,
+	/// Execute hot loops from the trace cache
+	trace_cache: bool,
 }
 
 pub enum DialectOption {
 	Specific(FungeDialect),
 	Unknown,
 }
+
+impl DialectOption {
+	/// Returns the given dialect or guesses it from the extension of the source file.
+	fn resolve(&self, source_file: &Path) -> FungeDialect {
+		return match self {
+			DialectOption::Specific(d) => *d,
+			DialectOption::Unknown => {
+				let extension = source_file.extension().and_then(|e| e.to_str()).unwrap_or("");
+				FungeDialect::from_file_extension(extension).unwrap_or(FungeDialect::Befunge98)
+			}
+		};
+	}
+}
+
+/// The type of the funge cells, selected via `--cell-bits`
+#[derive(Copy, Clone)]
+pub enum CellType {
+	Int32,
+	Int64,
+	Int128,
+	Big,
+}
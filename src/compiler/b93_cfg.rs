@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use crate::io::CodeBuffer;
+
+/// Width of the Befunge-93 torus
+pub const B93_WIDTH: usize = 80;
+/// Height of the Befunge-93 torus
+pub const B93_HEIGHT: usize = 25;
+
+/// The 80x25 cells of a Befunge-93 program.
+pub struct B93Program {
+	pub cells: Vec<i32>,
+}
+
+impl B93Program {
+	/// Copies the code into a Befunge-93 grid.
+	/// Returns an error message if the code doesn't fit into 80x25 cells.
+	pub fn from_code(code: &CodeBuffer) -> Result<Self, String> {
+		let size = code.bounding_box;
+		if size.x() as usize > B93_WIDTH || size.y() as usize > B93_HEIGHT {
+			return Err(format!("the code is {}x{} cells, but Befunge-93 programs must fit into {}x{} cells", size.x(), size.y(), B93_WIDTH, B93_HEIGHT));
+		}
+		
+		let mut cells = vec![32; B93_WIDTH * B93_HEIGHT];
+		for (offset, value) in code.cells() {
+			cells[offset.y() as usize * B93_WIDTH + offset.x() as usize] = value as i32;
+		}
+		return Ok(B93Program {
+			cells,
+		});
+	}
+	
+	#[inline]
+	pub fn get(&self, x: usize, y: usize) -> i32 {
+		return self.cells[y * B93_WIDTH + x];
+	}
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Direction {
+	East,
+	South,
+	West,
+	North,
+}
+
+impl Direction {
+	pub fn delta(&self) -> (i32, i32) {
+		return match self {
+			Direction::East => (1, 0),
+			Direction::South => (0, 1),
+			Direction::West => (-1, 0),
+			Direction::North => (0, -1),
+		};
+	}
+	
+	pub fn reverse(&self) -> Self {
+		return match self {
+			Direction::East => Direction::West,
+			Direction::South => Direction::North,
+			Direction::West => Direction::East,
+			Direction::North => Direction::South,
+		};
+	}
+}
+
+/// An ip that is about to execute the cell at x, y while moving in the given direction.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct CfgState {
+	pub x: usize,
+	pub y: usize,
+	pub dir: Direction,
+}
+
+impl CfgState {
+	/// Moves the given number of cells in the given direction, wrapping around the torus.
+	fn advance(&self, dir: Direction, cells: i32) -> Self {
+		let (dx, dy) = dir.delta();
+		return CfgState {
+			x: (self.x as i32 + dx * cells).rem_euclid(B93_WIDTH as i32) as usize,
+			y: (self.y as i32 + dy * cells).rem_euclid(B93_HEIGHT as i32) as usize,
+			dir,
+		};
+	}
+}
+
+/// An instruction that doesn't change the control flow.
+#[derive(Clone, Debug)]
+pub enum CfgOp {
+	Push(i32),
+	Add,
+	Subtract,
+	Multiply,
+	Divide,
+	Remainder,
+	Not,
+	Greater,
+	Duplicate,
+	Swap,
+	Pop,
+	OutputInteger,
+	OutputChar,
+	Get,
+	Put,
+}
+
+/// How control leaves a block.
+#[derive(Clone, Debug)]
+pub enum CfgExit {
+	Jump(usize),
+	/// Pops a value and continues with the first block if it is zero, with the second otherwise
+	Branch(usize, usize),
+	/// Continues with a random one of the blocks (`?`), west, east, north and south
+	/// like the directions of `semantics::RANDOM_DIRECTIONS_2D`
+	Random([usize; 4]),
+	/// Reads an integer (`&`), continues with the second block if nothing could be read
+	InputInteger(usize, usize),
+	/// Reads a character (`~`), continues with the second block if nothing could be read
+	InputChar(usize, usize),
+	Stop,
+}
+
+/// A straight line of instructions with a single entry.
+pub struct CfgBlock {
+	/// The state the block starts at
+	pub entry: CfgState,
+	pub ops: Vec<CfgOp>,
+	pub exit: CfgExit,
+}
+
+/// The effect of executing a single state, before states are grouped into blocks.
+enum StateExit {
+	Jump(CfgState),
+	Branch(CfgState, CfgState),
+	Random([CfgState; 4]),
+	InputInteger(CfgState, CfgState),
+	InputChar(CfgState, CfgState),
+	Stop,
+}
+
+impl StateExit {
+	fn successors(&self) -> Vec<CfgState> {
+		return match self {
+			StateExit::Jump(s) => vec![*s],
+			StateExit::Branch(a, b) | StateExit::InputInteger(a, b) | StateExit::InputChar(a, b) => vec![*a, *b],
+			StateExit::Random(states) => states.to_vec(),
+			StateExit::Stop => vec![],
+		};
+	}
+}
+
+/// Control flow graph of a Befunge-93 program over (cell, direction) states.
+///
+/// Every state an ip can reach from the start is decoded once. States are
+/// grouped into blocks of instructions that are always executed together,
+/// so the graph can be translated into straight-line code with jumps between blocks.
+/// Characters that aren't Befunge-93 instructions reflect the ip, like in the interpreter.
+pub struct B93Cfg {
+	/// The blocks of the program, the first one is the entry block
+	pub blocks: Vec<CfgBlock>,
+	/// All cells whose contents determine the control flow or the pushed values
+	code_cells: Vec<bool>,
+	/// Targets of all `p` instructions, None if a target isn't a constant
+	put_targets: Vec<Option<(i32, i32)>>,
+}
+
+impl B93Cfg {
+	pub fn build(program: &B93Program) -> Self {
+		let start = CfgState {x: 0, y: 0, dir: Direction::East};
+		let mut code_cells = vec![false; B93_WIDTH * B93_HEIGHT];
+		
+		// Decode all reachable states
+		let mut states: HashMap<CfgState, (Vec<CfgOp>, StateExit)> = HashMap::new();
+		let mut order = Vec::new();
+		let mut worklist = vec![start];
+		while let Some(state) = worklist.pop() {
+			if states.contains_key(&state) {
+				continue;
+			}
+			
+			let (ops, exit) = Self::decode_state(program, &state, &mut code_cells);
+			worklist.extend(exit.successors().into_iter().filter(|next| !states.contains_key(next)));
+			states.insert(state, (ops, exit));
+			order.push(state);
+		}
+		
+		// Count predecessors to find the states that start blocks
+		let mut predecessors: HashMap<CfgState, u32> = HashMap::new();
+		for (_, exit) in states.values() {
+			let is_jump = matches!(exit, StateExit::Jump(_));
+			for next in exit.successors() {
+				// Targets of branches always start a block
+				*predecessors.entry(next).or_insert(0) += if is_jump {1} else {2};
+			}
+		}
+		let is_leader = |state: &CfgState| *state == start || predecessors.get(state).cloned().unwrap_or(0) != 1;
+		
+		// Give every leader a block index, the start state first
+		let mut block_indices: HashMap<CfgState, usize> = HashMap::new();
+		let mut leaders = Vec::new();
+		for state in order.iter() {
+			if is_leader(state) {
+				block_indices.insert(*state, leaders.len());
+				leaders.push(*state);
+			}
+		}
+		
+		// Follow each leader along its jumps until the next leader
+		let mut blocks = Vec::with_capacity(leaders.len());
+		for leader in leaders.iter() {
+			let mut block_ops = Vec::new();
+			let mut state = *leader;
+			let exit = loop {
+				let (ops, exit) = &states[&state];
+				block_ops.extend(ops.iter().cloned());
+				
+				match exit {
+					StateExit::Jump(next) if !is_leader(next) => state = *next,
+					StateExit::Jump(next) => break CfgExit::Jump(block_indices[next]),
+					StateExit::Branch(a, b) => break CfgExit::Branch(block_indices[a], block_indices[b]),
+					StateExit::Random(s) => break CfgExit::Random([block_indices[&s[0]], block_indices[&s[1]], block_indices[&s[2]], block_indices[&s[3]]]),
+					StateExit::InputInteger(a, b) => break CfgExit::InputInteger(block_indices[a], block_indices[b]),
+					StateExit::InputChar(a, b) => break CfgExit::InputChar(block_indices[a], block_indices[b]),
+					StateExit::Stop => break CfgExit::Stop,
+				}
+			};
+			blocks.push(CfgBlock {
+				entry: *leader,
+				ops: block_ops,
+				exit,
+			});
+		}
+		
+		// Find the targets of all puts, which are constant if both coordinates were pushed right before
+		let mut put_targets = Vec::new();
+		for block in blocks.iter() {
+			for (i, op) in block.ops.iter().enumerate() {
+				if let CfgOp::Put = op {
+					let target = match (i.checked_sub(2).map(|j| &block.ops[j]), i.checked_sub(1).map(|j| &block.ops[j])) {
+						(Some(CfgOp::Push(x)), Some(CfgOp::Push(y))) => Some((*x, *y)),
+						_ => None,
+					};
+					put_targets.push(target);
+				}
+			}
+		}
+		
+		return B93Cfg {
+			blocks,
+			code_cells,
+			put_targets,
+		};
+	}
+	
+	/// Checks if the program never modifies its own code. That's the case if all
+	/// `p` instructions write to constant positions that are never executed.
+	pub fn is_static(&self) -> bool {
+		return self.put_targets.iter().all(|target| match target {
+			Some((x, y)) => {
+				let in_grid = (0..B93_WIDTH as i32).contains(x) && (0..B93_HEIGHT as i32).contains(y);
+				!in_grid || !self.code_cells[*y as usize * B93_WIDTH + *x as usize]
+			}
+			None => false,
+		});
+	}
+	
+	/// Decodes the instruction the ip executes in the given state.
+	fn decode_state(program: &B93Program, state: &CfgState, code_cells: &mut [bool]) -> (Vec<CfgOp>, StateExit) {
+		code_cells[state.y * B93_WIDTH + state.x] = true;
+		let forward = state.advance(state.dir, 1);
+		let turn = |dir: Direction| state.advance(dir, 1);
+		
+		let op = match program.get(state.x, state.y) {
+			/* space */ 32 => return (vec![], StateExit::Jump(forward)),
+			/* > */ 62 => return (vec![], StateExit::Jump(turn(Direction::East))),
+			/* v */ 118 => return (vec![], StateExit::Jump(turn(Direction::South))),
+			/* < */ 60 => return (vec![], StateExit::Jump(turn(Direction::West))),
+			/* ^ */ 94 => return (vec![], StateExit::Jump(turn(Direction::North))),
+			/* # */ 35 => return (vec![], StateExit::Jump(state.advance(state.dir, 2))),
+			/* _ */ 95 => return (vec![], StateExit::Branch(turn(Direction::East), turn(Direction::West))),
+			/* | */ 124 => return (vec![], StateExit::Branch(turn(Direction::South), turn(Direction::North))),
+			/* ? */ 63 => return (vec![], StateExit::Random([turn(Direction::West), turn(Direction::East), turn(Direction::North), turn(Direction::South)])),
+			/* & */ 38 => return (vec![], StateExit::InputInteger(forward, turn(state.dir.reverse()))),
+			/* ~ */ 126 => return (vec![], StateExit::InputChar(forward, turn(state.dir.reverse()))),
+			/* @ */ 64 => return (vec![], StateExit::Stop),
+			/* " */ 34 => {
+				// Push everything up to the closing quote, which exists because the line wraps back to the opening one
+				let mut ops = Vec::new();
+				let mut pos = forward;
+				loop {
+					code_cells[pos.y * B93_WIDTH + pos.x] = true;
+					let value = program.get(pos.x, pos.y);
+					if value == 34 {
+						break;
+					}
+					ops.push(CfgOp::Push(value));
+					pos = pos.advance(state.dir, 1);
+				}
+				return (ops, StateExit::Jump(pos.advance(state.dir, 1)));
+			}
+			/* 0...9 */ n @ 48..=57 => CfgOp::Push(n - 48),
+			/* + */ 43 => CfgOp::Add,
+			/* - */ 45 => CfgOp::Subtract,
+			/* * */ 42 => CfgOp::Multiply,
+			/* / */ 47 => CfgOp::Divide,
+			/* % */ 37 => CfgOp::Remainder,
+			/* ! */ 33 => CfgOp::Not,
+			/* ` */ 96 => CfgOp::Greater,
+			/* : */ 58 => CfgOp::Duplicate,
+			/* \ */ 92 => CfgOp::Swap,
+			/* $ */ 36 => CfgOp::Pop,
+			/* . */ 46 => CfgOp::OutputInteger,
+			/* , */ 44 => CfgOp::OutputChar,
+			/* g */ 103 => CfgOp::Get,
+			/* p */ 112 => CfgOp::Put,
+			// Everything else reflects
+			_ => return (vec![], StateExit::Jump(turn(state.dir.reverse()))),
+		};
+		return (vec![op], StateExit::Jump(forward));
+	}
+}
@@ -0,0 +1,496 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use crate::interpreter::{FungeAddress, FungeDimension, FungeSpace, FungeSpaceAccessor, InstructionDelta, InstructionPointer};
+use crate::interpreter::instruction::insts;
+use crate::io::CodeBuffer;
+use crate::compiler::{B93_WIDTH, B93_HEIGHT};
+use crate::FungeDialect;
+
+/// Maximum number of cells skipped while looking for the next instruction or the end of a string
+const MAX_SKIPPED_CELLS: u32 = 1 << 20;
+
+/// An ip that is about to execute the instruction at its position while moving with the given delta.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct FungeCfgState {
+	pub ip: InstructionPointer,
+	pub delta: InstructionDelta,
+	/// The argument of the `j` at the ip, if it was pushed as a constant right before
+	jump_count: Option<i32>,
+}
+
+/// Where control goes when leaving a block.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum FungeCfgTarget {
+	Block(usize),
+	/// The thread stops (`@` or `q`)
+	Stop,
+	/// The next state depends on values only known at runtime (`x` or `j` with a computed argument)
+	Unknown,
+}
+
+pub struct FungeCfgEdge {
+	pub target: FungeCfgTarget,
+	/// When the edge is taken, empty if it's the only way out of the block
+	pub label: &'static str,
+}
+
+/// A straight run of instructions with a single entry.
+pub struct FungeCfgBlock {
+	pub entry: FungeCfgState,
+	/// The executed instructions and their positions
+	pub instructions: Vec<(FungeAddress, i32)>,
+	pub edges: Vec<FungeCfgEdge>,
+}
+
+/// The successor of a single state, before states are grouped into blocks.
+#[derive(Copy, Clone)]
+enum Successor {
+	State(FungeCfgState),
+	Stop,
+	Unknown,
+}
+
+/// What the ip passes over while moving between instructions.
+#[derive(Default)]
+struct Trail {
+	cells: HashSet<FungeAddress>,
+	unterminated_strings: HashSet<FungeAddress>,
+	unterminated_comments: HashSet<FungeAddress>,
+}
+
+/// The loaded program, read and moved through like in its dialect.
+struct CfgSpace<'s, N, A> where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
+	funge_space: FungeSpace<'s, N, i32, A>,
+	dialect: FungeDialect,
+}
+
+impl<'s, N, A> CfgSpace<'s, N, A> where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
+	fn read_cell(&self, address: &FungeAddress) -> i32 {
+		return self.funge_space.read_cell(address);
+	}
+	
+	/// Moves the ip by delta. Befunge-93 wraps around its 80x25 torus,
+	/// all other dialects wrap around the bounds of the code like the interpreter.
+	fn next_ip(&self, ip: &InstructionPointer, delta: &InstructionDelta) -> InstructionPointer {
+		if let FungeDialect::Befunge93 = self.dialect {
+			let mut next = *ip;
+			next.set_x((ip.x() + delta.x()).rem_euclid(B93_WIDTH as i32));
+			next.set_y((ip.y() + delta.y()).rem_euclid(B93_HEIGHT as i32));
+			return next;
+		}
+		return self.funge_space.next_ip(ip, delta);
+	}
+	
+	/// Befunge-93 has no `;` comments.
+	fn has_comments(&self) -> bool {
+		return !matches!(self.dialect, FungeDialect::Befunge93);
+	}
+}
+
+/// Control flow graph of a Funge program over (position, delta) states.
+///
+/// Every state an ip can reach from the start is decoded once, moving through
+/// funge space with the same wrapping and skipping of spaces and `;` comments
+/// as the interpreter. Befunge-93 programs wrap around the 80x25 torus instead,
+/// have no comments and reflect on all Funge-98 instructions. Code modified at
+/// runtime by `p`, `s` or `i` isn't known, so the graph describes the program as it was loaded.
+pub struct FungeCfg {
+	/// The blocks of the program, the first one is the entry block
+	pub blocks: Vec<FungeCfgBlock>,
+	/// All cells an ip can pass over, including skipped cells, comments and strings
+	pub visited_cells: HashSet<FungeAddress>,
+	/// Opening quotes of strings that aren't closed on their line, so they wrap around to themselves
+	pub unterminated_strings: HashSet<FungeAddress>,
+	/// Opening `;` of comments that aren't closed on their line
+	pub unterminated_comments: HashSet<FungeAddress>,
+	dims: u32,
+}
+
+impl FungeCfg {
+	pub fn build<N, A>(code: &CodeBuffer, dialect: FungeDialect) -> Self where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
+		let dims = A::dimensionality();
+		
+		// Load code like the interpreter does
+		let mut funge_space = FungeSpace::<N, i32, A>::new();
+		for (offset, value) in code.cells() {
+			if value != 32 {
+				funge_space.write_cell(&offset, value as i32);
+			}
+		}
+		let funge_space = CfgSpace {
+			funge_space,
+			dialect,
+		};
+		
+		let trail = RefCell::new(Trail::default());
+		let start = match Self::settle(&funge_space, &trail, FungeAddress::new_value(0), InstructionDelta::new_xyz(1, 0, 0)) {
+			Successor::State(state) => state,
+			_ => return FungeCfg::from_blocks(Vec::new(), trail.into_inner(), dims), // No code at all
+		};
+		
+		// Decode all reachable states
+		let mut states: HashMap<FungeCfgState, Vec<(Successor, &'static str)>> = HashMap::new();
+		let mut order = Vec::new();
+		let mut worklist = vec![start];
+		while let Some(state) = worklist.pop() {
+			if states.contains_key(&state) {
+				continue;
+			}
+			
+			let successors = Self::decode_state(&funge_space, &trail, dims, &state);
+			for (successor, _) in successors.iter() {
+				if let Successor::State(next) = successor {
+					if !states.contains_key(next) {
+						worklist.push(*next);
+					}
+				}
+			}
+			states.insert(state, successors);
+			order.push(state);
+		}
+		
+		// Count predecessors to find the states that start blocks
+		let mut predecessors: HashMap<FungeCfgState, u32> = HashMap::new();
+		for successors in states.values() {
+			let is_jump = successors.len() == 1;
+			for (successor, _) in successors.iter() {
+				if let Successor::State(next) = successor {
+					// Targets of branches always start a block
+					*predecessors.entry(*next).or_insert(0) += if is_jump {1} else {2};
+				}
+			}
+		}
+		let is_leader = |state: &FungeCfgState| *state == start || predecessors.get(state).cloned().unwrap_or(0) != 1;
+		
+		// Give every leader a block index, the start state first
+		let mut block_indices: HashMap<FungeCfgState, usize> = HashMap::new();
+		let mut leaders = Vec::new();
+		for state in order.iter() {
+			if is_leader(state) {
+				block_indices.insert(*state, leaders.len());
+				leaders.push(*state);
+			}
+		}
+		
+		// Follow each leader until the next leader or branch
+		let mut blocks = Vec::with_capacity(leaders.len());
+		for leader in leaders.iter() {
+			let mut instructions = Vec::new();
+			let mut state = *leader;
+			let edges = loop {
+				instructions.push((state.ip, funge_space.read_cell(&state.ip)));
+				
+				let successors = &states[&state];
+				match successors.as_slice() {
+					[(Successor::State(next), _)] if !is_leader(next) => state = *next,
+					_ => break successors.iter().map(|(successor, label)| FungeCfgEdge {
+						target: match successor {
+							Successor::State(next) => FungeCfgTarget::Block(block_indices[next]),
+							Successor::Stop => FungeCfgTarget::Stop,
+							Successor::Unknown => FungeCfgTarget::Unknown,
+						},
+						label,
+					}).collect::<Vec<_>>(),
+				}
+			};
+			blocks.push(FungeCfgBlock {
+				entry: *leader,
+				instructions,
+				edges,
+			});
+		}
+		
+		return FungeCfg::from_blocks(blocks, trail.into_inner(), dims);
+	}
+	
+	fn from_blocks(blocks: Vec<FungeCfgBlock>, trail: Trail, dims: u32) -> Self {
+		return FungeCfg {
+			blocks,
+			visited_cells: trail.cells,
+			unterminated_strings: trail.unterminated_strings,
+			unterminated_comments: trail.unterminated_comments,
+			dims,
+		};
+	}
+	
+	/// Moves from the given position to the next instruction, skipping spaces and comments.
+	fn settle<N, A>(funge_space: &CfgSpace<N, A>, trail: &RefCell<Trail>, ip: InstructionPointer, delta: InstructionDelta) -> Successor
+		where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
+		let mut trail = trail.borrow_mut();
+		let mut pos = ip;
+		let mut skipped = 0;
+		loop {
+			trail.cells.insert(pos);
+			match funge_space.read_cell(&pos) {
+				/* space */ 32 => pos = funge_space.next_ip(&pos, &delta),
+				/* ; */ 59 if funge_space.has_comments() => {
+					let opening = pos;
+					while {
+						pos = funge_space.next_ip(&pos, &delta);
+						trail.cells.insert(pos);
+						skipped += 1;
+						(funge_space.read_cell(&pos) != 59) && (skipped < MAX_SKIPPED_CELLS)
+					} {}
+					if pos == opening {
+						trail.unterminated_comments.insert(opening);
+					}
+					pos = funge_space.next_ip(&pos, &delta);
+				}
+				_ => return Successor::State(FungeCfgState {ip: pos, delta, jump_count: None}),
+			}
+			
+			// An ip can get stuck in an empty line that never crosses the bounds
+			skipped += 1;
+			if skipped >= MAX_SKIPPED_CELLS {
+				return Successor::Unknown;
+			}
+		}
+	}
+	
+	/// Decodes the instruction the ip executes in the given state and returns all possible next states.
+	fn decode_state<N, A>(funge_space: &CfgSpace<N, A>, trail: &RefCell<Trail>, dims: u32, state: &FungeCfgState) -> Vec<(Successor, &'static str)>
+		where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
+		let move_from = |ip: &InstructionPointer, delta: InstructionDelta| Self::settle(funge_space, trail, funge_space.next_ip(ip, &delta), delta);
+		let go = |x: i32, y: i32, z: i32| move_from(&state.ip, InstructionDelta::new_xyz(x, y, z));
+		// Only moves that are actually taken may mark the cells they pass over as visited
+		let forward = || move_from(&state.ip, state.delta);
+		let reflect = || {
+			let mut delta = state.delta;
+			insts::_reflect_delta(&mut delta);
+			move_from(&state.ip, delta)
+		};
+		let rotate = |clockwise: bool| {
+			let mut delta = state.delta;
+			if clockwise {
+				insts::_rotate_delta_clockwise_90(&mut delta);
+			}
+			else {
+				insts::_rotate_delta_counterclockwise_90(&mut delta);
+			}
+			move_from(&state.ip, delta)
+		};
+		
+		// Remember constants that are pushed right before a `j`
+		let push_constant = |successor: Successor, value: i32| {
+			return match successor {
+				Successor::State(next) if funge_space.read_cell(&next.ip) == 106 => Successor::State(FungeCfgState {
+					jump_count: Some(value),
+					..next
+				}),
+				other => other,
+			};
+		};
+		
+		let instruction = funge_space.read_cell(&state.ip);
+		if !funge_space.dialect.supports_instruction(instruction) {
+			return vec![(reflect(), "")];
+		}
+		
+		return match instruction {
+			/* > */ 62 => vec![(go(1, 0, 0), "")],
+			/* < */ 60 => vec![(go(-1, 0, 0), "")],
+			/* ^ */ 94 if dims >= 2 => vec![(go(0, -1, 0), "")],
+			/* v */ 118 if dims >= 2 => vec![(go(0, 1, 0), "")],
+			/* h */ 104 if dims == 3 => vec![(go(0, 0, 1), "")],
+			/* l */ 108 if dims == 3 => vec![(go(0, 0, -1), "")],
+			/* [ */ 91 if dims >= 2 => vec![(rotate(false), "")],
+			/* ] */ 93 if dims >= 2 => vec![(rotate(true), "")],
+			/* r */ 114 => vec![(reflect(), "")],
+			/* _ */ 95 => vec![(go(1, 0, 0), "== 0"), (go(-1, 0, 0), "!= 0")],
+			/* | */ 124 if dims >= 2 => vec![(go(0, 1, 0), "== 0"), (go(0, -1, 0), "!= 0")],
+			/* m */ 109 if dims == 3 => vec![(go(0, 0, -1), "== 0"), (go(0, 0, 1), "!= 0")],
+			/* w */ 119 if dims >= 2 => vec![(rotate(false), "a < b"), (rotate(true), "a > b"), (forward(), "a == b")],
+			/* ? */ 63 => {
+				let mut successors = vec![(go(1, 0, 0), "?"), (go(-1, 0, 0), "?")];
+				if dims >= 2 {
+					successors.extend([(go(0, 1, 0), "?"), (go(0, -1, 0), "?")]);
+				}
+				if dims == 3 {
+					successors.extend([(go(0, 0, 1), "?"), (go(0, 0, -1), "?")]);
+				}
+				successors
+			}
+			/* # */ 35 => {
+				let pos = funge_space.next_ip(&state.ip, &state.delta);
+				trail.borrow_mut().cells.insert(pos);
+				vec![(move_from(&pos, state.delta), "")]
+			}
+			/* ' */ 39 => {
+				let pos = funge_space.next_ip(&state.ip, &state.delta);
+				trail.borrow_mut().cells.insert(pos);
+				vec![(push_constant(move_from(&pos, state.delta), funge_space.read_cell(&pos)), "")]
+			}
+			/* s */ 115 => {
+				let pos = funge_space.next_ip(&state.ip, &state.delta);
+				trail.borrow_mut().cells.insert(pos);
+				vec![(move_from(&pos, state.delta), "")]
+			}
+			/* j */ 106 => match state.jump_count {
+				Some(count) => {
+					// Jump like the interpreter, which moves the ip by count * delta before the regular move
+					let d = state.delta;
+					let mut ip = state.ip;
+					ip.set_x(ip.x().wrapping_add(d.x().wrapping_mul(count)));
+					ip.set_y(ip.y().wrapping_add(d.y().wrapping_mul(count)));
+					ip.set_z(ip.z().wrapping_add(d.z().wrapping_mul(count)));
+					vec![(move_from(&ip, d), "")]
+				}
+				None => vec![(Successor::Unknown, "j")],
+			},
+			/* k */ 107 => {
+				// Iterating an instruction that doesn't move the ip only changes the stack,
+				// after which the ip moves on to that instruction, or skips it for 0k
+				match forward() {
+					Successor::State(next) if Self::is_stack_instruction(funge_space.read_cell(&next.ip)) => {
+						vec![(forward(), ""), (move_from(&next.ip, state.delta), "0k")]
+					}
+					_ => vec![(Successor::Unknown, "k")],
+				}
+			}
+			/* x */ 120 => vec![(Successor::Unknown, "x")],
+			/* " */ 34 => {
+				// Skip the string, which always ends because the ip wraps back to the opening quote
+				let mut pos = funge_space.next_ip(&state.ip, &state.delta);
+				let mut skipped = 0;
+				{
+					let mut trail = trail.borrow_mut();
+					while funge_space.read_cell(&pos) != 34 && skipped < MAX_SKIPPED_CELLS {
+						trail.cells.insert(pos);
+						pos = funge_space.next_ip(&pos, &state.delta);
+						skipped += 1;
+					}
+					trail.cells.insert(pos);
+					if pos == state.ip {
+						trail.unterminated_strings.insert(pos);
+					}
+				}
+				vec![(move_from(&pos, state.delta), "")]
+			}
+			/* @ */ 64 | /* q */ 113 => vec![(Successor::Stop, "")],
+			/* t */ 116 => vec![(forward(), "parent"), (reflect(), "child")],
+			
+			// Instructions that reflect if they fail
+			/* & */ 38 | /* ( */ 40 | /* ) */ 41 | /* i */ 105 | /* u */ 117 | /* } */ 125 | /* ~ */ 126 | /* A...Z */ 65..=90 => {
+				vec![(forward(), ""), (reflect(), "reflects")]
+			}
+			
+			/* 0...9 */ n @ 48..=57 => vec![(push_constant(forward(), n - 48), "")],
+			/* a...f */ n @ 97..=102 => vec![(push_constant(forward(), n - 97 + 10), "")],
+			
+			// Instructions that don't change the control flow
+			/* ! */ 33 | /* $ */ 36 | /* % */ 37 | /* * */ 42 | /* + */ 43 | /* , */ 44 | /* - */ 45 | /* . */ 46 | /* / */ 47
+			| /* : */ 58 | /* \ */ 92 | /* ` */ 96 | /* g */ 103 | /* n */ 110 | /* p */ 112 | /* y */ 121 | /* z */ 122 | /* { */ 123 => {
+				vec![(forward(), "")]
+			}
+			
+			// Everything else reflects, like in the interpreter
+			_ => vec![(reflect(), "")],
+		};
+	}
+	
+	/// Checks if the instruction neither moves the ip nor changes its delta.
+	fn is_stack_instruction(instruction: i32) -> bool {
+		return match instruction {
+			/* 0...9 */ 48..=57 | /* a...f */ 97..=102 => true,
+			/* ! */ 33 | /* $ */ 36 | /* % */ 37 | /* * */ 42 | /* + */ 43 | /* , */ 44 | /* - */ 45 | /* . */ 46 | /* / */ 47
+			| /* : */ 58 | /* \ */ 92 | /* ` */ 96 | /* g */ 103 | /* n */ 110 | /* p */ 112 | /* y */ 121 | /* z */ 122 => true,
+			_ => false,
+		};
+	}
+	
+	/// Formats a position with as many coordinates as the program has dimensions.
+	fn format_position(&self, address: &FungeAddress) -> String {
+		return match self.dims {
+			1 => format!("({})", address.x()),
+			2 => format!("({}, {})", address.x(), address.y()),
+			_ => format!("({}, {}, {})", address.x(), address.y(), address.z()),
+		};
+	}
+	
+	/// Returns the entry position and direction of a block, like `(3, 0) east`.
+	fn describe_entry(&self, block: &FungeCfgBlock) -> String {
+		let d = block.entry.delta;
+		let direction = match (d.x(), d.y(), d.z()) {
+			(1, 0, 0) => String::from("east"),
+			(-1, 0, 0) => String::from("west"),
+			(0, 1, 0) => String::from("south"),
+			(0, -1, 0) => String::from("north"),
+			(0, 0, 1) => String::from("high"),
+			(0, 0, -1) => String::from("low"),
+			_ => format!("delta {}", self.format_position(&d)),
+		};
+		return format!("{} {}", self.format_position(&block.entry.ip), direction);
+	}
+	
+	fn instruction_text(block: &FungeCfgBlock) -> String {
+		return block.instructions.iter()
+			.map(|(_, value)| char::from_u32(*value as u32).filter(|c| !c.is_control()).unwrap_or('?'))
+			.collect();
+	}
+	
+	/// Formats the graph as a plain text list of blocks and their edges.
+	pub fn format_text(&self) -> String {
+		let mut text = String::new();
+		for (index, block) in self.blocks.iter().enumerate() {
+			let _ = writeln!(text, "block {} at {}: {}", index, self.describe_entry(block), Self::instruction_text(block));
+			for edge in block.edges.iter() {
+				let target = match edge.target {
+					FungeCfgTarget::Block(target) => format!("block {}", target),
+					FungeCfgTarget::Stop => String::from("stop"),
+					FungeCfgTarget::Unknown => String::from("unknown"),
+				};
+				if edge.label.is_empty() {
+					let _ = writeln!(text, "\t-> {}", target);
+				}
+				else {
+					let _ = writeln!(text, "\t-> {} if {}", target, edge.label);
+				}
+			}
+		}
+		return text;
+	}
+	
+	/// Formats the graph in the Graphviz DOT language.
+	pub fn format_dot(&self, name: &str) -> String {
+		let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+		
+		let mut dot = String::new();
+		let _ = writeln!(dot, "digraph \"{}\" {{", escape(name));
+		let _ = writeln!(dot, "\tnode [shape=box, fontname=\"monospace\"];");
+		
+		let mut uses_stop = false;
+		let mut uses_unknown = false;
+		for (index, block) in self.blocks.iter().enumerate() {
+			let _ = writeln!(dot, "\tb{} [label=\"{}\\l{}\\l\"];", index, escape(&self.describe_entry(block)), escape(&Self::instruction_text(block)));
+			for edge in block.edges.iter() {
+				let target = match edge.target {
+					FungeCfgTarget::Block(target) => format!("b{}", target),
+					FungeCfgTarget::Stop => {
+						uses_stop = true;
+						String::from("stop")
+					}
+					FungeCfgTarget::Unknown => {
+						uses_unknown = true;
+						String::from("unknown")
+					}
+				};
+				if edge.label.is_empty() {
+					let _ = writeln!(dot, "\tb{} -> {};", index, target);
+				}
+				else {
+					let _ = writeln!(dot, "\tb{} -> {} [label=\"{}\"];", index, target, escape(edge.label));
+				}
+			}
+		}
+		
+		if uses_stop {
+			let _ = writeln!(dot, "\tstop [shape=doublecircle];");
+		}
+		if uses_unknown {
+			let _ = writeln!(dot, "\tunknown [shape=diamond, label=\"?\"];");
+		}
+		let _ = writeln!(dot, "}}");
+		return dot;
+	}
+}
@@ -0,0 +1,7 @@
+mod b93_cfg;
+mod funge_cfg;
+mod rust_emitter;
+
+pub use b93_cfg::*;
+pub use funge_cfg::*;
+pub use rust_emitter::*;
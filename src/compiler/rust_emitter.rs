@@ -0,0 +1,318 @@
+use std::fmt::Write;
+use crate::compiler::{B93Program, B93Cfg, CfgOp, CfgExit, B93_WIDTH, B93_HEIGHT};
+
+/// Instruction semantics shared with the interpreter, copied into the generated programs
+const SEMANTICS: &str = include_str!("../interpreter/instruction/semantics.rs");
+
+/// Runtime of the generated programs. Everything that isn't shared through `SEMANTICS` is simple
+/// enough to restate: popping an empty stack yields 0, arithmetic wraps around, `g` outside of
+/// the program reads a space and input at the end of the file reflects.
+const RUNTIME: &str = r#"use std::collections::HashMap;
+use std::io::{self, Write};
+
+const WIDTH: i32 = 80;
+const HEIGHT: i32 = 25;
+
+struct Runtime {
+	stack: Vec<i32>,
+	grid: Vec<i32>,
+	/// Cells written outside of the 80x25 grid
+	far_cells: HashMap<(i32, i32), i32>,
+	rng: Pcg32,
+	stdin: io::StdinLock<'static>,
+	stdout: io::BufWriter<io::Stdout>,
+}
+
+impl Runtime {
+	fn new() -> Self {
+		Runtime {
+			stack: Vec::with_capacity(1024),
+			grid: CODE.to_vec(),
+			far_cells: HashMap::new(),
+			rng: Pcg32::new(RNG_SEED),
+			stdin: io::stdin().lock(),
+			stdout: io::BufWriter::new(io::stdout()),
+		}
+	}
+	
+	#[inline(always)]
+	fn push(&mut self, value: i32) {
+		self.stack.push(value);
+	}
+	
+	#[inline(always)]
+	fn pop(&mut self) -> i32 {
+		return self.stack.pop().unwrap_or(0);
+	}
+	
+	#[inline(always)]
+	fn add(&mut self) {
+		let (a, b) = (self.pop(), self.pop());
+		self.push(b.wrapping_add(a));
+	}
+	
+	#[inline(always)]
+	fn subtract(&mut self) {
+		let (a, b) = (self.pop(), self.pop());
+		self.push(b.wrapping_sub(a));
+	}
+	
+	#[inline(always)]
+	fn multiply(&mut self) {
+		let (a, b) = (self.pop(), self.pop());
+		self.push(b.wrapping_mul(a));
+	}
+	
+	#[inline(always)]
+	fn divide(&mut self) {
+		let (a, b) = (self.pop(), self.pop());
+		self.push(b.div_or_zero(a));
+	}
+	
+	#[inline(always)]
+	fn remainder(&mut self) {
+		let (a, b) = (self.pop(), self.pop());
+		self.push(b.rem_or_zero(a));
+	}
+	
+	#[inline(always)]
+	fn not(&mut self) {
+		let a = self.pop();
+		self.push((a == 0) as i32);
+	}
+	
+	#[inline(always)]
+	fn greater(&mut self) {
+		let (a, b) = (self.pop(), self.pop());
+		self.push((b > a) as i32);
+	}
+	
+	#[inline(always)]
+	fn duplicate(&mut self) {
+		let a = self.pop();
+		self.push(a);
+		self.push(a);
+	}
+	
+	#[inline(always)]
+	fn swap(&mut self) {
+		let (a, b) = (self.pop(), self.pop());
+		self.push(a);
+		self.push(b);
+	}
+	
+	fn output_integer(&mut self) {
+		let a = self.pop();
+		let _ = write!(self.stdout, "{} ", a);
+	}
+	
+	fn output_char(&mut self) {
+		let a = self.pop();
+		let _ = write_utf8_char(&mut self.stdout, a);
+	}
+	
+	fn get(&mut self) {
+		let (y, x) = (self.pop(), self.pop());
+		let value = if (0..WIDTH).contains(&x) && (0..HEIGHT).contains(&y) {
+			self.grid[(y * WIDTH + x) as usize]
+		}
+		else {
+			*self.far_cells.get(&(x, y)).unwrap_or(&32)
+		};
+		self.push(value);
+	}
+	
+	fn put(&mut self) {
+		let (y, x, value) = (self.pop(), self.pop(), self.pop());
+		if (0..WIDTH).contains(&x) && (0..HEIGHT).contains(&y) {
+			self.grid[(y * WIDTH + x) as usize] = value;
+		}
+		else {
+			self.far_cells.insert((x, y), value);
+		}
+	}
+	
+	/// Returns the index of the direction `?` goes to
+	fn random_direction(&mut self) -> usize {
+		return self.rng.below(RANDOM_DIRECTIONS_2D.len() as u32) as usize;
+	}
+	
+	/// Reads a decimal number. Returns false if no number could be read.
+	fn input_integer(&mut self) -> bool {
+		let _ = self.stdout.flush();
+		let value = read_integer(&mut self.stdin, |v: &i32, digit| v.checked_mul(10)?.checked_add(digit as i32));
+		if let Some(v) = value {
+			self.push(v);
+		}
+		return value.is_some();
+	}
+	
+	/// Reads an UTF-8 encoded character. Returns false at the end of the input.
+	fn input_char(&mut self) -> bool {
+		let _ = self.stdout.flush();
+		if let Ok(Some(c)) = read_utf8_char(&mut self.stdin) {
+			self.push(c as i32);
+			return true;
+		}
+		return false;
+	}
+	
+	fn flush(&mut self) {
+		let _ = self.stdout.flush();
+	}
+}
+"#;
+
+/// Main function of programs that modify their own code, which are interpreted
+const INTERPRETER: &str = r#"
+fn main() {
+	let mut rt = Runtime::new();
+	let (mut x, mut y, mut dx, mut dy) = (0i32, 0i32, 1i32, 0i32);
+	let mut string_mode = false;
+	
+	loop {
+		let instruction = rt.grid[(y * WIDTH + x) as usize];
+		if string_mode {
+			if instruction == 34 {
+				string_mode = false;
+			}
+			else {
+				rt.push(instruction);
+			}
+		}
+		else {
+			match instruction {
+				32 => {}
+				34 => string_mode = true,
+				n @ 48..=57 => rt.push(n - 48),
+				43 => rt.add(),
+				45 => rt.subtract(),
+				42 => rt.multiply(),
+				47 => rt.divide(),
+				37 => rt.remainder(),
+				33 => rt.not(),
+				96 => rt.greater(),
+				58 => rt.duplicate(),
+				92 => rt.swap(),
+				36 => {
+					rt.pop();
+				}
+				46 => rt.output_integer(),
+				44 => rt.output_char(),
+				103 => rt.get(),
+				112 => rt.put(),
+				62 => {dx = 1; dy = 0;}
+				60 => {dx = -1; dy = 0;}
+				118 => {dx = 0; dy = 1;}
+				94 => {dx = 0; dy = -1;}
+				95 => {dx = if rt.pop() == 0 {1} else {-1}; dy = 0;}
+				124 => {dx = 0; dy = if rt.pop() == 0 {1} else {-1};}
+				63 => {
+					let dir = RANDOM_DIRECTIONS_2D[rt.random_direction()];
+					dx = dir.0;
+					dy = dir.1;
+				}
+				35 => {
+					x = (x + dx).rem_euclid(WIDTH);
+					y = (y + dy).rem_euclid(HEIGHT);
+				}
+				38 => if !rt.input_integer() {dx = -dx; dy = -dy;},
+				126 => if !rt.input_char() {dx = -dx; dy = -dy;},
+				64 => break,
+				_ => {dx = -dx; dy = -dy;}
+			}
+		}
+		
+		x = (x + dx).rem_euclid(WIDTH);
+		y = (y + dy).rem_euclid(HEIGHT);
+	}
+	rt.flush();
+}
+"#;
+
+/// Translates a Befunge-93 program into the source code of a standalone Rust program.
+///
+/// Programs that never modify their own code are translated block by block from their
+/// control flow graph. Self-modifying programs are embedded into a small interpreter instead.
+/// `?` takes the same directions as in the interpreter with the given rng seed.
+pub fn emit_rust_program(program: &B93Program, cfg: &B93Cfg, source_name: &str, rng_seed: u64) -> String {
+	let mut out = String::new();
+	let _ = writeln!(out, "// Translated from \"{}\" by rsfunge {}", source_name, env!("CARGO_PKG_VERSION"));
+	let _ = writeln!(out, "// Build with: rustc -O <this file>");
+	out.push_str("#![allow(dead_code)]\n\n");
+	out.push_str("mod semantics {\n");
+	out.push_str(SEMANTICS);
+	out.push_str("}\n\nuse semantics::*;\n\n");
+	out.push_str(RUNTIME);
+	out.push('\n');
+	let _ = writeln!(out, "const RNG_SEED: u64 = {};\n", rng_seed);
+	emit_code(&mut out, program);
+	
+	if cfg.is_static() {
+		emit_compiled_main(&mut out, cfg);
+	}
+	else {
+		out.push_str(INTERPRETER);
+	}
+	return out;
+}
+
+/// Emits the initial contents of the grid
+fn emit_code(out: &mut String, program: &B93Program) {
+	let _ = writeln!(out, "static CODE: [i32; {}] = [", B93_WIDTH * B93_HEIGHT);
+	for row in program.cells.chunks(B93_WIDTH) {
+		let values: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+		let _ = writeln!(out, "\t{},", values.join(", "));
+	}
+	out.push_str("];\n");
+}
+
+/// Emits a main function that jumps between the blocks of the control flow graph
+fn emit_compiled_main(out: &mut String, cfg: &B93Cfg) {
+	out.push_str("\nfn main() {\n");
+	out.push_str("\tlet mut rt = Runtime::new();\n");
+	out.push_str("\tlet mut block: u32 = 0;\n");
+	out.push_str("\tloop {\n");
+	out.push_str("\t\tmatch block {\n");
+	
+	for (index, block) in cfg.blocks.iter().enumerate() {
+		let _ = writeln!(out, "\t\t\t{} => {{ // ({}, {}) {:?}", index, block.entry.x, block.entry.y, block.entry.dir);
+		for op in block.ops.iter() {
+			let statement = match op {
+				CfgOp::Push(n) => format!("rt.push({});", n),
+				CfgOp::Add => String::from("rt.add();"),
+				CfgOp::Subtract => String::from("rt.subtract();"),
+				CfgOp::Multiply => String::from("rt.multiply();"),
+				CfgOp::Divide => String::from("rt.divide();"),
+				CfgOp::Remainder => String::from("rt.remainder();"),
+				CfgOp::Not => String::from("rt.not();"),
+				CfgOp::Greater => String::from("rt.greater();"),
+				CfgOp::Duplicate => String::from("rt.duplicate();"),
+				CfgOp::Swap => String::from("rt.swap();"),
+				CfgOp::Pop => String::from("rt.pop();"),
+				CfgOp::OutputInteger => String::from("rt.output_integer();"),
+				CfgOp::OutputChar => String::from("rt.output_char();"),
+				CfgOp::Get => String::from("rt.get();"),
+				CfgOp::Put => String::from("rt.put();"),
+			};
+			let _ = writeln!(out, "\t\t\t\t{}", statement);
+		}
+		
+		let exit = match &block.exit {
+			CfgExit::Jump(next) => format!("block = {};", next),
+			CfgExit::Branch(zero, non_zero) => format!("block = if rt.pop() == 0 {{{}}} else {{{}}};", zero, non_zero),
+			CfgExit::Random(next) => format!("block = [{}, {}, {}, {}][rt.random_direction()];", next[0], next[1], next[2], next[3]),
+			CfgExit::InputInteger(ok, eof) => format!("block = if rt.input_integer() {{{}}} else {{{}}};", ok, eof),
+			CfgExit::InputChar(ok, eof) => format!("block = if rt.input_char() {{{}}} else {{{}}};", ok, eof),
+			CfgExit::Stop => String::from("break;"),
+		};
+		let _ = writeln!(out, "\t\t\t\t{}", exit);
+		out.push_str("\t\t\t}\n");
+	}
+	
+	out.push_str("\t\t\t_ => unreachable!(),\n");
+	out.push_str("\t\t}\n");
+	out.push_str("\t}\n");
+	out.push_str("\trt.flush();\n");
+	out.push_str("}\n");
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::ops::BitOr;
+use std::path::Path;
+use crate::interpreter::FungeAddress;
+
+/// Header line of coverage data files
+const COVERAGE_DATA_HEADER: &str = "rsfunge-coverage 2";
+
+/// A source file the coverage was recorded for, identified by its path and a hash of its contents.
+/// Coverage data is only merged if it was recorded for the same source files.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct CoverageSource {
+	pub path: String,
+	pub hash: u64,
+}
+
+impl CoverageSource {
+	pub fn new(path: String, contents: &[u8]) -> Self {
+		// 64 bit FNV-1a, which stays the same across Rust versions unlike the std hashers
+		let mut hash: u64 = 0xcbf29ce484222325;
+		for byte in contents {
+			hash ^= *byte as u64;
+			hash = hash.wrapping_mul(0x100000001b3);
+		}
+		return CoverageSource {path, hash};
+	}
+}
+
+/// A set of flags describing how a funge space cell was accessed.
+#[derive(Copy, Clone, Eq, PartialEq, Default)]
+pub struct CoverageFlags(u8);
+
+impl CoverageFlags {
+	pub const NONE: CoverageFlags = CoverageFlags(0);
+	/// The cell was executed as an instruction
+	pub const EXECUTED: CoverageFlags = CoverageFlags(1);
+	/// The cell was read as data (by `g` or `'`)
+	pub const READ: CoverageFlags = CoverageFlags(2);
+	/// The cell was overwritten (by `p` or `s`)
+	pub const WRITTEN: CoverageFlags = CoverageFlags(4);
+	
+	#[inline(always)]
+	pub fn contains(self, other: CoverageFlags) -> bool {
+		(self.0 & other.0) == other.0
+	}
+	
+	#[inline(always)]
+	pub fn bits(self) -> u8 {
+		self.0
+	}
+	
+	#[inline(always)]
+	pub fn from_bits(bits: u8) -> Self {
+		CoverageFlags(bits & 0b111)
+	}
+}
+
+impl BitOr for CoverageFlags {
+	type Output = CoverageFlags;
+	
+	fn bitor(self, rhs: Self) -> Self::Output {
+		CoverageFlags(self.0 | rhs.0)
+	}
+}
+
+/// Records which funge space cells were executed, read and written
+/// during one or more runs.
+pub struct CoverageMap {
+	cells: HashMap<FungeAddress, CoverageFlags>,
+}
+
+impl CoverageMap {
+	pub fn new() -> Self {
+		CoverageMap {
+			cells: HashMap::with_capacity(1024),
+		}
+	}
+	
+	/// Adds the given flags to the cell at the given address.
+	#[inline]
+	pub fn record(&mut self, address: &FungeAddress, flags: CoverageFlags) {
+		let entry = self.cells.entry(*address).or_insert(CoverageFlags::NONE);
+		*entry = *entry | flags;
+	}
+	
+	/// Returns the flags recorded for the cell at the given address.
+	#[inline]
+	pub fn get(&self, address: &FungeAddress) -> CoverageFlags {
+		return self.cells.get(address).cloned().unwrap_or(CoverageFlags::NONE);
+	}
+	
+	/// Merges the given coverage map into this one, so that each cell
+	/// has the union of the flags recorded in both maps.
+	pub fn merge(&mut self, other: &CoverageMap) {
+		for (address, flags) in other.cells.iter() {
+			self.record(address, *flags);
+		}
+	}
+	
+	/// Loads coverage data previously written by `write_data_file`,
+	/// returns the coverage and the source files it was recorded for.
+	pub fn read_data_file(path: &Path) -> io::Result<(CoverageMap, Vec<CoverageSource>)> {
+		let reader = BufReader::new(File::open(path)?);
+		let mut map = CoverageMap::new();
+		let mut sources = Vec::new();
+		
+		let invalid_data = |line_num: usize| {
+			io::Error::new(io::ErrorKind::InvalidData, format!("Malformed coverage data in line {}", line_num + 1))
+		};
+		
+		for (line_num, line) in reader.lines().enumerate() {
+			let line = line?;
+			
+			// Check header
+			if line_num == 0 {
+				if line.trim() != COVERAGE_DATA_HEADER {
+					return Err(io::Error::new(io::ErrorKind::InvalidData, "Not an rsfunge coverage data file"));
+				}
+				continue;
+			}
+			
+			// Skip empty lines and comments
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			
+			// Parse "source hash path"
+			if let Some(source) = line.strip_prefix("source ") {
+				let (hash, source_path) = source.split_once(' ').ok_or_else(|| invalid_data(line_num))?;
+				let hash = u64::from_str_radix(hash, 16).map_err(|_| invalid_data(line_num))?;
+				sources.push(CoverageSource {path: source_path.to_string(), hash});
+				continue;
+			}
+			
+			// Parse "x y z flags"
+			let mut values = [0i32; 4];
+			let mut parts = line.split_whitespace();
+			for value in values.iter_mut() {
+				let part = parts.next().ok_or_else(|| invalid_data(line_num))?;
+				*value = part.parse::<i32>().map_err(|_| invalid_data(line_num))?;
+			}
+			
+			let address = FungeAddress::new_xyz(values[0], values[1], values[2]);
+			map.record(&address, CoverageFlags::from_bits(values[3] as u8));
+		}
+		return Ok((map, sources));
+	}
+	
+	/// Writes the coverage data to the given file so that it can be
+	/// merged with the coverage of later runs of the same source files.
+	pub fn write_data_file(&self, path: &Path, sources: &[CoverageSource], comment: &str) -> io::Result<()> {
+		let mut writer = BufWriter::new(File::create(path)?);
+		
+		writeln!(writer, "{}", COVERAGE_DATA_HEADER)?;
+		for line in comment.lines() {
+			writeln!(writer, "# {}", line)?;
+		}
+		for source in sources {
+			writeln!(writer, "source {:016x} {}", source.hash, source.path)?;
+		}
+		
+		// Sort cells so the file is stable across runs
+		let mut cells: Vec<(&FungeAddress, &CoverageFlags)> = self.cells.iter().collect();
+		cells.sort_by_key(|(a, _)| (a.z(), a.y(), a.x()));
+		
+		for (address, flags) in cells {
+			writeln!(writer, "{} {} {} {}", address.x(), address.y(), address.z(), flags.bits())?;
+		}
+		return writer.flush();
+	}
+}
+
+impl Default for CoverageMap {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
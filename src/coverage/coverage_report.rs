@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use crate::coverage::{CoverageMap, CoverageFlags};
+use crate::interpreter::FungeAddress;
+use crate::io::{CodeBuffer, Codepoint, SourceMap};
+
+/// A code buffer that was loaded into funge space.
+struct CoverageFile<'c> {
+	name: String,
+	code: &'c CodeBuffer,
+	/// The position of the top left corner of the code
+	origin: FungeAddress,
+}
+
+/// Summary of the coverage of the loaded code buffers.
+pub struct CoverageReport<'c> {
+	files: Vec<CoverageFile<'c>>,
+	/// The index of the file each non-space cell was loaded from,
+	/// a later file owns the cells it overwrote
+	owners: HashMap<FungeAddress, usize>,
+	coverage: &'c CoverageMap,
+	/// Used to print the file positions of unexecuted cells
+	source_map: Option<&'c SourceMap>,
+	/// Seed of the run that wrote the report, so that it can be replayed
+	rng_seed: Option<u64>,
+	
+	pub total_cells: u32,
+	pub executed_cells: u32,
+	pub read_cells: u32,
+	pub written_cells: u32,
+	
+	/// All non-space cells of the code that were never executed
+	pub unexecuted: Vec<(FungeAddress, Codepoint)>,
+}
+
+impl<'c> CoverageReport<'c> {
+	/// Builds a coverage report over the non-space cells of the given code buffer,
+	/// which was loaded from the named file with its top left corner at the given origin.
+	pub fn new(name: &str, code: &'c CodeBuffer, origin: FungeAddress, coverage: &'c CoverageMap) -> Self {
+		let mut report = CoverageReport {
+			files: Vec::new(),
+			owners: HashMap::new(),
+			coverage,
+			source_map: None,
+			rng_seed: None,
+			total_cells: 0,
+			executed_cells: 0,
+			read_cells: 0,
+			written_cells: 0,
+			unexecuted: Vec::new(),
+		};
+		report.add_file(name, code, origin);
+		return report;
+	}
+	
+	/// Adds code that was loaded on top of the previous code, like a file given with `--load`.
+	/// The non-space cells it overwrote are only counted for the overlay.
+	pub fn with_overlay(mut self, name: &str, code: &'c CodeBuffer, origin: FungeAddress) -> Self {
+		self.add_file(name, code, origin);
+		return self;
+	}
+	
+	fn add_file(&mut self, name: &str, code: &'c CodeBuffer, origin: FungeAddress) {
+		let index = self.files.len();
+		self.files.push(CoverageFile {name: name.to_string(), code, origin});
+		for (offset, value) in code.cells() {
+			if value != ' ' as Codepoint {
+				self.owners.insert(Self::absolute_address(&origin, &offset), index);
+			}
+		}
+		self.count_cells();
+	}
+	
+	/// Counts the non-space cells of all files by their flags.
+	fn count_cells(&mut self) {
+		self.total_cells = 0;
+		self.executed_cells = 0;
+		self.read_cells = 0;
+		self.written_cells = 0;
+		self.unexecuted.clear();
+		
+		for (index, file) in self.files.iter().enumerate() {
+			for (offset, value) in file.code.cells() {
+				// Spaces are never executed, so don't count them
+				if value == ' ' as Codepoint {
+					continue;
+				}
+				
+				// Skip cells overwritten by a later file
+				let address = Self::absolute_address(&file.origin, &offset);
+				if self.owners.get(&address) != Some(&index) {
+					continue;
+				}
+				let flags = self.coverage.get(&address);
+				
+				self.total_cells += 1;
+				if flags.contains(CoverageFlags::EXECUTED) {
+					self.executed_cells += 1;
+				}
+				else {
+					self.unexecuted.push((address, value));
+				}
+				if flags.contains(CoverageFlags::READ) {
+					self.read_cells += 1;
+				}
+				if flags.contains(CoverageFlags::WRITTEN) {
+					self.written_cells += 1;
+				}
+			}
+		}
+	}
+	
+	/// Uses the given source map to print file positions of unexecuted cells.
+	pub fn with_source_map(mut self, source_map: &'c SourceMap) -> Self {
+		self.source_map = Some(source_map);
+		return self;
+	}
+	
+	/// Prints the rng seed of the run that wrote the report.
+	pub fn with_rng_seed(mut self, rng_seed: u64) -> Self {
+		self.rng_seed = Some(rng_seed);
+		return self;
+	}
+	
+	/// Returns the percentage of executed non-space cells.
+	pub fn percentage(&self) -> f64 {
+		if self.total_cells == 0 {
+			return 100.0;
+		}
+		return (self.executed_cells as f64 / self.total_cells as f64) * 100.0;
+	}
+	
+	/// Formats the report as plain text with an annotated grid of the code.
+	///
+	/// Below each code line a marker line is printed that contains a `#` for
+	/// executed cells, `p` for cells overwritten by `p`, `g` for cells
+	/// read by `g` and `.` for cells that were never touched.
+	pub fn format_text(&self) -> String {
+		let mut out = String::new();
+		
+		let _ = writeln!(out, "Coverage report for \"{}\"", self.files[0].name);
+		let _ = writeln!(out, "{}", self.format_summary());
+		if let Some(rng_seed) = self.rng_seed {
+			let _ = writeln!(out, "Rng seed of the last run: {}", rng_seed);
+		}
+		let _ = writeln!(out);
+		let _ = writeln!(out, "Legend: # executed, p overwritten by p, g read by g, . never executed");
+		let _ = writeln!(out);
+		
+		for (index, file) in self.files.iter().enumerate() {
+			// Print a header for each overlay
+			if index > 0 {
+				let _ = writeln!(out);
+				let _ = writeln!(out, "     -- \"{}\" at ({}, {}, {}) --", file.name, file.origin.x(), file.origin.y(), file.origin.z());
+			}
+			
+			let positions = file.code.line_positions();
+			for (line, pos) in file.code.lines.iter().zip(positions.iter()) {
+				// Print plane header when a new z plane starts
+				if pos.x() == 0 && pos.y() == 0 && pos.z() != 0 {
+					let _ = writeln!(out, "     -- z = {} --", file.origin.z().wrapping_add(pos.z()));
+				}
+				
+				let source: String = line.data.iter().map(|c| Self::printable_char(*c)).collect();
+				let _ = writeln!(out, "{:>4} | {}", file.origin.y().wrapping_add(pos.y()), source.trim_end());
+				
+				// Make marker line
+				let mut markers = String::with_capacity(line.data.len());
+				for (x, value) in line.data.iter().enumerate() {
+					let address = Self::absolute_address(&file.origin, &FungeAddress::new_xyz(pos.x() + x as i32, pos.y(), pos.z()));
+					markers.push(self.marker_char(*value, self.coverage.get(&address)));
+				}
+				if !markers.trim().is_empty() {
+					let _ = writeln!(out, "     | {}", markers.trim_end());
+				}
+			}
+		}
+		
+		// List unexecuted cells
+		if !self.unexecuted.is_empty() {
+			let _ = writeln!(out);
+			let _ = writeln!(out, "Unexecuted non-space cells:");
+			for (address, value) in self.unexecuted.iter() {
+				let _ = writeln!(out, "  {} '{}'", self.describe_address(address), Self::printable_char(*value));
+			}
+		}
+		return out;
+	}
+	
+	/// Formats the report as a standalone html page.
+	pub fn format_html(&self) -> String {
+		let mut out = String::new();
+		let title = Self::escape_html(&format!("Coverage report for \"{}\"", self.files[0].name));
+		
+		let _ = writeln!(out, "<!DOCTYPE html>");
+		let _ = writeln!(out, "<html><head><meta charset=\"utf-8\"><title>{}</title>", title);
+		let _ = writeln!(out, "<style>");
+		let _ = writeln!(out, "body {{ font-family: sans-serif; }}");
+		let _ = writeln!(out, "pre {{ font-family: monospace; line-height: 1.2; }}");
+		let _ = writeln!(out, ".exec {{ background: #b5f0b5; }}");
+		let _ = writeln!(out, ".write {{ background: #f5d19b; }}");
+		let _ = writeln!(out, ".read {{ background: #b5d3f5; }}");
+		let _ = writeln!(out, ".miss {{ background: #f5b5b5; }}");
+		let _ = writeln!(out, "</style></head><body>");
+		let _ = writeln!(out, "<h1>{}</h1>", title);
+		let _ = writeln!(out, "<p>{}</p>", Self::escape_html(&self.format_summary()));
+		if let Some(rng_seed) = self.rng_seed {
+			let _ = writeln!(out, "<p>Rng seed of the last run: {}</p>", rng_seed);
+		}
+		let _ = writeln!(out, "<p><span class=\"exec\">executed</span> <span class=\"write\">overwritten by p</span> <span class=\"read\">read by g</span> <span class=\"miss\">never executed</span></p>");
+		for (index, file) in self.files.iter().enumerate() {
+			// Print a header for each overlay
+			if index > 0 {
+				let heading = format!("\"{}\" at ({}, {}, {})", file.name, file.origin.x(), file.origin.y(), file.origin.z());
+				let _ = writeln!(out, "<h2>{}</h2>", Self::escape_html(&heading));
+			}
+			let _ = writeln!(out, "<pre>");
+			
+			let positions = file.code.line_positions();
+			for (line, pos) in file.code.lines.iter().zip(positions.iter()) {
+				if pos.x() == 0 && pos.y() == 0 && pos.z() != 0 {
+					let _ = writeln!(out, "<hr>");
+				}
+				
+				for (x, value) in line.data.iter().enumerate() {
+					let address = Self::absolute_address(&file.origin, &FungeAddress::new_xyz(pos.x() + x as i32, pos.y(), pos.z()));
+					let flags = self.coverage.get(&address);
+					let text = Self::escape_html(&Self::printable_char(*value).to_string());
+					
+					let class = match self.marker_char(*value, flags) {
+						'#' => "exec",
+						'p' => "write",
+						'g' => "read",
+						'.' => "miss",
+						_ => {
+							out.push_str(&text);
+							continue;
+						}
+					};
+					let _ = write!(out, "<span class=\"{}\" title=\"({}, {}, {})\">{}</span>", class, address.x(), address.y(), address.z(), text);
+				}
+				out.push('\n');
+			}
+			let _ = writeln!(out, "</pre>");
+		}
+		
+		// List unexecuted cells
+		if !self.unexecuted.is_empty() {
+			let _ = writeln!(out, "<h2>Unexecuted non-space cells</h2>");
+			let _ = writeln!(out, "<ul>");
+			for (address, value) in self.unexecuted.iter() {
+				let text = Self::escape_html(&Self::printable_char(*value).to_string());
+				let _ = writeln!(out, "<li><code>{} '{}'</code></li>", Self::escape_html(&self.describe_address(address)), text);
+			}
+			let _ = writeln!(out, "</ul>");
+		}
+		let _ = writeln!(out, "</body></html>");
+		return out;
+	}
+	
+	fn format_summary(&self) -> String {
+		return format!("Executed {} of {} non-space cells ({:.2}%), {} read by g, {} overwritten by p",
+			self.executed_cells, self.total_cells, self.percentage(), self.read_cells, self.written_cells);
+	}
+	
+	/// Describes an address by its file position if known, followed by its coordinates
+	fn describe_address(&self, address: &FungeAddress) -> String {
+		let coords = format!("({}, {}, {})", address.x(), address.y(), address.z());
+		return match self.source_map.and_then(|m| m.lookup(address)) {
+			Some(location) => format!("{} {}", location, coords),
+			None => coords,
+		};
+	}
+	
+	fn absolute_address(origin: &FungeAddress, offset: &FungeAddress) -> FungeAddress {
+		let mut address = *origin;
+		address.add_wrapping(offset);
+		return address;
+	}
+	
+	fn marker_char(&self, value: Codepoint, flags: CoverageFlags) -> char {
+		if value == ' ' as Codepoint {
+			' '
+		}
+		else if flags.contains(CoverageFlags::EXECUTED) {
+			'#'
+		}
+		else if flags.contains(CoverageFlags::WRITTEN) {
+			'p'
+		}
+		else if flags.contains(CoverageFlags::READ) {
+			'g'
+		}
+		else {
+			'.'
+		}
+	}
+	
+	/// Converts a code cell into a char that can be safely printed
+	fn printable_char(value: Codepoint) -> char {
+		match std::char::from_u32(value) {
+			Some(c) if !c.is_control() => c,
+			_ => '?',
+		}
+	}
+	
+	fn escape_html(text: &str) -> String {
+		let mut escaped = String::with_capacity(text.len());
+		for c in text.chars() {
+			match c {
+				'<' => escaped.push_str("&lt;"),
+				'>' => escaped.push_str("&gt;"),
+				'&' => escaped.push_str("&amp;"),
+				'"' => escaped.push_str("&quot;"),
+				_ => escaped.push(c),
+			}
+		}
+		return escaped;
+	}
+}
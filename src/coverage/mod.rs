@@ -0,0 +1,6 @@
+
+mod coverage_map;
+mod coverage_report;
+
+pub use coverage_map::*;
+pub use coverage_report::*;
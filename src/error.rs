@@ -0,0 +1,91 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io;
+use std::path::PathBuf;
+use crate::io::CodeReadError;
+use crate::interpreter::ResourceLimit;
+
+/// All errors that can stop rsfunge.
+///
+/// Each kind of error ends the process with its own exit status:
+///
+/// | Error            | Exit status |
+/// |------------------|-------------|
+/// | InvalidArguments | 2           |
+/// | Load             | 3           |
+/// | Dialect          | 4           |
+/// | Io               | 5           |
+/// | Internal         | 6           |
+/// | Compile          | 7           |
+/// | LimitExceeded    | 120...124 (see `ResourceLimit`) |
+///
+/// Errors of a run are wrapped in `Seeded`, which keeps the exit status of the wrapped error.
+#[derive(Debug)]
+pub enum RsfungeError {
+	/// The command line arguments are invalid
+	InvalidArguments(String),
+	/// The source file couldn't be loaded
+	Load {
+		path: PathBuf,
+		error: CodeReadError,
+	},
+	/// The dialect couldn't be determined or isn't supported
+	Dialect(String),
+	/// A resource limit was exceeded during execution,
+	/// by the thread at the given location if known
+	LimitExceeded(ResourceLimit, Option<String>),
+	/// Reading or writing a file failed
+	Io {
+		path: PathBuf,
+		error: io::Error,
+	},
+	/// Something went wrong inside the interpreter
+	Internal(String),
+	/// The program can't be translated by `rsfunge compile`
+	Compile(String),
+	/// An error while running a program with the given rng seed,
+	/// the seed is printed so that the run can be replayed with `--seed`
+	Seeded(Box<RsfungeError>, u64),
+}
+
+impl RsfungeError {
+	pub fn exit_code(&self) -> i32 {
+		return match self {
+			RsfungeError::InvalidArguments(_) => 2,
+			RsfungeError::Load {..} => 3,
+			RsfungeError::Dialect(_) => 4,
+			RsfungeError::Io {..} => 5,
+			RsfungeError::Internal(_) => 6,
+			RsfungeError::Compile(_) => 7,
+			RsfungeError::LimitExceeded(limit, _) => limit.exit_code(),
+			RsfungeError::Seeded(error, _) => error.exit_code(),
+		};
+	}
+}
+
+impl Error for RsfungeError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		return match self {
+			RsfungeError::Load {error, ..} => Some(error),
+			RsfungeError::Io {error, ..} => Some(error),
+			RsfungeError::Seeded(error, _) => error.source(),
+			_ => None,
+		};
+	}
+}
+
+impl Display for RsfungeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+		match self {
+			RsfungeError::InvalidArguments(message) => write!(f, "{}", message),
+			RsfungeError::Load {path, error} => write!(f, "failed to load code from \"{}\": {}", path.display(), error),
+			RsfungeError::Dialect(message) => write!(f, "{}", message),
+			RsfungeError::LimitExceeded(limit, Some(location)) => write!(f, "{} at {}", limit.describe(), location),
+			RsfungeError::LimitExceeded(limit, None) => write!(f, "{}", limit.describe()),
+			RsfungeError::Io {path, error} => write!(f, "I/O error on \"{}\": {}", path.display(), error),
+			RsfungeError::Internal(message) => write!(f, "internal error: {}", message),
+			RsfungeError::Compile(message) => write!(f, "can't compile: {}", message),
+			RsfungeError::Seeded(error, seed) => write!(f, "{} (rng seed {})", error, seed),
+		}
+	}
+}
@@ -8,6 +8,54 @@ pub enum FungeDialect {
 	Trefunge98,
 }
 
+impl FungeDialect {
+	/// Returns the number of dimensions of the funge space of this dialect.
+	pub fn dimensionality(&self) -> u32 {
+		return match self {
+			FungeDialect::Unefunge98 => 1,
+			FungeDialect::Befunge93 | FungeDialect::Befunge98 => 2,
+			FungeDialect::Trefunge98 => 3,
+		};
+	}
+	
+	/// Returns the name of the dialect, like `Befunge-98`.
+	pub fn name(&self) -> &'static str {
+		return match self {
+			FungeDialect::Befunge93 => "Befunge-93",
+			FungeDialect::Befunge98 => "Befunge-98",
+			FungeDialect::Unefunge98 => "Unefunge-98",
+			FungeDialect::Trefunge98 => "Trefunge-98",
+		};
+	}
+	
+	/// Checks if the instruction exists in this dialect, all other instructions reflect.
+	pub fn supports_instruction(&self, instruction: i32) -> bool {
+		let c = match instruction {
+			33..=126 => instruction as u8 as char,
+			_ => return false,
+		};
+		return match self {
+			FungeDialect::Befunge93 => "+-*/%!`><^v?_|\":\\$.,#gp&~@0123456789".contains(c),
+			_ => match c {
+				'^' | 'v' | '|' | '[' | ']' | 'w' => self.dimensionality() >= 2,
+				'h' | 'l' | 'm' => self.dimensionality() == 3,
+				_ => true,
+			},
+		};
+	}
+	
+	/// Guesses the dialect from the extension of a source file.
+	pub fn from_file_extension(extension: &str) -> Option<Self> {
+		return match extension.to_ascii_lowercase().as_str() {
+			"bf" | "b93" => Some(FungeDialect::Befunge93),
+			"u98" => Some(FungeDialect::Unefunge98),
+			"b98" => Some(FungeDialect::Befunge98),
+			"t98" => Some(FungeDialect::Trefunge98),
+			_ => None,
+		};
+	}
+}
+
 //// Real enum pattern (total bruh moment)
 //pub struct FungeDialect {
 //	pub dialect_id: &'static str,
@@ -1,9 +1,16 @@
-use crate::interpreter::fingerprint::{FingerprintName, FingerprintInstFunction};
+use crate::interpreter::fingerprint::{FingerprintName, FingerprintInstFunction, FingerprintState};
+use crate::interpreter::FungeValue;
 
-pub trait Fingerprint<'f> {
+pub trait Fingerprint<'f, V: FungeValue = i32> {
 	fn get_name(&self) -> FingerprintName
 	;
 	
-	fn get_alphabet_instructions(&self, dest: &mut [Option<&'f FingerprintInstFunction>; 26])
+	fn get_alphabet_instructions(&self, dest: &mut [Option<&'f FingerprintInstFunction<V>>; 26])
 	;
+	
+	/// Creates the data the fingerprint keeps per interpreter, which is shared by all threads.
+	/// Called when an interpreter loads the fingerprint for the first time.
+	fn create_state(&self) -> Option<Box<dyn FingerprintState>> {
+		return None;
+	}
 }
@@ -0,0 +1,59 @@
+use std::io::{Stdin, Stdout};
+use crate::io::CharEncoding;
+use crate::interpreter::fingerprint::FingerprintStates;
+use crate::interpreter::{FungeAddress, FungeClock, FungeDimension, FungeRng, FungeSpace, FungeSpaceAccessor, FungeValue, InstructionPointer, InstructionDelta};
+
+/// The parts of the interpreter that fingerprint instructions can use besides their thread.
+pub struct FingerprintContext<'a, V: FungeValue> {
+	/// Number of dimensions of funge space
+	pub dims: u32,
+	pub funge_space: &'a mut dyn FingerprintSpace<V>,
+	pub charout: &'a mut Stdout,
+	pub charin: &'a mut Stdin,
+	/// Encoding of the character input and output
+	pub encoding: CharEncoding,
+	/// The rng of the interpreter, so seeded runs stay reproducible
+	pub rng: &'a mut FungeRng,
+	/// The clock of the interpreter, which may be fake
+	pub clock: &'a FungeClock,
+	/// The per interpreter data of the loaded fingerprints
+	pub states: &'a mut FingerprintStates,
+}
+
+/// Funge space as fingerprints see it, independent of its dimensionality and layout.
+pub trait FingerprintSpace<V: FungeValue> {
+	fn read_cell(&self, address: &FungeAddress) -> V;
+	
+	fn write_cell(&mut self, address: &FungeAddress, value: V);
+	
+	/// Returns the least and greatest point of all non-space cells, or None if there are none.
+	fn bounds(&self) -> Option<(FungeAddress, FungeAddress)>;
+	
+	/// Returns the position of an ip after moving it by delta, wrapping around like the ip does.
+	fn next_ip(&self, ip: &InstructionPointer, delta: &InstructionDelta) -> InstructionPointer;
+	
+	/// Returns whether a write was dropped because of the page limit.
+	fn pages_exceeded(&self) -> bool;
+}
+
+impl<'s, N, V, A> FingerprintSpace<V> for FungeSpace<'s, N, V, A> where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
+	fn read_cell(&self, address: &FungeAddress) -> V {
+		return FungeSpace::read_cell(self, address);
+	}
+	
+	fn write_cell(&mut self, address: &FungeAddress, value: V) {
+		FungeSpace::write_cell(self, address, value);
+	}
+	
+	fn bounds(&self) -> Option<(FungeAddress, FungeAddress)> {
+		return FungeSpace::bounds(self);
+	}
+	
+	fn next_ip(&self, ip: &InstructionPointer, delta: &InstructionDelta) -> InstructionPointer {
+		return FungeSpace::next_ip(self, ip, delta);
+	}
+	
+	fn pages_exceeded(&self) -> bool {
+		return FungeSpace::pages_exceeded(self);
+	}
+}
@@ -1,3 +1,4 @@
 use crate::interpreter::FungeThread;
+use crate::interpreter::fingerprint::FingerprintContext;
 
-pub type FingerprintInstFunction = fn(&mut FungeThread);
+pub type FingerprintInstFunction<V = i32> = fn(&mut FungeThread<'_, '_, V>, &mut FingerprintContext<'_, V>);
@@ -1,24 +1,31 @@
 use crate::interpreter::fingerprint::Fingerprint;
 use std::collections::HashMap;
 use std::rc::Rc;
+use crate::interpreter::FungeValue;
 
-pub struct FingerprintRegistry<'f> {
-	fingerprint_map: HashMap<u32, Rc<dyn Fingerprint<'f>>>,
+pub struct FingerprintRegistry<'f, V: FungeValue = i32> {
+	fingerprint_map: HashMap<u32, Rc<dyn Fingerprint<'f, V>>>,
 }
 
-impl<'f> FingerprintRegistry<'f> {
+impl<'f, V: FungeValue> FingerprintRegistry<'f, V> {
 	pub fn new() -> Self {
 		FingerprintRegistry {
 			fingerprint_map: HashMap::new(),
 		}
 	}
 	
-	pub fn register_fingerprint(&mut self, fingerprint: Rc<dyn Fingerprint<'f>>) {
+	pub fn register_fingerprint(&mut self, fingerprint: Rc<dyn Fingerprint<'f, V>>) {
 		// Put into registry map
 		self.fingerprint_map.insert(fingerprint.get_name().get_fid(), fingerprint);
 	}
 	
-	pub fn find_fingerprint(&mut self, fid: u32) -> Option<&Rc<dyn Fingerprint<'f>>> {
+	pub fn find_fingerprint(&mut self, fid: u32) -> Option<&Rc<dyn Fingerprint<'f, V>>> {
 		return self.fingerprint_map.get(&fid);
 	}
 }
+
+impl<'f, V: FungeValue> Default for FingerprintRegistry<'f, V> {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
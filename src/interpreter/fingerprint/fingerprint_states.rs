@@ -0,0 +1,55 @@
+use std::any::Any;
+use std::collections::hash_map::Entry;
+use rustc_hash::FxHashMap;
+use crate::interpreter::fingerprint::Fingerprint;
+use crate::interpreter::FungeValue;
+
+/// The data a fingerprint keeps per interpreter.
+pub trait FingerprintState: Any {
+	fn as_any_mut(&mut self) -> &mut dyn Any;
+	
+	/// Drops the data kept for a thread that stopped.
+	fn thread_stopped(&mut self, _thread_id: u32) {}
+}
+
+/// The per interpreter data of the loaded fingerprints, by fingerprint id.
+pub struct FingerprintStates {
+	states: FxHashMap<u32, Box<dyn FingerprintState>>,
+}
+
+impl FingerprintStates {
+	pub fn new() -> Self {
+		FingerprintStates {
+			states: FxHashMap::default(),
+		}
+	}
+	
+	/// Creates the data of the fingerprint if it has any and it wasn't loaded before.
+	pub fn init<'f, V: FungeValue>(&mut self, fingerprint: &dyn Fingerprint<'f, V>) {
+		let fid = fingerprint.get_name().get_fid();
+		if let Entry::Vacant(entry) = self.states.entry(fid) {
+			if let Some(state) = fingerprint.create_state() {
+				entry.insert(state);
+			}
+		}
+	}
+	
+	/// Returns the data of the fingerprint with the given id, or None if it
+	/// wasn't loaded yet or its data has another type.
+	pub fn get_mut<T: Any>(&mut self, fid: u32) -> Option<&mut T> {
+		return self.states.get_mut(&fid).and_then(|state| state.as_any_mut().downcast_mut::<T>());
+	}
+	
+	/// Lets the fingerprints drop the data they keep for the thread.
+	pub fn thread_stopped(&mut self, thread_id: u32) {
+		for state in self.states.values_mut() {
+			state.thread_stopped(thread_id);
+		}
+	}
+}
+
+impl Default for FingerprintStates {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
@@ -1,14 +1,19 @@
 
+#[allow(clippy::module_inception)]
 mod fingerprint;
 mod simple_fingerprint;
 mod fingerprint_name;
 mod fingerprint_registry;
 mod fingerprint_inst_function;
+mod fingerprint_context;
+mod fingerprint_states;
 
 pub use fingerprint::*;
 pub use simple_fingerprint::*;
 pub use fingerprint_name::*;
 pub use fingerprint_registry::*;
 pub use fingerprint_inst_function::*;
+pub use fingerprint_context::*;
+pub use fingerprint_states::*;
 
 pub mod standard;
@@ -1,25 +1,38 @@
-use crate::interpreter::fingerprint::{Fingerprint, FingerprintName, FingerprintInstFunction};
+use crate::interpreter::fingerprint::{Fingerprint, FingerprintName, FingerprintInstFunction, FingerprintState};
+use crate::interpreter::FungeValue;
 
-pub struct SimpleFingerprint<'f> {
+pub struct SimpleFingerprint<'f, V: FungeValue = i32> {
 	name: FingerprintName,
-	inst_functions: [Option<&'f FingerprintInstFunction>; 26],
+	inst_functions: [Option<&'f FingerprintInstFunction<V>>; 26],
+	state_factory: Option<fn() -> Box<dyn FingerprintState>>,
 }
 
-impl<'f> SimpleFingerprint<'f> {
-	pub fn new(name: FingerprintName, insts: [Option<&'f FingerprintInstFunction>; 26]) -> SimpleFingerprint<'f> {
+impl<'f, V: FungeValue> SimpleFingerprint<'f, V> {
+	pub fn new(name: FingerprintName, insts: [Option<&'f FingerprintInstFunction<V>>; 26]) -> SimpleFingerprint<'f, V> {
 		SimpleFingerprint {
 			name,
 			inst_functions: insts,
+			state_factory: None,
 		}
 	}
+	
+	/// Gives the fingerprint per interpreter data made by the given function.
+	pub fn with_state(mut self, state_factory: fn() -> Box<dyn FingerprintState>) -> SimpleFingerprint<'f, V> {
+		self.state_factory = Some(state_factory);
+		return self;
+	}
 }
 
-impl<'f> Fingerprint<'f> for SimpleFingerprint<'f> {
+impl<'f, V: FungeValue> Fingerprint<'f, V> for SimpleFingerprint<'f, V> {
 	fn get_name(&self) -> FingerprintName {
 		self.name
 	}
 	
-	fn get_alphabet_instructions(&self, dest: &mut [Option<&'f FingerprintInstFunction>; 26]) {
+	fn get_alphabet_instructions(&self, dest: &mut [Option<&'f FingerprintInstFunction<V>>; 26]) {
 		dest.copy_from_slice(&self.inst_functions);
 	}
+	
+	fn create_state(&self) -> Option<Box<dyn FingerprintState>> {
+		return self.state_factory.map(|factory| factory());
+	}
 }
@@ -0,0 +1,23 @@
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext};
+use crate::interpreter::{FungeThread, FungeValue};
+use super::integer_fingerprint::{inst_bit_and, inst_bit_or, inst_bit_xor};
+
+/// BOOL (0x424f4f4c): Logic functions
+pub fn create_bool_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	// Create inst array
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[0] = Some(&(inst_bit_and::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'N' - b'A') as usize] = Some(&(inst_n::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'O' - b'A') as usize] = Some(&(inst_bit_or::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'X' - b'A') as usize] = Some(&(inst_bit_xor::<V> as FingerprintInstFunction<V>));
+	
+	// Build fingerprint object
+	let name = FingerprintName::from_fid(0x424f4f4c);
+	return Box::new(SimpleFingerprint::new(name, inst_array));
+}
+
+/// N: Bitwise not
+fn inst_n<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let a = thread.stack_stack.pop();
+	thread.stack_stack.push(a.bit_not());
+}
@@ -0,0 +1,109 @@
+use std::f64::consts::PI;
+use rand::Rng;
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext};
+use crate::interpreter::{FungeThread, FungeValue};
+use crate::interpreter::instruction::insts;
+use super::integer_fingerprint::{inst_bit_and, inst_bit_or, inst_bit_xor, inst_negate, inst_absolute};
+
+/// Scale of the fixed-point values the trigonometric functions take and return
+const SCALE: f64 = 10000.0;
+
+/// FIXP (0x46495850): Some useful fixed point math functions
+pub fn create_fixp_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	// Create inst array
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[0] = Some(&(inst_bit_and::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'B' - b'A') as usize] = Some(&(inst_b::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'C' - b'A') as usize] = Some(&(inst_c::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'D' - b'A') as usize] = Some(&(inst_d::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'I' - b'A') as usize] = Some(&(inst_i::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'J' - b'A') as usize] = Some(&(inst_j::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'N' - b'A') as usize] = Some(&(inst_negate::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'O' - b'A') as usize] = Some(&(inst_bit_or::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'P' - b'A') as usize] = Some(&(inst_p::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'Q' - b'A') as usize] = Some(&(inst_q::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'R' - b'A') as usize] = Some(&(inst_r::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'S' - b'A') as usize] = Some(&(inst_s::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'T' - b'A') as usize] = Some(&(inst_t::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'U' - b'A') as usize] = Some(&(inst_u::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'V' - b'A') as usize] = Some(&(inst_absolute::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'X' - b'A') as usize] = Some(&(inst_bit_xor::<V> as FingerprintInstFunction<V>));
+	
+	// Build fingerprint object
+	let name = FingerprintName::from_fid(0x46495850);
+	return Box::new(SimpleFingerprint::new(name, inst_array));
+}
+
+/// Applies the function to the top of the stack as a float, truncating the result
+/// and saturating it at the limits of the cell.
+#[inline(always)]
+fn unary<V: FungeValue>(thread: &mut FungeThread<V>, function: fn(f64) -> f64) {
+	let a = thread.stack_stack.pop().to_i32_wrapping() as f64;
+	thread.stack_stack.push(V::from_f64_saturating(function(a)));
+}
+
+/// B: Arccosine, in degrees
+fn inst_b<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary(thread, |a| (a / SCALE).acos().to_degrees() * SCALE);
+}
+
+/// C: Cosine of degrees
+fn inst_c<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary(thread, |a| (a / SCALE).to_radians().cos() * SCALE);
+}
+
+/// D: Random number between 0 and n (exclusive), with the sign of n
+fn inst_d<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let n = thread.stack_stack.pop().to_i32_wrapping() as i64;
+	let value = match n {
+		0 => 0,
+		n if n > 0 => context.rng.gen_range(0, n),
+		n => -context.rng.gen_range(0, -n),
+	};
+	insts::inst_push_number(thread, value as i32);
+}
+
+/// I: Sine of degrees
+fn inst_i<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary(thread, |a| (a / SCALE).to_radians().sin() * SCALE);
+}
+
+/// J: Arcsine, in degrees
+fn inst_j<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary(thread, |a| (a / SCALE).asin().to_degrees() * SCALE);
+}
+
+/// P: Multiply by pi
+fn inst_p<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary(thread, |a| a * PI);
+}
+
+/// Q: Square root
+fn inst_q<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary(thread, f64::sqrt);
+}
+
+/// R: Raise the second value to the power of the top value, saturating at the limits of the cell
+fn inst_r<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let (b, a) = thread.stack_stack.pop_two();
+	let c = (a.to_i32_wrapping() as f64).powf(b.to_i32_wrapping() as f64);
+	thread.stack_stack.push(V::from_f64_saturating(c));
+}
+
+/// S: Sign (-1, 0 or 1)
+fn inst_s<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let a = thread.stack_stack.pop();
+	let zero = V::default();
+	let sign = if a > zero { 1 } else if a < zero { -1 } else { 0 };
+	insts::inst_push_number(thread, sign);
+}
+
+/// T: Tangent of degrees
+fn inst_t<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary(thread, |a| (a / SCALE).to_radians().tan() * SCALE);
+}
+
+/// U: Arctangent, in degrees
+fn inst_u<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary(thread, |a| (a / SCALE).atan().to_degrees() * SCALE);
+}
@@ -0,0 +1,221 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::str::FromStr;
+use num_traits::Float;
+use crate::interpreter::fingerprint::{FingerprintInstFunction, FingerprintContext};
+use crate::interpreter::{FungeThread, FungeValue};
+use crate::interpreter::instruction::insts;
+
+/// A floating point number that is stored in one or more cells.
+/// Shared by FPSP and FPDP, which only differ in the precision.
+pub trait CellFloat: Float + FromStr + Display + 'static {
+	fn pop<V: FungeValue>(thread: &mut FungeThread<V>) -> Self;
+	
+	fn push<V: FungeValue>(thread: &mut FungeThread<V>, value: Self);
+	
+	fn from_i32(value: i32) -> Self;
+	
+	/// Converts to an integer, truncating towards zero and saturating (NaN is 0).
+	fn to_i32(self) -> i32;
+}
+
+/// Single precision floats take one cell with their bits
+impl CellFloat for f32 {
+	fn pop<V: FungeValue>(thread: &mut FungeThread<V>) -> Self {
+		return f32::from_bits(thread.stack_stack.pop().to_i32_wrapping() as u32);
+	}
+	
+	fn push<V: FungeValue>(thread: &mut FungeThread<V>, value: Self) {
+		thread.stack_stack.push(V::from(value.to_bits() as i32));
+	}
+	
+	fn from_i32(value: i32) -> Self {
+		return value as f32;
+	}
+	
+	fn to_i32(self) -> i32 {
+		return self as i32;
+	}
+}
+
+/// Double precision floats take two cells, the high 32 bits below the low 32 bits
+impl CellFloat for f64 {
+	fn pop<V: FungeValue>(thread: &mut FungeThread<V>) -> Self {
+		let (low, high) = thread.stack_stack.pop_two();
+		let bits = ((high.to_i32_wrapping() as u32 as u64) << 32) | (low.to_i32_wrapping() as u32 as u64);
+		return f64::from_bits(bits);
+	}
+	
+	fn push<V: FungeValue>(thread: &mut FungeThread<V>, value: Self) {
+		let bits = value.to_bits();
+		thread.stack_stack.push(V::from((bits >> 32) as u32 as i32));
+		thread.stack_stack.push(V::from(bits as u32 as i32));
+	}
+	
+	fn from_i32(value: i32) -> Self {
+		return value as f64;
+	}
+	
+	fn to_i32(self) -> i32 {
+		return self as i32;
+	}
+}
+
+/// Returns the instructions of a floating point fingerprint with the given precision.
+pub fn float_inst_array<V: FungeValue, F: CellFloat>() -> [Option<&'static FingerprintInstFunction<V>>; 26] {
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[0] = Some(&(inst_add::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'B' - b'A') as usize] = Some(&(inst_sin::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'C' - b'A') as usize] = Some(&(inst_cos::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'D' - b'A') as usize] = Some(&(inst_divide::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'E' - b'A') as usize] = Some(&(inst_arcsin::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'F' - b'A') as usize] = Some(&(inst_from_integer::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'G' - b'A') as usize] = Some(&(inst_arctan::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'H' - b'A') as usize] = Some(&(inst_arccos::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'I' - b'A') as usize] = Some(&(inst_to_integer::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'K' - b'A') as usize] = Some(&(inst_ln::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'L' - b'A') as usize] = Some(&(inst_log10::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'M' - b'A') as usize] = Some(&(inst_multiply::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'N' - b'A') as usize] = Some(&(inst_negate::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'P' - b'A') as usize] = Some(&(inst_print::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'Q' - b'A') as usize] = Some(&(inst_sqrt::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'R' - b'A') as usize] = Some(&(inst_parse::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'S' - b'A') as usize] = Some(&(inst_subtract::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'T' - b'A') as usize] = Some(&(inst_tan::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'V' - b'A') as usize] = Some(&(inst_abs::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'X' - b'A') as usize] = Some(&(inst_exp::<V, F> as FingerprintInstFunction<V>));
+	inst_array[(b'Y' - b'A') as usize] = Some(&(inst_pow::<V, F> as FingerprintInstFunction<V>));
+	return inst_array;
+}
+
+/// Applies the function to the float on top of the stack.
+#[inline(always)]
+fn unary<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, function: fn(F) -> F) {
+	let a = F::pop(thread);
+	F::push(thread, function(a));
+}
+
+/// Applies the function to the second and the top float of the stack.
+#[inline(always)]
+fn binary<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, function: fn(F, F) -> F) {
+	let b = F::pop(thread);
+	let a = F::pop(thread);
+	F::push(thread, function(a, b));
+}
+
+/// A: Add
+fn inst_add<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	binary::<V, F>(thread, |a, b| a + b);
+}
+
+/// B: Sine (radians)
+fn inst_sin<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::sin);
+}
+
+/// C: Cosine (radians)
+fn inst_cos<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::cos);
+}
+
+/// D: Divide
+fn inst_divide<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	binary::<V, F>(thread, |a, b| a / b);
+}
+
+/// E: Arcsine (radians)
+fn inst_arcsin<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::asin);
+}
+
+/// F: Convert integer to float
+fn inst_from_integer<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let n = thread.stack_stack.pop().to_i32_wrapping();
+	F::push(thread, F::from_i32(n));
+}
+
+/// G: Arctangent (radians)
+fn inst_arctan<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::atan);
+}
+
+/// H: Arccosine (radians)
+fn inst_arccos<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::acos);
+}
+
+/// I: Convert float to integer
+fn inst_to_integer<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let a = F::pop(thread);
+	insts::inst_push_number(thread, a.to_i32());
+}
+
+/// K: Natural logarithm
+fn inst_ln<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::ln);
+}
+
+/// L: Base 10 logarithm
+fn inst_log10<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::log10);
+}
+
+/// M: Multiply
+fn inst_multiply<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	binary::<V, F>(thread, |a, b| a * b);
+}
+
+/// N: Negate
+fn inst_negate<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, |a| -a);
+}
+
+/// P: Print the float with six decimals and a space, like C's `printf("%f ")`
+fn inst_print<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let a = F::pop(thread);
+	
+	// Act as `r` if the write failed
+	if write!(context.charout, "{:.6} ", a).is_err() {
+		insts::_reflect_delta(&mut thread.delta);
+	}
+	let _ = context.charout.flush();
+}
+
+/// Q: Square root
+fn inst_sqrt<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::sqrt);
+}
+
+/// R: Parse a 0gnirts into a float, reflects if it isn't a number
+fn inst_parse<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let string = insts::_pop_string(thread.stack_stack.top_stack());
+	match string.trim().parse::<F>() {
+		Ok(a) => F::push(thread, a),
+		Err(_) => insts::_reflect_delta(&mut thread.delta),
+	}
+}
+
+/// S: Subtract
+fn inst_subtract<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	binary::<V, F>(thread, |a, b| a - b);
+}
+
+/// T: Tangent (radians)
+fn inst_tan<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::tan);
+}
+
+/// V: Absolute value
+fn inst_abs<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::abs);
+}
+
+/// X: Exponential function
+fn inst_exp<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	unary::<V, F>(thread, F::exp);
+}
+
+/// Y: Raise the second float to the power of the top float
+fn inst_pow<V: FungeValue, F: CellFloat>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	binary::<V, F>(thread, F::powf);
+}
@@ -0,0 +1,9 @@
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintName};
+use crate::interpreter::FungeValue;
+use super::float_fingerprint::float_inst_array;
+
+/// FPDP (0x46504450): Double precision floating point, two cells per float
+pub fn create_fpdp_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	let name = FingerprintName::from_fid(0x46504450);
+	return Box::new(SimpleFingerprint::new(name, float_inst_array::<V, f64>()));
+}
@@ -0,0 +1,9 @@
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintName};
+use crate::interpreter::FungeValue;
+use super::float_fingerprint::float_inst_array;
+
+/// FPSP (0x46505350): Single precision floating point, one cell per float
+pub fn create_fpsp_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	let name = FingerprintName::from_fid(0x46505350);
+	return Box::new(SimpleFingerprint::new(name, float_inst_array::<V, f32>()));
+}
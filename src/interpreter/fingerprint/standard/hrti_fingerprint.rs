@@ -0,0 +1,85 @@
+use std::any::Any;
+use chrono::NaiveDateTime;
+use rustc_hash::FxHashMap;
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext, FingerprintState};
+use crate::interpreter::{FungeThread, FungeValue};
+use crate::interpreter::instruction::insts;
+
+const HRTI_FID: u32 = 0x48525449;
+
+/// HRTI (0x48525449): High-resolution timer interface
+pub fn create_hrti_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	// Create inst array
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[(b'E' - b'A') as usize] = Some(&(inst_e::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'G' - b'A') as usize] = Some(&(inst_g::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'M' - b'A') as usize] = Some(&(inst_m::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'S' - b'A') as usize] = Some(&(inst_s::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'T' - b'A') as usize] = Some(&(inst_t::<V> as FingerprintInstFunction<V>));
+	
+	// Build fingerprint object
+	let name = FingerprintName::from_fid(HRTI_FID);
+	return Box::new(SimpleFingerprint::new(name, inst_array).with_state(create_marks));
+}
+
+/// The timer marks of the threads, by thread id
+struct Marks {
+	marks: FxHashMap<u32, NaiveDateTime>,
+}
+
+impl FingerprintState for Marks {
+	fn as_any_mut(&mut self) -> &mut dyn Any {
+		return self;
+	}
+	
+	fn thread_stopped(&mut self, thread_id: u32) {
+		self.marks.remove(&thread_id);
+	}
+}
+
+fn create_marks() -> Box<dyn FingerprintState> {
+	return Box::new(Marks {
+		marks: FxHashMap::default(),
+	});
+}
+
+/// E: Erase the mark of the thread
+fn inst_e<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	if let Some(marks) = context.states.get_mut::<Marks>(HRTI_FID) {
+		marks.marks.remove(&thread.id);
+	}
+}
+
+/// G: Granularity of the timer in microseconds
+fn inst_g<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let granularity = context.clock.granularity_micros();
+	thread.stack_stack.push(V::from_i64_saturating(granularity));
+}
+
+/// M: Mark the current time for the thread
+fn inst_m<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let now = context.clock.now();
+	match context.states.get_mut::<Marks>(HRTI_FID) {
+		Some(marks) => { marks.marks.insert(thread.id, now); }
+		None => insts::_reflect_delta(&mut thread.delta),
+	}
+}
+
+/// S: Microseconds since the start of the current second
+fn inst_s<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let micros = context.clock.now().timestamp_subsec_micros();
+	insts::inst_push_number(thread, micros as i32);
+}
+
+/// T: Microseconds since the mark of the thread, reflects if the thread has no mark
+fn inst_t<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let now = context.clock.now();
+	let mark = context.states.get_mut::<Marks>(HRTI_FID).and_then(|marks| marks.marks.get(&thread.id).copied());
+	match mark {
+		Some(mark) => {
+			let micros = (now - mark).num_microseconds().unwrap_or(i64::MAX);
+			thread.stack_stack.push(V::from_i64_saturating(micros));
+		}
+		None => insts::_reflect_delta(&mut thread.delta),
+	}
+}
@@ -0,0 +1,39 @@
+use crate::interpreter::fingerprint::FingerprintContext;
+use crate::interpreter::{FungeThread, FungeValue};
+
+// Integer instructions that several fingerprints define under different letters
+
+/// Bitwise and
+pub fn inst_bit_and<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let (a, b) = thread.stack_stack.pop_two();
+	thread.stack_stack.push(b.bit_and(&a));
+}
+
+/// Bitwise or
+pub fn inst_bit_or<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let (a, b) = thread.stack_stack.pop_two();
+	thread.stack_stack.push(b.bit_or(&a));
+}
+
+/// Bitwise exclusive or
+pub fn inst_bit_xor<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let (a, b) = thread.stack_stack.pop_two();
+	thread.stack_stack.push(b.bit_xor(&a));
+}
+
+/// Negate
+pub fn inst_negate<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let a = thread.stack_stack.pop();
+	thread.stack_stack.push(V::default().wrapping_sub(&a));
+}
+
+/// Absolute value
+pub fn inst_absolute<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let a = thread.stack_stack.pop();
+	if a < V::default() {
+		thread.stack_stack.push(V::default().wrapping_sub(&a));
+	}
+	else {
+		thread.stack_stack.push(a);
+	}
+}
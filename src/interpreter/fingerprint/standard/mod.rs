@@ -1,4 +1,27 @@
-
+mod bool_fingerprint;
+mod fixp_fingerprint;
+mod float_fingerprint;
+mod fpdp_fingerprint;
+mod fpsp_fingerprint;
+mod hrti_fingerprint;
+mod integer_fingerprint;
+mod modu_fingerprint;
 mod null_fingerprint;
+mod orth_fingerprint;
+mod refc_fingerprint;
+mod roma_fingerprint;
+mod strn_fingerprint;
+mod toys_fingerprint;
 
+pub use bool_fingerprint::*;
+pub use fixp_fingerprint::*;
+pub use fpdp_fingerprint::*;
+pub use fpsp_fingerprint::*;
+pub use hrti_fingerprint::*;
+pub use modu_fingerprint::*;
 pub use null_fingerprint::*;
+pub use orth_fingerprint::*;
+pub use refc_fingerprint::*;
+pub use roma_fingerprint::*;
+pub use strn_fingerprint::*;
+pub use toys_fingerprint::*;
@@ -0,0 +1,47 @@
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext};
+use crate::interpreter::{FungeThread, FungeValue};
+
+/// MODU (0x4d4f4455): Modulo arithmetic extension
+pub fn create_modu_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	// Create inst array
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[(b'M' - b'A') as usize] = Some(&(inst_m::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'R' - b'A') as usize] = Some(&(inst_r::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'U' - b'A') as usize] = Some(&(inst_u::<V> as FingerprintInstFunction<V>));
+	
+	// Build fingerprint object
+	let name = FingerprintName::from_fid(0x4d4f4455);
+	return Box::new(SimpleFingerprint::new(name, inst_array));
+}
+
+/// M: Signed-result modulo, the result has the sign of the divisor (floored division)
+fn inst_m<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let (a, b) = thread.stack_stack.pop_two();
+	
+	let mut c = b.wrapping_rem(&a);
+	let zero = V::default();
+	if !c.is_zero() && ((c < zero) != (a < zero)) {
+		c = c.wrapping_add(&a);
+	}
+	thread.stack_stack.push(c);
+}
+
+/// R: C-language integer remainder, the result has the sign of the dividend (truncated division)
+fn inst_r<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let (a, b) = thread.stack_stack.pop_two();
+	thread.stack_stack.push(b.wrapping_rem(&a));
+}
+
+/// U: Sam Holden's unsigned-result modulo, the absolute value of the remainder
+fn inst_u<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let (a, b) = thread.stack_stack.pop_two();
+	
+	let c = b.wrapping_rem(&a);
+	let zero = V::default();
+	if c < zero {
+		thread.stack_stack.push(zero.wrapping_sub(&c));
+	}
+	else {
+		thread.stack_stack.push(c);
+	}
+}
@@ -1,9 +1,10 @@
-use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName};
-use crate::interpreter::FungeThread;
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext};
+use crate::interpreter::{FungeThread, FungeValue};
+use crate::interpreter::instruction::insts;
 
-pub fn create_null_fingerprint() -> Box<dyn Fingerprint<'static>> {
+pub fn create_null_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
 	// Create inst array
-	let inst_array: [Option<&FingerprintInstFunction>; 26] = [Some(&(inst_null as FingerprintInstFunction)); 26];
+	let inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [Some(&(inst_null::<V> as FingerprintInstFunction<V>)); 26];
 	
 	// Build fingerprint object
 	let name = FingerprintName::from_fid(0x4e554c4c);
@@ -18,6 +19,7 @@ pub fn create_null_fingerprint() -> Box<dyn Fingerprint<'static>> {
 //	
 //}
 
-fn inst_null(thread: &mut FungeThread) {
-	// Do nothing
+fn inst_null<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	// Act like 'r'
+	insts::_reflect_delta(&mut thread.delta);
 }
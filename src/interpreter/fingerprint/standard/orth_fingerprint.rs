@@ -0,0 +1,92 @@
+use std::io::Write;
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext};
+use crate::interpreter::{FungeThread, FungeValue, FungeAddress};
+use crate::interpreter::instruction::insts;
+use super::integer_fingerprint::{inst_bit_and, inst_bit_or, inst_bit_xor};
+
+/// ORTH (0x4f525448): Orthogonal easement library
+pub fn create_orth_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	// Create inst array
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[0] = Some(&(inst_bit_and::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'E' - b'A') as usize] = Some(&(inst_bit_xor::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'G' - b'A') as usize] = Some(&(inst_g::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'O' - b'A') as usize] = Some(&(inst_bit_or::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'P' - b'A') as usize] = Some(&(inst_p::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'S' - b'A') as usize] = Some(&(inst_s::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'V' - b'A') as usize] = Some(&(inst_v::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'W' - b'A') as usize] = Some(&(inst_w::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'X' - b'A') as usize] = Some(&(inst_x::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'Y' - b'A') as usize] = Some(&(inst_y::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'Z' - b'A') as usize] = Some(&(inst_z::<V> as FingerprintInstFunction<V>));
+	
+	// Build fingerprint object
+	let name = FingerprintName::from_fid(0x4f525448);
+	return Box::new(SimpleFingerprint::new(name, inst_array));
+}
+
+/// Pops a vector in the order y x (x on top) and adds the storage offset.
+fn pop_swapped_vector<V: FungeValue>(thread: &mut FungeThread<V>) -> FungeAddress {
+	let (x, y) = thread.stack_stack.pop_two();
+	let mut position = FungeAddress::new_xyz(x.to_i32_wrapping(), y.to_i32_wrapping(), 0);
+	position.add_wrapping(&thread.get_storage_offset());
+	return position;
+}
+
+/// G: Get like `g`, but with the vector in the order y x
+fn inst_g<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let position = pop_swapped_vector(thread);
+	thread.stack_stack.push(context.funge_space.read_cell(&position));
+}
+
+/// P: Put like `p`, but with the vector in the order y x
+fn inst_p<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let position = pop_swapped_vector(thread);
+	let value = thread.stack_stack.pop();
+	context.funge_space.write_cell(&position, value);
+}
+
+/// S: Output a 0gnirts
+fn inst_s<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let string = insts::_pop_string(thread.stack_stack.top_stack());
+	
+	// Act as `r` if the write failed
+	for c in string.chars() {
+		if context.encoding.write_char(context.charout, c as i32).is_err() {
+			insts::_reflect_delta(&mut thread.delta);
+			break;
+		}
+	}
+	let _ = context.charout.flush();
+}
+
+/// V: Set the x component of the delta
+fn inst_v<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let dx = thread.stack_stack.pop().to_i32_wrapping();
+	thread.delta.set_x(dx);
+}
+
+/// W: Set the y component of the delta
+fn inst_w<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let dy = thread.stack_stack.pop().to_i32_wrapping();
+	thread.delta.set_y(dy);
+}
+
+/// X: Set the x coordinate of the ip
+fn inst_x<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let x = thread.stack_stack.pop().to_i32_wrapping();
+	thread.ip.set_x(x);
+}
+
+/// Y: Set the y coordinate of the ip
+fn inst_y<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let y = thread.stack_stack.pop().to_i32_wrapping();
+	thread.ip.set_y(y);
+}
+
+/// Z: Skip the next cell like `#` if the popped value is zero
+fn inst_z<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	if thread.stack_stack.pop().is_zero() {
+		thread.ip = context.funge_space.next_ip(&thread.ip, &thread.delta);
+	}
+}
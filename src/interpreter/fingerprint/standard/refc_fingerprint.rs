@@ -0,0 +1,61 @@
+use std::any::Any;
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext, FingerprintState};
+use crate::interpreter::{FungeThread, FungeValue, FungeAddress};
+use crate::interpreter::instruction::insts;
+
+const REFC_FID: u32 = 0x52454643;
+
+/// REFC (0x52454643): Referenced cells extension
+pub fn create_refc_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	// Create inst array
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[(b'D' - b'A') as usize] = Some(&(inst_d::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'R' - b'A') as usize] = Some(&(inst_r::<V> as FingerprintInstFunction<V>));
+	
+	// Build fingerprint object
+	let name = FingerprintName::from_fid(REFC_FID);
+	return Box::new(SimpleFingerprint::new(name, inst_array).with_state(create_references));
+}
+
+/// The vectors referenced so far, a reference is the index of its vector
+struct References {
+	vectors: Vec<FungeAddress>,
+}
+
+impl FingerprintState for References {
+	fn as_any_mut(&mut self) -> &mut dyn Any {
+		return self;
+	}
+}
+
+fn create_references() -> Box<dyn FingerprintState> {
+	return Box::new(References {
+		vectors: Vec::new(),
+	});
+}
+
+/// D: Dereference a scalar back into its vector, reflects if the reference is unknown
+fn inst_d<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let reference = thread.stack_stack.pop().to_i32();
+	let vector = context.states.get_mut::<References>(REFC_FID)
+		.zip(reference)
+		.and_then(|(references, reference)| references.vectors.get(reference as usize).copied());
+	
+	match vector {
+		Some(vector) => insts::_push_vector(thread.stack_stack.top_stack(), &vector, context.dims),
+		None => insts::_reflect_delta(&mut thread.delta),
+	}
+}
+
+/// R: Reference a vector by a scalar
+fn inst_r<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let vector = insts::_pop_vector(thread.stack_stack.top_stack(), context.dims);
+	
+	match context.states.get_mut::<References>(REFC_FID) {
+		Some(references) => {
+			references.vectors.push(vector);
+			insts::inst_push_number(thread, (references.vectors.len() - 1) as i32);
+		}
+		None => insts::_reflect_delta(&mut thread.delta),
+	}
+}
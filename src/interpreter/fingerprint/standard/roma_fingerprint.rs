@@ -0,0 +1,55 @@
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext};
+use crate::interpreter::{FungeThread, FungeValue};
+use crate::interpreter::instruction::insts;
+
+/// ROMA (0x524f4d41): Roman numerals
+pub fn create_roma_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	// Create inst array
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[(b'C' - b'A') as usize] = Some(&(inst_c::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'D' - b'A') as usize] = Some(&(inst_d::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'I' - b'A') as usize] = Some(&(inst_i::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'L' - b'A') as usize] = Some(&(inst_l::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'M' - b'A') as usize] = Some(&(inst_m::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'V' - b'A') as usize] = Some(&(inst_v::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'X' - b'A') as usize] = Some(&(inst_x::<V> as FingerprintInstFunction<V>));
+	
+	// Build fingerprint object
+	let name = FingerprintName::from_fid(0x524f4d41);
+	return Box::new(SimpleFingerprint::new(name, inst_array));
+}
+
+/// C: Push 100
+fn inst_c<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	insts::inst_push_number(thread, 100);
+}
+
+/// D: Push 500
+fn inst_d<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	insts::inst_push_number(thread, 500);
+}
+
+/// I: Push 1
+fn inst_i<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	insts::inst_push_number(thread, 1);
+}
+
+/// L: Push 50
+fn inst_l<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	insts::inst_push_number(thread, 50);
+}
+
+/// M: Push 1000
+fn inst_m<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	insts::inst_push_number(thread, 1000);
+}
+
+/// V: Push 5
+fn inst_v<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	insts::inst_push_number(thread, 5);
+}
+
+/// X: Push 10
+fn inst_x<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	insts::inst_push_number(thread, 10);
+}
@@ -0,0 +1,234 @@
+use std::cmp::Ordering;
+use std::io::Write;
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext};
+use crate::interpreter::{FungeThread, FungeValue, FungeAddress};
+use crate::interpreter::instruction::insts;
+
+/// STRN (0x5354524e): String functions on 0gnirts
+pub fn create_strn_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	// Create inst array
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[0] = Some(&(inst_a::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'C' - b'A') as usize] = Some(&(inst_c::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'D' - b'A') as usize] = Some(&(inst_d::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'F' - b'A') as usize] = Some(&(inst_f::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'G' - b'A') as usize] = Some(&(inst_g::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'I' - b'A') as usize] = Some(&(inst_i::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'L' - b'A') as usize] = Some(&(inst_l::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'M' - b'A') as usize] = Some(&(inst_m::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'N' - b'A') as usize] = Some(&(inst_n::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'P' - b'A') as usize] = Some(&(inst_p::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'R' - b'A') as usize] = Some(&(inst_r::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'S' - b'A') as usize] = Some(&(inst_s::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'V' - b'A') as usize] = Some(&(inst_v::<V> as FingerprintInstFunction<V>));
+	
+	// Build fingerprint object
+	let name = FingerprintName::from_fid(0x5354524e);
+	return Box::new(SimpleFingerprint::new(name, inst_array));
+}
+
+/// Pops a 0gnirts and returns its cells, first character first.
+/// Strings are kept as cells, so cells that aren't valid chars survive.
+fn pop_cells<V: FungeValue>(thread: &mut FungeThread<V>) -> Vec<V> {
+	let mut cells = Vec::new();
+	loop {
+		let cell = thread.stack_stack.pop();
+		if cell.is_zero() {
+			break;
+		}
+		cells.push(cell);
+	}
+	return cells;
+}
+
+/// Pushes the cells as a 0gnirts.
+fn push_cells<V: FungeValue>(thread: &mut FungeThread<V>, cells: &[V]) {
+	thread.stack_stack.push(V::default());
+	for cell in cells.iter().rev() {
+		thread.stack_stack.push(cell.clone());
+	}
+}
+
+/// Pops a count, reflects and returns None if it's negative.
+fn pop_count<V: FungeValue>(thread: &mut FungeThread<V>) -> Option<usize> {
+	let n = thread.stack_stack.pop().to_i32_wrapping();
+	if n < 0 {
+		insts::_reflect_delta(&mut thread.delta);
+		return None;
+	}
+	return Some(n as usize);
+}
+
+/// A: Append the second string to the top string
+fn inst_a<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let mut top = pop_cells(thread);
+	let second = pop_cells(thread);
+	top.extend(second);
+	push_cells(thread, &top);
+}
+
+/// C: Compare the top string to the second string, pushes -1, 0 or 1
+fn inst_c<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let top = pop_cells(thread);
+	let second = pop_cells(thread);
+	let ordering = match top.partial_cmp(&second) {
+		Some(Ordering::Less) => -1,
+		Some(Ordering::Greater) => 1,
+		_ => 0,
+	};
+	insts::inst_push_number(thread, ordering);
+}
+
+/// D: Display a string
+fn inst_d<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let cells = pop_cells(thread);
+	
+	// Act as `r` if the write failed
+	for cell in cells.iter() {
+		if context.encoding.write_char(context.charout, cell.to_i32().unwrap_or(-1)).is_err() {
+			insts::_reflect_delta(&mut thread.delta);
+			break;
+		}
+	}
+	let _ = context.charout.flush();
+}
+
+/// F: Search the top string for the second string, pushes the rest of the top string
+/// from the first match on, or an empty string if there is none
+fn inst_f<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let top = pop_cells(thread);
+	let second = pop_cells(thread);
+	let position = if second.is_empty() {
+		Some(0)
+	}
+	else {
+		top.windows(second.len()).position(|window| window == second.as_slice())
+	};
+	match position {
+		Some(position) => push_cells(thread, &top[position..]),
+		None => push_cells(thread, &[]),
+	}
+}
+
+/// G: Get a string from funge space, read eastwards from the vector (relative to the storage offset).
+/// Reflects if the string runs out of the bounds of funge space without its terminating 0.
+fn inst_g<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let mut position = insts::_pop_vector(thread.stack_stack.top_stack(), context.dims);
+	position.add_wrapping(&thread.get_storage_offset());
+	
+	let greatest_x = context.funge_space.bounds().map_or(position.x(), |(_, greatest)| greatest.x());
+	let mut cells = Vec::new();
+	loop {
+		let cell = context.funge_space.read_cell(&position);
+		if cell.is_zero() {
+			break;
+		}
+		if position.x() >= greatest_x {
+			insts::_reflect_delta(&mut thread.delta);
+			return;
+		}
+		cells.push(cell);
+		position.add_wrapping(&FungeAddress::new_xyz(1, 0, 0));
+	}
+	push_cells(thread, &cells);
+}
+
+/// I: Input a line as a string, without its line break
+fn inst_i<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let mut charin = context.charin.lock();
+	let mut cells = Vec::new();
+	loop {
+		match context.encoding.read_char(&mut charin) {
+			Ok(Some(10)) => break,
+			Ok(Some(c)) => cells.push(V::from(c as i32)),
+			// Reflect at the end of input if nothing was read, like `~`
+			_ if cells.is_empty() => {
+				insts::_reflect_delta(&mut thread.delta);
+				return;
+			}
+			_ => break,
+		}
+	}
+	if cells.last() == Some(&V::from(13)) {
+		cells.pop();
+	}
+	push_cells(thread, &cells);
+}
+
+/// L: Leftmost n characters of a string
+fn inst_l<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	if let Some(n) = pop_count(thread) {
+		let cells = pop_cells(thread);
+		push_cells(thread, &cells[..n.min(cells.len())]);
+	}
+}
+
+/// M: n characters of a string, starting at position s (n on top)
+fn inst_m<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	if let Some(n) = pop_count(thread) {
+		if let Some(s) = pop_count(thread) {
+			let cells = pop_cells(thread);
+			let start = s.min(cells.len());
+			let end = start.saturating_add(n).min(cells.len());
+			push_cells(thread, &cells[start..end]);
+		}
+	}
+}
+
+/// N: Length of a string, keeping the string
+fn inst_n<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let cells = pop_cells(thread);
+	push_cells(thread, &cells);
+	insts::inst_push_number(thread, cells.len() as i32);
+}
+
+/// P: Put a string with its terminating 0 into funge space, eastwards from the vector (relative to the storage offset)
+fn inst_p<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let mut position = insts::_pop_vector(thread.stack_stack.top_stack(), context.dims);
+	position.add_wrapping(&thread.get_storage_offset());
+	
+	let cells = pop_cells(thread);
+	for cell in cells.into_iter().chain(std::iter::once(V::default())) {
+		context.funge_space.write_cell(&position, cell);
+		position.add_wrapping(&FungeAddress::new_xyz(1, 0, 0));
+	}
+}
+
+/// R: Rightmost n characters of a string
+fn inst_r<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	if let Some(n) = pop_count(thread) {
+		let cells = pop_cells(thread);
+		push_cells(thread, &cells[(cells.len() - n.min(cells.len()))..]);
+	}
+}
+
+/// S: Convert an integer to a decimal string
+fn inst_s<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let n = thread.stack_stack.pop();
+	let cells: Vec<V> = n.to_string().bytes().map(|b| V::from(b as i32)).collect();
+	push_cells(thread, &cells);
+}
+
+/// V: Convert a decimal string to an integer like C's `atoi`,
+/// leading whitespace and a sign are allowed and parsing stops at the first non-digit
+fn inst_v<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let cells = pop_cells(thread);
+	let mut chars = cells.iter().map(|cell| cell.to_i32().unwrap_or(-1)).skip_while(|c| matches!(c, 9..=13 | 32)).peekable();
+	
+	let negative = chars.peek() == Some(&45);
+	if matches!(chars.peek(), Some(43) | Some(45)) {
+		chars.next();
+	}
+	
+	let mut value = V::default();
+	for c in chars {
+		if !(48..=57).contains(&c) {
+			break;
+		}
+		value = value.wrapping_mul(&V::from(10)).wrapping_add(&V::from(c - 48));
+	}
+	if negative {
+		value = V::default().wrapping_sub(&value);
+	}
+	thread.stack_stack.push(value);
+}
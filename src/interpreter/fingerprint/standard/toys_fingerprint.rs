@@ -0,0 +1,366 @@
+use crate::interpreter::fingerprint::{Fingerprint, SimpleFingerprint, FingerprintInstFunction, FingerprintName, FingerprintContext};
+use crate::interpreter::{FungeThread, FungeValue, FungeAddress, InstructionDelta};
+use crate::interpreter::instruction::insts;
+use super::integer_fingerprint::inst_negate;
+
+/// TOYS (0x544f5953): Funge-98 Standard Toys
+pub fn create_toys_fingerprint<V: FungeValue>() -> Box<dyn Fingerprint<'static, V>> {
+	// Create inst array
+	let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+	inst_array[0] = Some(&(inst_a::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'B' - b'A') as usize] = Some(&(inst_b::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'C' - b'A') as usize] = Some(&(inst_c::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'D' - b'A') as usize] = Some(&(inst_d::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'E' - b'A') as usize] = Some(&(inst_e::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'F' - b'A') as usize] = Some(&(inst_f::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'G' - b'A') as usize] = Some(&(inst_g::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'H' - b'A') as usize] = Some(&(inst_h::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'I' - b'A') as usize] = Some(&(inst_i::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'J' - b'A') as usize] = Some(&(inst_j::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'K' - b'A') as usize] = Some(&(inst_k::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'L' - b'A') as usize] = Some(&(inst_l::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'M' - b'A') as usize] = Some(&(inst_m::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'N' - b'A') as usize] = Some(&(inst_negate::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'O' - b'A') as usize] = Some(&(inst_o::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'P' - b'A') as usize] = Some(&(inst_p::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'Q' - b'A') as usize] = Some(&(inst_q::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'R' - b'A') as usize] = Some(&(inst_r::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'S' - b'A') as usize] = Some(&(inst_s::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'T' - b'A') as usize] = Some(&(inst_t::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'U' - b'A') as usize] = Some(&(inst_u::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'V' - b'A') as usize] = Some(&(inst_v::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'W' - b'A') as usize] = Some(&(inst_w::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'X' - b'A') as usize] = Some(&(inst_x::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'Y' - b'A') as usize] = Some(&(inst_y::<V> as FingerprintInstFunction<V>));
+	inst_array[(b'Z' - b'A') as usize] = Some(&(inst_z::<V> as FingerprintInstFunction<V>));
+	
+	// Build fingerprint object
+	let name = FingerprintName::from_fid(0x544f5953);
+	return Box::new(SimpleFingerprint::new(name, inst_array));
+}
+
+/// Pops a vector and adds the storage offset.
+fn pop_position<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> FungeAddress {
+	let mut position = insts::_pop_vector(thread.stack_stack.top_stack(), dims);
+	position.add_wrapping(&thread.get_storage_offset());
+	return position;
+}
+
+/// Returns the width, height and depth of a box of the given size.
+/// Components of dimensions the dialect doesn't have count as size 1.
+/// Reflects and returns None if a component is negative.
+fn box_size<V: FungeValue>(thread: &mut FungeThread<V>, size: &FungeAddress, dims: u32) -> Option<(i32, i32, i32)> {
+	let width = size.x();
+	let height = if dims >= 2 { size.y() } else { 1 };
+	let depth = if dims >= 3 { size.z() } else { 1 };
+	if width < 0 || height < 0 || depth < 0 {
+		insts::_reflect_delta(&mut thread.delta);
+		return None;
+	}
+	return Some((width, height, depth));
+}
+
+/// Iterates over the offsets of all cells in a box, from the least to the greatest cell.
+/// The offsets are generated as they are needed, a box can have far more cells than fit in memory.
+fn box_offsets(width: i32, height: i32, depth: i32) -> impl DoubleEndedIterator<Item = FungeAddress> {
+	return (0..depth).flat_map(move |z| {
+		(0..height).flat_map(move |y| (0..width).map(move |x| FungeAddress::new_xyz(x, y, z)))
+	});
+}
+
+/// Whether the page or stack limit was hit, filling the rest of a box would only waste time then.
+fn limit_exceeded<V: FungeValue>(thread: &mut FungeThread<V>, context: &FingerprintContext<V>) -> bool {
+	return context.funge_space.pages_exceeded() || thread.stack_stack.overflowed();
+}
+
+fn offset_by(position: &FungeAddress, offset: &FungeAddress) -> FungeAddress {
+	let mut result = *position;
+	result.add_wrapping(offset);
+	return result;
+}
+
+/// Copies (or moves) a box, the vectors are popped as destination, size and source.
+/// A low-order copy goes from the least to the greatest cell, a high-order copy the other way round,
+/// which matters if source and destination overlap. A move clears each source cell right after reading it.
+fn copy_box<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>, high_order: bool, clear_source: bool) {
+	let destination = pop_position(thread, context.dims);
+	let size = insts::_pop_vector(thread.stack_stack.top_stack(), context.dims);
+	let source = pop_position(thread, context.dims);
+	
+	if let Some((width, height, depth)) = box_size(thread, &size, context.dims) {
+		let offsets = box_offsets(width, height, depth);
+		let offsets: Box<dyn Iterator<Item = FungeAddress>> = if high_order { Box::new(offsets.rev()) } else { Box::new(offsets) };
+		
+		for offset in offsets {
+			let from = offset_by(&source, &offset);
+			let cell = context.funge_space.read_cell(&from);
+			if clear_source {
+				context.funge_space.write_cell(&from, V::from(32));
+			}
+			context.funge_space.write_cell(&offset_by(&destination, &offset), cell);
+			if limit_exceeded(thread, context) {
+				return;
+			}
+		}
+	}
+}
+
+/// Shifts the cells on the line through the ip along the given axis by n cells, within the bounds of funge space.
+fn shift_line<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>, axis: usize) {
+	let n = thread.stack_stack.pop().to_i32_wrapping();
+	let (least, greatest) = match context.funge_space.bounds() {
+		Some(bounds) => bounds,
+		None => return,
+	};
+	
+	let component = |address: &FungeAddress| if axis == 0 { address.x() } else { address.y() };
+	let at = |i: i32| {
+		let mut address = thread.ip;
+		if axis == 0 { address.set_x(i); } else { address.set_y(i); }
+		address
+	};
+	
+	let cells: Vec<V> = (component(&least)..=component(&greatest)).map(|i| context.funge_space.read_cell(&at(i))).collect();
+	for i in component(&least)..=component(&greatest) {
+		context.funge_space.write_cell(&at(i), V::from(32));
+	}
+	for (i, cell) in (component(&least)..=component(&greatest)).zip(cells) {
+		context.funge_space.write_cell(&at(i.wrapping_add(n)), cell);
+	}
+}
+
+/// Pops all cells of the stack and folds them with the given function.
+fn fold_stack<V: FungeValue>(thread: &mut FungeThread<V>, initial: V, function: fn(&V, &V) -> V) {
+	let mut result = initial;
+	while let Some(cell) = thread.stack_stack.top_stack().try_pop() {
+		result = function(&result, &cell);
+	}
+	thread.stack_stack.push(result);
+}
+
+/// A: Gable, pops n and a value and pushes n copies of the value
+fn inst_a<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let n = thread.stack_stack.pop().to_i32_wrapping();
+	let value = thread.stack_stack.pop();
+	if n < 0 {
+		insts::_reflect_delta(&mut thread.delta);
+		return;
+	}
+	for _ in 0..n {
+		thread.stack_stack.push(value.clone());
+		if thread.stack_stack.overflowed() {
+			return;
+		}
+	}
+}
+
+/// B: Pair of shoes, pops a and b and pushes a+b and a-b
+fn inst_b<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let (b, a) = thread.stack_stack.pop_two();
+	thread.stack_stack.push(a.wrapping_add(&b));
+	thread.stack_stack.push(a.wrapping_sub(&b));
+}
+
+/// C: Bracelet, low-order copy of a box
+fn inst_c<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	copy_box(thread, context, false, false);
+}
+
+/// D: Toilet seat, decrement
+fn inst_d<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let a = thread.stack_stack.pop();
+	thread.stack_stack.push(a.wrapping_sub(&V::from(1)));
+}
+
+/// E: Pitchfork head, sum of all cells on the stack
+fn inst_e<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	fold_stack(thread, V::default(), V::wrapping_add);
+}
+
+/// F: Calipers, pops a vector, j and i and then i*j cells, which are written as a matrix
+/// of width i and height j at the vector, top row first and each row from left to right
+fn inst_f<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let origin = pop_position(thread, context.dims);
+	let height = thread.stack_stack.pop().to_i32_wrapping();
+	let width = thread.stack_stack.pop().to_i32_wrapping();
+	if width < 0 || height < 0 {
+		insts::_reflect_delta(&mut thread.delta);
+		return;
+	}
+	
+	for offset in box_offsets(width, height, 1) {
+		let cell = thread.stack_stack.pop();
+		context.funge_space.write_cell(&offset_by(&origin, &offset), cell);
+		if limit_exceeded(thread, context) {
+			return;
+		}
+	}
+}
+
+/// G: Counterclockwise, the inverse of F, pushes the matrix so that its top left cell ends up on top
+fn inst_g<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let origin = pop_position(thread, context.dims);
+	let height = thread.stack_stack.pop().to_i32_wrapping();
+	let width = thread.stack_stack.pop().to_i32_wrapping();
+	if width < 0 || height < 0 {
+		insts::_reflect_delta(&mut thread.delta);
+		return;
+	}
+	
+	for offset in box_offsets(width, height, 1).rev() {
+		let cell = context.funge_space.read_cell(&offset_by(&origin, &offset));
+		thread.stack_stack.push(cell);
+		if limit_exceeded(thread, context) {
+			return;
+		}
+	}
+}
+
+/// H: Pair of stilts, shifts a left by b bits, or right (arithmetic) if b is negative
+fn inst_h<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let b = thread.stack_stack.pop().to_i32_wrapping();
+	let a = thread.stack_stack.pop();
+	thread.stack_stack.push(a.shift(b));
+}
+
+/// I: Doric column, increment
+fn inst_i<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	let a = thread.stack_stack.pop();
+	thread.stack_stack.push(a.wrapping_add(&V::from(1)));
+}
+
+/// J: Fishhook, shifts the column of the ip south by n cells (north if negative)
+fn inst_j<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	shift_line(thread, context, 1);
+}
+
+/// K: Scissors, high-order copy of a box
+fn inst_k<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	copy_box(thread, context, true, false);
+}
+
+/// L: Corner, pushes the cell left of the ip relative to its delta
+fn inst_l<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let mut delta: InstructionDelta = thread.delta;
+	insts::_rotate_delta_counterclockwise_90(&mut delta);
+	let cell = context.funge_space.read_cell(&offset_by(&thread.ip, &delta));
+	thread.stack_stack.push(cell);
+}
+
+/// M: Kittycat, low-order move of a box
+fn inst_m<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	copy_box(thread, context, false, true);
+}
+
+/// O: Boulder, shifts the row of the ip east by n cells (west if negative)
+fn inst_o<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	shift_line(thread, context, 0);
+}
+
+/// P: Mailbox, product of all cells on the stack
+fn inst_p<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	fold_stack(thread, V::from(1), V::wrapping_mul);
+}
+
+/// Q: Necklace, puts a value into the cell behind the ip
+fn inst_q<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let value = thread.stack_stack.pop();
+	let mut delta: InstructionDelta = thread.delta;
+	insts::_reflect_delta(&mut delta);
+	context.funge_space.write_cell(&offset_by(&thread.ip, &delta), value);
+}
+
+/// R: Can opener, pushes the cell right of the ip relative to its delta
+fn inst_r<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let mut delta: InstructionDelta = thread.delta;
+	insts::_rotate_delta_clockwise_90(&mut delta);
+	let cell = context.funge_space.read_cell(&offset_by(&thread.ip, &delta));
+	thread.stack_stack.push(cell);
+}
+
+/// S: Chicane, pops the origin and size of a box and a value and fills the box with the value
+fn inst_s<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let origin = pop_position(thread, context.dims);
+	let size = insts::_pop_vector(thread.stack_stack.top_stack(), context.dims);
+	let value = thread.stack_stack.pop();
+	
+	if let Some((width, height, depth)) = box_size(thread, &size, context.dims) {
+		for offset in box_offsets(width, height, depth) {
+			context.funge_space.write_cell(&offset_by(&origin, &offset), value.clone());
+			if limit_exceeded(thread, context) {
+				return;
+			}
+		}
+	}
+}
+
+/// T: Barstool, pops a dimension and acts like `_` (0), `|` (1) or `m` (2)
+fn inst_t<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let dimension = thread.stack_stack.pop().to_i32_wrapping();
+	let valid = match dimension {
+		0 => { insts::inst_east_west_if(thread); true }
+		1 => insts::inst_north_south_if(thread, context.dims),
+		2 => insts::inst_high_low_if(thread, context.dims),
+		_ => false,
+	};
+	if !valid {
+		insts::_reflect_delta(&mut thread.delta);
+	}
+}
+
+/// U: Tumbler, turns in a random direction like `?` and replaces itself with the matching arrow
+fn inst_u<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	insts::inst_go_away(thread, context.dims, context.rng);
+	
+	let arrow = match (thread.delta.x(), thread.delta.y(), thread.delta.z()) {
+		(1, _, _) => '>',
+		(-1, _, _) => '<',
+		(_, 1, _) => 'v',
+		(_, -1, _) => '^',
+		(_, _, 1) => 'h',
+		_ => 'l',
+	};
+	context.funge_space.write_cell(&thread.ip, V::from(arrow as i32));
+}
+
+/// V: Dixiecup, high-order move of a box
+fn inst_v<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	copy_box(thread, context, true, true);
+}
+
+/// W: Television antenna, pops a vector and a value and waits until the cell at the vector holds
+/// at least the value: if it's less, everything is pushed back and the W is executed again,
+/// if it's greater the ip reflects
+fn inst_w<V: FungeValue>(thread: &mut FungeThread<V>, context: &mut FingerprintContext<V>) {
+	let vector = insts::_pop_vector(thread.stack_stack.top_stack(), context.dims);
+	let value = thread.stack_stack.pop();
+	let position = offset_by(&vector, &thread.get_storage_offset());
+	let cell = context.funge_space.read_cell(&position);
+	
+	if cell < value {
+		// Wait by executing W again on the next tick
+		thread.stack_stack.push(value);
+		insts::_push_vector(thread.stack_stack.top_stack(), &vector, context.dims);
+		let mut delta: InstructionDelta = thread.delta;
+		insts::_reflect_delta(&mut delta);
+		thread.ip = context.funge_space.next_ip(&thread.ip, &delta);
+	}
+	else if cell > value {
+		insts::_reflect_delta(&mut thread.delta);
+	}
+}
+
+/// X: Buried treasure, increments the x coordinate of the ip
+fn inst_x<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	thread.ip.set_x(thread.ip.x().wrapping_add(1));
+}
+
+/// Y: Slingshot, increments the y coordinate of the ip
+fn inst_y<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	thread.ip.set_y(thread.ip.y().wrapping_add(1));
+}
+
+/// Z: Barn door, increments the z coordinate of the ip
+fn inst_z<V: FungeValue>(thread: &mut FungeThread<V>, _context: &mut FingerprintContext<V>) {
+	thread.ip.set_z(thread.ip.z().wrapping_add(1));
+}
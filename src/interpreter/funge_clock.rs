@@ -0,0 +1,70 @@
+use chrono::{NaiveDateTime, Duration, Local};
+use chrono::naive::MAX_DATE;
+use std::convert::TryFrom;
+
+/// The source of the current date and time of an interpreter.
+/// 
+/// Everything that reads the time (like `y` and timer fingerprints)
+/// must use the clock of the interpreter, so that runs can be made
+/// independent of the real time.
+#[derive(Clone)]
+pub enum FungeClock {
+	/// The real local time
+	Real,
+	/// A fixed point in time that never changes
+	Fixed(NaiveDateTime),
+	/// A virtual time that starts at the given point in time
+	/// and advances by a fixed number of microseconds every instruction
+	Ticking {
+		start: NaiveDateTime,
+		tick_micros: i64,
+		ticks: u64,
+	},
+}
+
+impl FungeClock {
+	/// Creates a ticking clock, or returns None if the tick length is negative
+	/// or a single tick already leaves the range of representable dates.
+	pub fn new_ticking(start: NaiveDateTime, tick_micros: i64) -> Option<Self> {
+		if tick_micros < 0 {
+			return None;
+		}
+		start.checked_add_signed(Duration::microseconds(tick_micros))?;
+		
+		return Some(FungeClock::Ticking {
+			start,
+			tick_micros,
+			ticks: 0,
+		});
+	}
+	
+	/// Returns the current local date and time of this clock.
+	pub fn now(&self) -> NaiveDateTime {
+		return match self {
+			FungeClock::Real => Local::now().naive_local(),
+			FungeClock::Fixed(time) => *time,
+			FungeClock::Ticking { start, tick_micros, ticks } => {
+				// Stop at the last representable date instead of overflowing
+				let elapsed = tick_micros.saturating_mul(i64::try_from(*ticks).unwrap_or(i64::MAX));
+				start.checked_add_signed(Duration::microseconds(elapsed))
+					.unwrap_or_else(|| MAX_DATE.and_hms(23, 59, 59))
+			}
+		};
+	}
+	
+	/// Returns the smallest step in which the time of this clock advances, in microseconds.
+	pub fn granularity_micros(&self) -> i64 {
+		return match self {
+			FungeClock::Ticking { tick_micros, .. } => (*tick_micros).max(1),
+			_ => 1,
+		};
+	}
+	
+	/// Advances the clock by the given number of ticks. Only affects ticking clocks.
+	#[inline]
+	pub fn tick(&mut self, num: u64) {
+		if let FungeClock::Ticking { ticks, .. } = self {
+			*ticks = ticks.saturating_add(num);
+		}
+	}
+}
@@ -1,49 +1,96 @@
-use crate::interpreter::{FungeAddress, FungeDim2, FungeSpace, SpaceAccessorDim2, ThreadList, FungeThread, FungeDimension, FungeSpaceAccessor};
-use crate::io::{CodeBuffer, CodeSource, LineTerminator};
+use crate::interpreter::{FungeAddress, FungeSpace, ThreadList, FungeThread, FungeDimension, FungeSpaceAccessor, FungeValue, FungeRng, DEFAULT_RNG_SEED, make_funge_rng, FungeClock, ResourceLimits, ResourceLimit, TraceCache};
+use crate::io::{CodeBuffer, CodeSource, CharEncoding, SourceMap, SourcePlacement};
 use crate::vector::Vector3;
 use crate::interpreter::instruction::insts;
 use std::io::{Stdin, Stdout};
 use std::num::Wrapping;
 use crate::FungeDialect;
 use std::env;
-use crate::interpreter::fingerprint::FingerprintRegistry;
+use crate::interpreter::fingerprint::{FingerprintRegistry, FingerprintContext, FingerprintStates};
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::borrow::Borrow;
+use crate::coverage::{CoverageMap, CoverageFlags};
+use std::time::Instant;
+use std::convert::TryFrom;
+use crate::error::RsfungeError;
 
 /// The handprint of rsfunge, "RSFN"
 pub const RSFUNGE_HANDPRINT: u32 = 0x5253464e;
-pub const RSFUNGE_VERSION: u32 = 010000;
+pub const RSFUNGE_VERSION: u32 = 10000;
 
 /// Interpreter for funge*.
 /// Instances directly contain the interpretation state.
-pub struct FungeInterpreter<'s, 'f, N, A> where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
-	threads: ThreadList<'s, 'f>,
-	funge_space: FungeSpace<'s, N, i32, A>,
+/// 
+/// The interpreter is generic over the cell type V, which
+/// determines the width of all cells in funge space and on the stacks.
+pub struct FungeInterpreter<'s, 'f, N, V, A> where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
+	threads: ThreadList<'s, 'f, V>,
+	funge_space: FungeSpace<'s, N, V, A>,
 	#[allow(dead_code)]
 	dialect_mode: FungeDialect,
 	#[allow(dead_code)]
 	code_source: CodeSource,
 	
-	fingerprint_registry: Rc<RefCell<FingerprintRegistry<'f>>>,
+	fingerprint_registry: Rc<RefCell<FingerprintRegistry<'f, V>>>,
+	/// Data of the loaded fingerprints, shared by all threads
+	fingerprint_states: FingerprintStates,
 	
 	charout: Stdout,
 	charin: Stdin,
+	/// Encoding of the character input and output and of files loaded by `i`
+	encoding: CharEncoding,
 	
 	programatically_quit: bool,
 	quit_exit_code: i32,
 	
+	/// The seed the rng was last seeded with, so a run can be replayed
+	rng_seed: u64,
+	rng: FungeRng,
+	
+	/// The source of the date and time
+	clock: FungeClock,
+	
 	/// A single null terminated env var string with null terminated "name=value" variables
 	env_var_string: Vec<u8>,
 	
 	/// A double null terminated cli arg string with null terminated strings
 	cli_arg_string: Vec<u8>,
+	
+	/// Records executed, read and written cells if coverage is enabled
+	coverage: Option<CoverageMap>,
+	/// Precompiled runs of hot instructions, None if every instruction is interpreted
+	trace_cache: Option<TraceCache<V>>,
+	/// Where the loaded source files were placed
+	source_map: SourceMap,
+	
+	limits: ResourceLimits,
+	/// The limit that stopped the execution, if any
+	exceeded_limit: Option<ResourceLimit>,
+	/// Position of the thread that exceeded the limit
+	exceeded_limit_location: Option<FungeAddress>,
+	executed_instructions: u64,
+	total_stack_cells: u64,
+	/// When the execution started, for the wall time limit
+	start_time: Instant,
+	
+	next_thread_id: u32,
+}
+
+/// The outcome of a single thread tick
+enum ThreadTickOutcome<'s, 'f, V: FungeValue> {
+	Alive,
+	Stopped,
+	/// The thread split (t) and spawned the given child thread
+	Spawned(Box<FungeThread<'s, 'f, V>>),
+	/// An iteration of k exceeded the given resource limit
+	LimitExceeded(ResourceLimit),
 }
 
-impl<'s, 'f, N, A> FungeInterpreter<'s, 'f, N, A> where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
-	pub fn new(dialect_mode: FungeDialect, code_source: CodeSource, fingerprint_registry: Rc<RefCell<FingerprintRegistry<'f>>>, charout: Stdout, charin: Stdin) -> Self { //charout: &'io mut dyn Write, charin: &'io mut dyn Read
+impl<'s, 'f, N, V, A> FungeInterpreter<'s, 'f, N, V, A> where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
+	pub fn new(dialect_mode: FungeDialect, code_source: CodeSource, fingerprint_registry: Rc<RefCell<FingerprintRegistry<'f, V>>>, charout: Stdout, charin: Stdin) -> Self { //charout: &'io mut dyn Write, charin: &'io mut dyn Read
 		// Build cli arg string // TODO: Include real (after --) given cli args aswell
-		let mut cli_arg_string = Vec::from(code_source.get_path().file_stem().unwrap().to_os_string().into_string().unwrap().as_bytes());
+		let mut cli_arg_string = Vec::from(code_source.get_path().file_stem().unwrap_or_default().to_string_lossy().as_bytes());
 		cli_arg_string.push(0); // Null terminate program name
 		cli_arg_string.push(0); cli_arg_string.push(0); // Double null terminate arg string
 		
@@ -58,15 +105,35 @@ impl<'s, 'f, N, A> FungeInterpreter<'s, 'f, N, A> where N: FungeDimension, A: Fu
 			code_source,
 			
 			fingerprint_registry,
+			fingerprint_states: FingerprintStates::new(),
 			
 			charout,
 			charin,
+			encoding: CharEncoding::Utf8,
 			
 			programatically_quit: false,
 			quit_exit_code: 0,
 			
+			rng_seed: DEFAULT_RNG_SEED,
+			rng: make_funge_rng(DEFAULT_RNG_SEED),
+			
+			clock: FungeClock::Real,
+			
 			env_var_string,
 			cli_arg_string,
+			
+			coverage: None,
+			trace_cache: Some(TraceCache::new()),
+			source_map: SourceMap::new(),
+			
+			limits: ResourceLimits::new(),
+			exceeded_limit: None,
+			exceeded_limit_location: None,
+			executed_instructions: 0,
+			total_stack_cells: 0,
+			start_time: Instant::now(),
+			
+			next_thread_id: 0,
 		};
 		
 		// Create initial thread
@@ -84,11 +151,83 @@ impl<'s, 'f, N, A> FungeInterpreter<'s, 'f, N, A> where N: FungeDimension, A: Fu
 		}
 	}
 	
+	/// Reseeds the rng of this interpreter. Runs with the same seed
+	/// (and the same input) behave exactly the same.
+	pub fn set_rng_seed(&mut self, seed: u64) {
+		self.rng_seed = seed;
+		self.rng = make_funge_rng(seed);
+	}
+	
+	pub fn get_rng_seed(&self) -> u64 {
+		return self.rng_seed;
+	}
+	
+	pub fn set_clock(&mut self, clock: FungeClock) {
+		self.clock = clock;
+	}
+	
+	pub fn set_encoding(&mut self, encoding: CharEncoding) {
+		self.encoding = encoding;
+	}
+	
+	pub fn get_clock(&self) -> &FungeClock {
+		return &self.clock;
+	}
+	
+	pub fn set_limits(&mut self, limits: ResourceLimits) {
+		self.limits = limits;
+	}
+	
+	/// Returns the resource limit that stopped the execution,
+	/// or None if the program ended by itself.
+	pub fn get_exceeded_limit(&self) -> Option<ResourceLimit> {
+		return self.exceeded_limit;
+	}
+	
+	/// Enables recording of which cells are executed, read and written.
+	/// This disables the trace cache, so that every instruction is recorded.
+	pub fn enable_coverage(&mut self) {
+		if self.coverage.is_none() {
+			self.coverage = Some(CoverageMap::new());
+		}
+		self.trace_cache = None;
+	}
+	
+	/// Enables or disables executing hot instruction runs from the trace cache.
+	/// The behaviour of programs is the same either way.
+	pub fn set_trace_cache_enabled(&mut self, enabled: bool) {
+		self.trace_cache = if enabled && self.coverage.is_none() {Some(TraceCache::new())} else {None};
+	}
+	
+	pub fn get_coverage(&self) -> Option<&CoverageMap> {
+		return self.coverage.as_ref();
+	}
+	
+	pub fn get_source_map(&self) -> &SourceMap {
+		return &self.source_map;
+	}
+	
 	/// Starts the execution of this interpreter by transferring
 	/// control over to it's main loop.
 	/// This method does not return aslong as the interpreter
 	/// is running.
-	pub fn start_execution(&mut self) {
+	/// 
+	/// Returns an error if a resource limit stopped the execution.
+	pub fn start_execution(&mut self) -> Result<(), RsfungeError> {
+		self.start_time = Instant::now();
+		
+		// Enforce the page and stack limits where funge space and the stacks grow,
+		// so a single instruction can't allocate far beyond them
+		if let Some(max) = self.limits.max_pages {
+			self.funge_space.set_max_pages(max);
+		}
+		if let Some(max) = self.limits.max_stack_cells {
+			let max_depth = usize::try_from(max).unwrap_or(usize::MAX);
+			for i in 0..self.threads.num() {
+				self.threads.get_mut(i).unwrap().stack_stack.set_max_depth(max_depth);
+			}
+		}
+		
 		// Main execution loop
 		'mainloop:
 		while self.threads.num() > 0 {
@@ -96,36 +235,139 @@ impl<'s, 'f, N, A> FungeInterpreter<'s, 'f, N, A> where N: FungeDimension, A: Fu
 			let mut i = 0;
 			while i < self.threads.num() {
 //				let thread = self.threads.get_mut(i).unwrap();
-				
+
 				// Run thread tick
-				self.execute_thread_tick(i);
+				let outcome = self.execute_thread_tick(i);
+				
+				// Advance virtual clock
+				self.clock.tick(1);
 				
 				// Exit
 				if self.programatically_quit {
 					break 'mainloop;
 				}
 				
+				// Check resource limits
+				let exceeded_limit = match outcome {
+					ThreadTickOutcome::LimitExceeded(limit) => Some(limit),
+					_ => self.check_limits(i),
+				};
+				if let Some(limit) = exceeded_limit {
+					self.exceeded_limit = Some(limit);
+					self.exceeded_limit_location = self.threads.get_mut(i).map(|t| t.ip);
+					break 'mainloop;
+				}
+				
+				match outcome {
+					ThreadTickOutcome::Alive | ThreadTickOutcome::LimitExceeded(_) => {}
+					ThreadTickOutcome::Stopped => {
+						// Remove stopped thread, the next thread moves into the current index
+						let thread = self.threads.remove(i);
+						self.total_stack_cells -= thread.last_stack_cells;
+						self.fingerprint_states.thread_stopped(thread.id);
+						continue;
+					}
+					ThreadTickOutcome::Spawned(mut child) => {
+						if let Some(max) = self.limits.max_threads {
+							if self.threads.num() >= max {
+								self.exceeded_limit = Some(ResourceLimit::Threads(max));
+								self.exceeded_limit_location = self.threads.get_mut(i).map(|t| t.ip);
+								break 'mainloop;
+							}
+						}
+						
+						// Insert the child in front of its parent, so that it is
+						// executed for the first time before the parent is executed again
+						child.id = self.next_thread_id;
+						self.next_thread_id += 1;
+						self.total_stack_cells += child.last_stack_cells;
+						self.threads.insert(i, *child);
+						i += 1;
+					}
+				}
+				
 				// Increment index
 				i += 1;
 			}
 		}
 		
-		// Log
-		print!("Main loop terminated");
+		if let Some(limit) = self.exceeded_limit {
+			let location = self.exceeded_limit_location.map(|ip| self.source_map.describe(&ip));
+			return Err(RsfungeError::LimitExceeded(limit, location));
+		}
+		return Ok(());
+	}
+	
+	/// Checks the resource limits after the thread with the given index
+	/// executed an instruction and returns the first exceeded limit.
+	#[inline]
+	fn check_limits(&mut self, thread_index: u32) -> Option<ResourceLimit> {
+		let thread = self.threads.get_mut(thread_index).unwrap();
+		return Self::check_thread_limits(&self.limits, &mut self.executed_instructions, &mut self.total_stack_cells, &self.start_time, &self.funge_space, thread);
+	}
+	
+	/// Checks the resource limits after the given thread executed an instruction
+	/// and returns the first exceeded limit. Takes the fields it needs, so it can
+	/// also be called while the thread is borrowed, e.g. between the iterations of k.
+	#[inline]
+	fn check_thread_limits(limits: &ResourceLimits, executed_instructions: &mut u64, total_stack_cells: &mut u64, start_time: &Instant, funge_space: &FungeSpace<'s, N, V, A>, thread: &mut FungeThread<'s, 'f, V>) -> Option<ResourceLimit> {
+		*executed_instructions += 1;
+		
+		if let Some(max) = limits.max_instructions {
+			if *executed_instructions > max {
+				return Some(ResourceLimit::Instructions(max));
+			}
+		}
+		
+		// Only check the wall time every few instructions, because it's comparatively expensive
+		if let Some(max) = limits.max_wall_time {
+			if (*executed_instructions & 1023) == 0 && start_time.elapsed() > max {
+				return Some(ResourceLimit::WallTime(max));
+			}
+		}
+		
+		if let Some(max) = limits.max_pages {
+			if funge_space.num_pages() > max || funge_space.pages_exceeded() {
+				return Some(ResourceLimit::Pages(max));
+			}
+		}
+		
+		if let Some(max) = limits.max_stack_cells {
+			// Update running total of stack cells with the cells of the current thread
+			let stack_cells = thread.stack_stack.total_cells();
+			*total_stack_cells = *total_stack_cells - thread.last_stack_cells + stack_cells;
+			thread.last_stack_cells = stack_cells;
+			
+			if *total_stack_cells > max || thread.stack_stack.overflowed() {
+				return Some(ResourceLimit::StackCells(max));
+			}
+		}
+		return None;
 	}
 	
+	/// Executes a single tick of the thread with the given index.
 	#[inline]
-	fn execute_thread_tick(&mut self, thread_index: u32) {
-		let mut thread = self.threads.get_mut(thread_index).unwrap();
+	fn execute_thread_tick(&mut self, thread_index: u32) -> ThreadTickOutcome<'s, 'f, V> {
+		let thread = self.threads.get_mut(thread_index).unwrap();
+		let coverage = &mut self.coverage;
+		let space = V::from(32);
+		let mut spawned_thread = None;
 		
 		if !thread.string_mode {
+			// Continue the precompiled run the thread is in
+			if let Some(trace_cache) = &mut self.trace_cache {
+				if trace_cache.step(thread, &mut self.funge_space) {
+					return ThreadTickOutcome::Alive;
+				}
+			}
+
 //			let mut move_ip = true;
 			let mut valid_instruction = false;
 			
-			let mut instruction;
+			let mut instruction: i32;
 			while {
-				// Read instruction cell
-				instruction = self.funge_space.read_cell(&thread.ip);
+				// Read instruction cell (cells outside of the i32 range are never valid instructions)
+				instruction = self.funge_space.read_cell_cached(&thread.ip, &mut thread.page_cache).to_i32().unwrap_or(-1);
 				
 				// Pseudo-execute space and semicolon instruction
 				// Both take zero ticks. Do as long as there is still some left
@@ -134,8 +376,8 @@ impl<'s, 'f, N, A> FungeInterpreter<'s, 'f, N, A> where N: FungeDimension, A: Fu
 						// Search for next non-space instruction
 						let mut pos = thread.ip; // Copy ip
 						while {
-							pos.add_delta_wrapping(&thread.delta);
-							(self.funge_space.read_cell(&pos) == 32)
+							pos = self.funge_space.next_ip(&pos, &thread.delta);
+							(self.funge_space.read_cell_cached(&pos, &mut thread.page_cache) == space)
 						} {}
 						
 						// Set thread ip to next non-space instruction
@@ -143,15 +385,19 @@ impl<'s, 'f, N, A> FungeInterpreter<'s, 'f, N, A> where N: FungeDimension, A: Fu
 					}; true},
 					
 					/* ; */ 59 => {{
+						if let Some(c) = coverage { c.record(&thread.ip, CoverageFlags::EXECUTED); }
+						
 						// Search for next non-space instruction
 						let mut pos = thread.ip; // Copy ip
 						while {
-							pos.add_delta_wrapping(&thread.delta);
-							(self.funge_space.read_cell(&pos) != 59)
+							pos = self.funge_space.next_ip(&pos, &thread.delta);
+							(self.funge_space.read_cell_cached(&pos, &mut thread.page_cache) != V::from(59))
 						} {}
 						
+						if let Some(c) = coverage { c.record(&pos, CoverageFlags::EXECUTED); }
+						
 						// Move ip to next actual instruction
-						pos.add_delta_wrapping(&thread.delta);
+						pos = self.funge_space.next_ip(&pos, &thread.delta);
 						
 						// Set thread ip to next non-space instruction
 						thread.ip = pos;
@@ -160,140 +406,234 @@ impl<'s, 'f, N, A> FungeInterpreter<'s, 'f, N, A> where N: FungeDimension, A: Fu
 				}
 			} {}
 			
-			// Execute instruction
-			if (32 < instruction) && (instruction <= 126) {
-				// Get dims
-				const DIMS: u32 = 2; // TODO: Implement
-				
-				// Set flag
-				valid_instruction = true;
-				
-				match instruction {
-					/* ! */ 33 => insts::inst_logical_not(thread),
-					/* " */ 34 => {
-						// Enable string mode
-						thread.string_mode = true;
-					}
-					/* # */ 35 => thread.ip.add_delta_wrapping(&thread.delta),
-					/* $ */ 36 => insts::inst_pop(thread),
-					/* % */ 37 => insts::inst_remainder(thread),
-					/* & */
-					/* ' */ 39 => insts::inst_fetch_character(thread, &mut self.funge_space),
-					/* ( */ 40 => insts::inst_load_semantics(thread, DIMS, self.fingerprint_registry.borrow()),
-					/* ) */ 41 => insts::inst_unload_semantics(thread, DIMS, self.fingerprint_registry.borrow()),
-					/* * */ 42 => insts::inst_multiply(thread),
-					/* + */ 43 => insts::inst_add(thread),
-					/* , */ 44 => insts::inst_output_char(thread, &mut self.charout),
-					/* - */ 45 => insts::inst_subtract(thread),
-					/* . */ 46 => insts::inst_output_integer(thread, &mut self.charout),
-					/* / */ 47 => insts::inst_divide(thread),
-					/* -> (0...9) */
-					/* : */ 58 => insts::inst_duplicate(thread),
-					/* -> (;) */
-					/* < */ 60 => insts::inst_go_west(thread),
-					/* = */
-					/* > */ 62 => insts::inst_go_east(thread),
-					/* ? */ 63 => insts::inst_go_away(thread, DIMS),
-					/* @ */ 64 => panic!("[[Stop instruction]]"),
-					/* -> (A...Z) */
-					/* [ */ 91 => valid_instruction = insts::inst_turn_left(thread, DIMS),
-					/* \ */ 92 => insts::inst_swap(thread),
-					/* ] */ 93 => valid_instruction = insts::inst_turn_right(thread, DIMS),
-					/* ^ */ 94 => valid_instruction = insts::inst_go_north(thread, DIMS),
-					/* _ */ 95 => insts::inst_east_west_if(thread),
-					/* ` */ 96 => insts::inst_greater_than(thread),
-					/* -> (a...f) */
-					/* g */ 103 => insts::inst_get(thread, &mut self.funge_space, DIMS),
-					/* h */ 104 => valid_instruction = insts::inst_go_high(thread, DIMS),
-					/* i */
-					/* j */ 106 => insts::inst_jump_forward(thread),
-					/* k */
-					/* l */ 108 => valid_instruction = insts::inst_go_low(thread, DIMS),
-					/* m */ 109 => valid_instruction = insts::inst_high_low_if(thread, DIMS),
-					/* n */ 110 => insts::inst_clear_stack(thread),
-					/* o */
-					/* p */ 112 => insts::inst_put(thread, &mut self.funge_space, DIMS),
-					/* q */ 113 => {
-						self.programatically_quit = true;
-						self.quit_exit_code = thread.stack_stack.pop();
-					}
-					/* r */ 114 => insts::inst_reflect(thread),
-					/* s */ 115 => insts::inst_store_character(thread, &mut self.funge_space),
-					/* t */
-					/* u */ 117 => insts::inst_stack_under_stack(thread),
-					/* v */ 118 => valid_instruction = insts::inst_go_south(thread, DIMS),
-					/* w */ 119 => valid_instruction = insts::inst_compare(thread, DIMS),
-					/* x */ 120 => insts::inst_absolute_delta(thread, DIMS),
-					/* y */ 121 => insts::inst_get_sysinfo::<FungeDim2, SpaceAccessorDim2<i32>>(thread, self.env_var_string.as_slice(), self.cli_arg_string.as_slice()), // TODO: Use generics from interpreter
-					/* z */ 122 => {/* No-op */}
-					/* { */ 123 => insts::inst_begin_block(thread, DIMS),
-					/* | */ 124 => valid_instruction = insts::inst_north_south_if(thread, DIMS),
-					/* } */ 125 => insts::inst_end_block(thread, DIMS),
-					/* ~ */ 126 => insts::inst_input_character(thread, &mut self.charin),
-					
-					/* A...Z */ n @ 65..=90 => {
-						// TODO: Implement alphabet instructions properly
-						// Reflect delta
-						let d = &mut thread.delta;
-						d.set_x(-d.x());
-						d.set_y(-d.y());
-						d.set_z(-d.z());
-					}
+			// Record instruction as executed
+			if let Some(c) = coverage { c.record(&thread.ip, CoverageFlags::EXECUTED); }
+			
+			// Execute hot stack instructions from the trace cache
+			if let Some(trace_cache) = &mut self.trace_cache {
+				if TraceCache::<V>::is_traceable(instruction) && trace_cache.enter(thread, &mut self.funge_space) {
+					return ThreadTickOutcome::Alive;
+				}
+			}
+			
+			// Iterate (k) executes the next instruction a number of times at its own position
+			let mut iterations = 1;
+			if instruction == 107 {
+				(instruction, iterations) = insts::inst_iterate(thread, &self.funge_space);
+			}
+			
+			for iteration in 0..iterations {
+				// Execute instruction
+				if (32 < instruction) && (instruction <= 126) {
+					// Get dims
+					let dims = A::dimensionality();
 					
-					/* 0...9 */ n @ 48..=57 => insts::inst_push_number(thread, n - 48),
-					/* a...f */ n @ 97..=102 => insts::inst_push_number(thread, n - 97),
+					// Set flag
+					valid_instruction = true;
 					
-					_ => {
-						// Invalid (or implmented) instruction so set flag
-						valid_instruction = false
+					match instruction {
+						/* ! */ 33 => insts::inst_logical_not(thread),
+						/* " */ 34 => {
+							// Enable string mode
+							thread.string_mode = true;
+						}
+						/* # */ 35 => thread.ip = self.funge_space.next_ip(&thread.ip, &thread.delta),
+						/* $ */ 36 => insts::inst_pop(thread),
+						/* % */ 37 => insts::inst_remainder(thread),
+						/* & */ 38 => insts::inst_input_integer(thread, &mut self.charin),
+						/* ' */ 39 => {
+							insts::inst_fetch_character(thread, &mut self.funge_space);
+							if let Some(c) = coverage { c.record(&thread.ip, CoverageFlags::READ); }
+						}
+						/* ( */ 40 => insts::inst_load_semantics(thread, dims, self.fingerprint_registry.borrow(), &mut self.fingerprint_states),
+						/* ) */ 41 => insts::inst_unload_semantics(thread, dims, self.fingerprint_registry.borrow()),
+						/* * */ 42 => insts::inst_multiply(thread),
+						/* + */ 43 => insts::inst_add(thread),
+						/* , */ 44 => insts::inst_output_char(thread, &mut self.charout, self.encoding),
+						/* - */ 45 => insts::inst_subtract(thread),
+						/* . */ 46 => insts::inst_output_integer(thread, &mut self.charout),
+						/* / */ 47 => insts::inst_divide(thread),
+						/* -> (0...9) */
+						/* : */ 58 => insts::inst_duplicate(thread),
+						/* -> (;) */
+						/* < */ 60 => insts::inst_go_west(thread),
+						/* = */
+						/* > */ 62 => insts::inst_go_east(thread),
+						/* ? */ 63 => insts::inst_go_away(thread, dims, &mut self.rng),
+						/* @ */ 64 => {
+							// Stop the current thread
+							return ThreadTickOutcome::Stopped;
+						}
+						/* -> (A...Z) */
+						/* [ */ 91 => valid_instruction = insts::inst_turn_left(thread, dims),
+						/* \ */ 92 => insts::inst_swap(thread),
+						/* ] */ 93 => valid_instruction = insts::inst_turn_right(thread, dims),
+						/* ^ */ 94 => valid_instruction = insts::inst_go_north(thread, dims),
+						/* _ */ 95 => insts::inst_east_west_if(thread),
+						/* ` */ 96 => insts::inst_greater_than(thread),
+						/* -> (a...f) */
+						/* g */ 103 => {
+							let address = insts::inst_get(thread, &mut self.funge_space, dims);
+							if let Some(c) = coverage { c.record(&address, CoverageFlags::READ); }
+						}
+						/* h */ 104 => valid_instruction = insts::inst_go_high(thread, dims),
+						/* i */ 105 => insts::inst_input_file(thread, &mut self.funge_space, &mut self.source_map, dims, self.encoding),
+						/* j */ 106 => insts::inst_jump_forward(thread),
+						/* k */
+						/* l */ 108 => valid_instruction = insts::inst_go_low(thread, dims),
+						/* m */ 109 => valid_instruction = insts::inst_high_low_if(thread, dims),
+						/* n */ 110 => insts::inst_clear_stack(thread),
+						/* o */
+						/* p */ 112 => {
+							let address = insts::inst_put(thread, &mut self.funge_space, dims);
+							if let Some(c) = coverage { c.record(&address, CoverageFlags::WRITTEN); }
+							self.source_map.mark_dynamic(&address);
+						}
+						/* q */ 113 => {
+							self.programatically_quit = true;
+							self.quit_exit_code = thread.stack_stack.pop().to_i32_wrapping();
+						}
+						/* r */ 114 => insts::inst_reflect(thread),
+						/* s */ 115 => {
+							let address = insts::inst_store_character(thread, &mut self.funge_space);
+							if let Some(c) = coverage { c.record(&address, CoverageFlags::WRITTEN); }
+							self.source_map.mark_dynamic(&address);
+						}
+						/* t */ 116 => {
+							// Split into a child thread with reversed delta
+							let mut child = thread.clone();
+							insts::_reflect_delta(&mut child.delta);
+							child.ip = self.funge_space.next_ip(&child.ip, &child.delta);
+							spawned_thread = Some(child);
+						}
+						/* u */ 117 => insts::inst_stack_under_stack(thread),
+						/* v */ 118 => valid_instruction = insts::inst_go_south(thread, dims),
+						/* w */ 119 => valid_instruction = insts::inst_compare(thread, dims),
+						/* x */ 120 => insts::inst_absolute_delta(thread, dims),
+						/* y */ 121 => insts::inst_get_sysinfo::<N, V, A>(thread, &mut self.funge_space, self.env_var_string.as_slice(), self.cli_arg_string.as_slice(), &self.clock),
+						/* z */ 122 => {/* No-op */}
+						/* { */ 123 => insts::inst_begin_block(thread, dims),
+						/* | */ 124 => valid_instruction = insts::inst_north_south_if(thread, dims),
+						/* } */ 125 => insts::inst_end_block(thread, dims),
+						/* ~ */ 126 => insts::inst_input_character(thread, &mut self.charin, self.encoding),
+						
+						/* A...Z */ n @ 65..=90 => {
+							// Execute the semantics loaded by a fingerprint, reflect if there are none
+							match thread.alphabet_inst_table.find_inst((n - 65) as u32) {
+								Some(inst) => {
+									let mut context = FingerprintContext {
+										dims,
+										funge_space: &mut self.funge_space,
+										charout: &mut self.charout,
+										charin: &mut self.charin,
+										encoding: self.encoding,
+										rng: &mut self.rng,
+										clock: &self.clock,
+										states: &mut self.fingerprint_states,
+									};
+									inst(thread, &mut context);
+								}
+								None => valid_instruction = false,
+							}
+						}
+						
+						/* 0...9 */ n @ 48..=57 => insts::inst_push_number(thread, n - 48),
+						/* a...f */ n @ 97..=102 => insts::inst_push_number(thread, n - 97 + 10),
+						
+						_ => {
+							// Invalid (or implmented) instruction so set flag
+							valid_instruction = false
+						}
+					}
+				}
+				
+				// If an invalid instruction was encountered, act as reflect
+				if !valid_instruction {
+					insts::_reflect_delta(&mut thread.delta);
+				}
+				
+				// Each iteration of k counts as a tick, the last one is counted by the main loop
+				if iteration + 1 < iterations {
+					self.clock.tick(1);
+					if let Some(limit) = Self::check_thread_limits(&self.limits, &mut self.executed_instructions, &mut self.total_stack_cells, &self.start_time, &self.funge_space, thread) {
+						return ThreadTickOutcome::LimitExceeded(limit);
 					}
 				}
-			}
-			
-			// If an invalid instruction was encountered, act as reflect
-			if !valid_instruction {
-				insts::_reflect_delta(&mut thread.delta);
 			}
 			
 			// Move ip by delta
-			thread.ip.add_delta_wrapping(&thread.delta);
+			thread.ip = self.funge_space.next_ip(&thread.ip, &thread.delta);
 		}
 		else { // If in string mode
 			// Read instruction cell
-			let instruction = self.funge_space.read_cell(&thread.ip);
+			let instruction = self.funge_space.read_cell_cached(&thread.ip, &mut thread.page_cache);
 			
-			match instruction {
-				/* space */ 32 => {
+			// Record string mode characters as executed
+			if instruction != space {
+				if let Some(c) = coverage { c.record(&thread.ip, CoverageFlags::EXECUTED); }
+			}
+			
+			match instruction.to_i32() {
+				/* space */ Some(32) if !matches!(self.dialect_mode, FungeDialect::Befunge93) => {
+					// Push a run of spaces as a single space (SGML style)
+					thread.stack_stack.push(space.clone());
+					
 					// Search for next non-space instruction
 					let mut pos = thread.ip; // Copy ip
 					while {
-						pos.add_delta_wrapping(&thread.delta);
-						(self.funge_space.read_cell(&pos) == 32)
+						pos = self.funge_space.next_ip(&pos, &thread.delta);
+						(self.funge_space.read_cell_cached(&pos, &mut thread.page_cache) == space)
 					} {}
 					
 					// Set thread ip to next non-space instruction
 					thread.ip = pos;
 				}
-				/* " */ 34 => {
+				/* " */ Some(34) => {
 					// Disable string mode
 					thread.string_mode = false;
 					
 					// Advance ip
-					thread.ip.add_delta_wrapping(&thread.delta);
+					thread.ip = self.funge_space.next_ip(&thread.ip, &thread.delta);
 				}
 				_ => {
 					// Push char as value onto the stack
 					thread.stack_stack.push(instruction);
 					
 					// Advance ip
-					thread.ip.add_delta_wrapping(&thread.delta);
+					thread.ip = self.funge_space.next_ip(&thread.ip, &thread.delta);
 				}
 			}
 		}
+		
+		return match spawned_thread {
+			Some(child) => ThreadTickOutcome::Spawned(Box::new(child)),
+			None => ThreadTickOutcome::Alive,
+		};
 	}
 	
 	pub fn load_initial_code(&mut self, code: &CodeBuffer) {
-		self.load_code(code, FungeAddress::new_xyz(0, 0, 0));
+		let source = self.code_source.clone();
+		self.load_overlay(&source, code, FungeAddress::new_xyz(0, 0, 0));
+	}
+	
+	/// Loads the code of the given source file at the given position,
+	/// on top of the code that's already in funge space, and records the
+	/// placement in the source map.
+	/// 
+	/// Returns the addresses of all non-space cells that were overwritten,
+	/// each with the source location of the overwritten cell like `main.b98:1:3`.
+	pub fn load_overlay(&mut self, source: &CodeSource, code: &CodeBuffer, position: FungeAddress) -> Vec<(FungeAddress, String)> {
+		// Describe the overwritten cells before the source map points them to the new code
+		let overlaps = self.load_code(code, position).into_iter()
+			.map(|address| (address, self.source_map.describe(&address)))
+			.collect();
+		
+		self.source_map.add_placement(SourcePlacement {
+			source: source.clone(),
+			origin: position,
+			size: code.bounding_box,
+		}, code);
+		return overlaps;
 	}
 	
 	/// Loads the code from the given buffer into this interpreters
@@ -313,38 +653,34 @@ impl<'s, 'f, N, A> FungeInterpreter<'s, 'f, N, A> where N: FungeDimension, A: Fu
 	/// terminator does occur before the last line, the loading procedure
 	/// is simply stopped instead of panicing.
 	/// 
-	pub fn load_code(&mut self, code: &CodeBuffer, position: FungeAddress) {
-		let mut offset = FungeAddress::new_value(0);
+	/// Returns the addresses of all non-space cells that were overwritten.
+	pub fn load_code(&mut self, code: &CodeBuffer, position: FungeAddress) -> Vec<FungeAddress> {
+		let space = V::from(32);
+		let mut overlaps = Vec::new();
 		
-		// TODO: Handle dimensionality properly
-		
-		'line_loop:
-		for line in code.lines.iter() {
-			// Put line values into funge space
-			for (x, raw_value) in line.data.iter().enumerate() {
-				let cell_value = *raw_value as i32; // Reinterpret u32 codepoint as i32 cell value
+		for (offset, raw_value) in code.cells() {
+			let cell_value = raw_value as i32; // Reinterpret u32 codepoint as i32 cell value
+			
+			// Only overwrite cell if code cell is not a space (32)
+			if cell_value != 32 {
+				let mut address = position;
+				address.add_wrapping(&offset);
 				
-				// Only overwrite cell if code cell is not a space (32)
-				if cell_value != 32 {
-					let mut address = FungeAddress::new_xyz(x as i32 + position.x(), position.y(), position.z());
-					address.add_wrapping(&offset);
-					
-					// Write cell
-					self.funge_space.write_cell(&address, cell_value);
+				// Remember overwritten code
+				if self.funge_space.read_cell(&address) != space {
+					overlaps.push(address);
 				}
+				
+				// Write cell
+				self.funge_space.write_cell(&address, V::from(cell_value));
 			}
-			
-			// Process terminator
-			match line.terminator {
-				LineTerminator::FeedY => offset.set_y(offset.y().wrapping_add(1i32)),
-				LineTerminator::FeedZ => offset.set_z(offset.z().wrapping_add(1i32)),
-				LineTerminator::End => break 'line_loop, // Stop iteration on End terminator
-			};
 		}
+		return overlaps;
 	}
 	
 	pub fn create_thread(&mut self, ip: InstructionPointer, delta: InstructionDelta) {
-		let thread = FungeThread::new(ip, delta);
+		let thread = FungeThread::new(self.next_thread_id, ip, delta);
+		self.next_thread_id += 1;
 		self.threads.test_add(thread); // TODO: Implement this properly
 	}
 	
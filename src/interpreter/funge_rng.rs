@@ -0,0 +1,41 @@
+use rand::{Error, RngCore};
+use crate::interpreter::instruction::semantics::Pcg32;
+
+/// The pseudo random number generator of an interpreter, used by `?`
+/// and by fingerprints that need randomness.
+///
+/// Programs emitted by `rsfunge compile` use the same generator, so they
+/// take the same random paths as the interpreter with the same seed.
+pub type FungeRng = Pcg32;
+
+/// The seed used if no seed is given explicitly, so that runs
+/// are reproducible by default (e.g. in test harnesses).
+pub const DEFAULT_RNG_SEED: u64 = 0;
+
+pub fn make_funge_rng(seed: u64) -> FungeRng {
+	return Pcg32::new(seed);
+}
+
+/// Lets fingerprints use the distributions of `rand`
+impl RngCore for Pcg32 {
+	fn next_u32(&mut self) -> u32 {
+		return Pcg32::next_u32(self);
+	}
+	
+	fn next_u64(&mut self) -> u64 {
+		let low = Pcg32::next_u32(self) as u64;
+		return ((Pcg32::next_u32(self) as u64) << 32) | low;
+	}
+	
+	fn fill_bytes(&mut self, dest: &mut [u8]) {
+		for chunk in dest.chunks_mut(4) {
+			let bytes = Pcg32::next_u32(self).to_le_bytes();
+			chunk.copy_from_slice(&bytes[..chunk.len()]);
+		}
+	}
+	
+	fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+		self.fill_bytes(dest);
+		return Ok(());
+	}
+}
@@ -1,33 +1,85 @@
-use std::collections::HashMap;
-use crate::interpreter::{FungeAddress, FungeDimension, FungeSpaceAccessor, FungeValue, FungePageAddress};
+use rustc_hash::{FxHashMap, FxHashSet};
+use crate::interpreter::{FungeAddress, FungeDimension, FungeSpaceAccessor, FungeValue, FungePageAddress, InstructionPointer, InstructionDelta};
 use std::marker::PhantomData;
 
 /// Space character (32)
 const EMPTY_CELL_VALUE: i32 = 32;
 
+/// Number of pages per axis in the direct-indexed page table
+const PAGE_TABLE_WIDTH: i32 = 16;
+/// Page coordinate that is stored at index 0 of each page table axis,
+/// so the table also covers a few pages left of and above the origin
+const PAGE_TABLE_ORIGIN: i32 = -4;
+
+/// Page index of absent pages
+const NO_PAGE: u32 = u32::MAX;
+
+/// Funge space made of pages of cells that are allocated on first write.
+///
+/// Pages near the origin, where almost all code lives, are found through
+/// a direct-indexed page table. All other pages are found through a hash map.
+/// Pages are never freed, so page indices stay valid and can be cached
+/// by threads (see `FungePageCache`).
 pub struct FungeSpace<'s, N, V, A> where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
-	page_map: HashMap<FungeAddress, FungeSpacePage<'s, N, V, A>>,
+	pages: Vec<FungeSpacePage<'s, N, V, A>>,
+	/// Page address of each page in `pages`
+	page_addresses: Vec<FungePageAddress>,
+	page_table: Vec<u32>,
+	page_map: FxHashMap<FungePageAddress, u32>,
+	
+	/// Bounding box of all non-space cells ever written,
+	/// only valid if the space isn't empty
+	least_point: FungeAddress,
+	greatest_point: FungeAddress,
+	empty: bool,
+	/// A space was written onto the edge of the bounds, so they might be too large
+	bounds_loose: bool,
+	
+	/// Cells whose writes are reported by `take_touched_cells`
+	watched_cells: FxHashSet<FungeAddress>,
+	touched_cells: Vec<FungeAddress>,
+	/// The bounds changed since the last call of `take_bounds_changed`
+	bounds_changed: bool,
+	
+	/// Maximum number of pages, writes that would allocate more pages are dropped
+	max_pages: u32,
+	/// A write was dropped because it would have exceeded `max_pages`
+	pages_exceeded: bool,
+	
 	_unused: PhantomData<(&'s u8, N)>,
 }
 impl<'s, N, V, A> FungeSpace<'s, N, V, A> where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
 	pub fn new() -> Self {
+		let table_size = (PAGE_TABLE_WIDTH as usize).pow(A::dimensionality());
+		
 		FungeSpace {
-			page_map: HashMap::with_capacity(128),
+			pages: Vec::with_capacity(128),
+			page_addresses: Vec::with_capacity(128),
+			page_table: vec![NO_PAGE; table_size],
+			page_map: FxHashMap::default(),
+			least_point: FungeAddress::new_value(0),
+			greatest_point: FungeAddress::new_value(0),
+			empty: true,
+			bounds_loose: false,
+			watched_cells: FxHashSet::default(),
+			touched_cells: Vec::new(),
+			bounds_changed: false,
+			max_pages: u32::MAX,
+			pages_exceeded: false,
 			_unused: PhantomData,
 		}
 	}
 	
-	pub fn read_cell(&mut self, address: &FungeAddress) -> V {
+	pub fn read_cell(&self, address: &FungeAddress) -> V {
 		// Try get page at address
 		let page_address = A::make_page_address(address);
-		let page = self.get_page_maybe(&page_address);
 		
-		if let Some(page) = page {
+		if let Some(page_index) = self.find_page(&page_address) {
 			// Localize page address
 			let local_address = A::localize_address(address);
 			
 			// Read value from page
-			return page.read_cell(&local_address);
+			return self.pages[page_index as usize].read_cell(&local_address);
 		}
 		else {
 			// Return empty value if page absent
@@ -35,35 +87,304 @@ impl<'s, N, V, A> FungeSpace<'s, N, V, A> where N: FungeDimension, V: FungeValue
 		}
 	}
 	
+	/// Reads a cell like `read_cell`, but looks up the page in the given
+	/// cache first. Consecutive reads from the same page never touch
+	/// the page table or page map.
+	#[inline]
+	pub fn read_cell_cached(&self, address: &FungeAddress, cache: &mut FungePageCache) -> V {
+		let page_address = A::make_page_address(address);
+		
+		// Update cache on miss, absent pages are not cached because they might be created later
+		if cache.page_index == NO_PAGE || cache.page_address != page_address {
+			match self.find_page(&page_address) {
+				Some(page_index) => {
+					cache.page_address = page_address;
+					cache.page_index = page_index;
+				}
+				None => return V::from(EMPTY_CELL_VALUE),
+			}
+		}
+		
+		let local_address = A::localize_address(address);
+		return self.pages[cache.page_index as usize].read_cell(&local_address);
+	}
+	
 	pub fn write_cell(&mut self, address: &FungeAddress, value: V) {
 		// Get page (create if necessary)
 		let page_address = A::make_page_address(address);
-		let page = self.get_page_or_create(&page_address);
+		let page_index = match self.get_page_or_create(&page_address) {
+			Some(page_index) => page_index,
+			None => {
+				self.pages_exceeded = true;
+				return;
+			}
+		};
+		
+		// Extend bounds to non-space cells, spaces on the edge might shrink them
+		if value != V::from(EMPTY_CELL_VALUE) {
+			self.extend_bounds(address);
+		}
+		else if !self.empty && self.is_on_edge(address) {
+			self.bounds_loose = true;
+		}
+		
+		// Report writes to watched cells
+		if !self.watched_cells.is_empty() && self.watched_cells.contains(address) {
+			self.touched_cells.push(*address);
+		}
 		
 		// Localize address
 		let local_address = A::localize_address(address); // TODO: This might be sped up because we already have the page address, because we already have the page address
 		
 		// Write cell to page
-		page.write_cell(&local_address, value);
+		self.pages[page_index as usize].write_cell(&local_address, value);
 	}
 	
+	/// Returns the position of an ip after moving it from the given position by delta.
+	///
+	/// If the new position lies outside of the bounds of the space, the ip wraps
+	/// around to the other side of the space like in Lahey-space. It travels backwards
+	/// to the last cell on its line that is still within bounds. An ip outside of the bounds
+	/// whose line crosses the bounds moves directly to the first cell within bounds.
+	pub fn next_ip(&self, ip: &InstructionPointer, delta: &InstructionDelta) -> InstructionPointer {
+		let mut next = *ip;
+		next.add_delta_wrapping(delta);
+		if self.empty {
+			return next;
+		}
+		
+		// Find the range of k for which ip - k * delta lies within bounds
+		let mut in_bounds = true;
+		let mut k_min = i64::MIN;
+		let mut k_max = i64::MAX;
+		for axis in 0..3 {
+			let p = ip.elements[axis] as i64;
+			let d = delta.elements[axis] as i64;
+			let least = self.least_point.elements[axis] as i64;
+			let greatest = self.greatest_point.elements[axis] as i64;
+			
+			in_bounds &= (least <= p + d) && (p + d <= greatest);
+			
+			if d > 0 {
+				k_min = k_min.max(-(greatest - p).div_euclid(d));
+				k_max = k_max.min((p - least).div_euclid(d));
+			}
+			else if d < 0 {
+				k_min = k_min.max(-(p - least).div_euclid(-d));
+				k_max = k_max.min((greatest - p).div_euclid(-d));
+			}
+			else if (p < least) || (p > greatest) {
+				// Line never crosses the bounds
+				k_min = 1;
+				k_max = 0;
+			}
+		}
+		
+		// Stay on the straight line if within bounds or if it never crosses them
+		if in_bounds || (k_min > k_max) {
+			return next;
+		}
+		
+		// Wrap to the farthest cell within bounds behind the ip
+		for axis in 0..3 {
+			next.elements[axis] = (ip.elements[axis] as i64 - k_max * delta.elements[axis] as i64) as i32;
+		}
+		return next;
+	}
+	
+	/// Reports future writes to the given cell through `take_touched_cells`.
+	pub fn watch_cell(&mut self, address: &FungeAddress) {
+		self.watched_cells.insert(*address);
+	}
+	
+	pub fn unwatch_cell(&mut self, address: &FungeAddress) {
+		self.watched_cells.remove(address);
+	}
+	
+	/// Checks if a watched cell was written or the bounds grew since
+	/// the touched cells and the bounds change were last taken.
 	#[inline]
-	pub fn get_page_or_create(&mut self, page_address: &FungePageAddress) -> &mut FungeSpacePage<'s, N, V, A> {
-		let map = &mut self.page_map;
+	pub fn has_changes(&self) -> bool {
+		return !self.touched_cells.is_empty() || self.bounds_changed;
+	}
+	
+	/// Returns all watched cells that were written since the last call.
+	pub fn take_touched_cells(&mut self) -> Vec<FungeAddress> {
+		return std::mem::take(&mut self.touched_cells);
+	}
+	
+	/// Checks if the bounds grew since the last call, which changes where ips wrap around.
+	pub fn take_bounds_changed(&mut self) -> bool {
+		return std::mem::replace(&mut self.bounds_changed, false);
+	}
+	
+	/// Returns the number of allocated pages.
+	#[inline]
+	pub fn num_pages(&self) -> u32 {
+		return self.pages.len() as u32;
+	}
+	
+	/// Limits the number of pages. Writes that would allocate a page
+	/// beyond the limit are dropped and reported by `pages_exceeded`.
+	pub fn set_max_pages(&mut self, max_pages: u32) {
+		self.max_pages = max_pages;
+	}
+	
+	/// Returns whether a write was dropped because of the page limit.
+	#[inline]
+	pub fn pages_exceeded(&self) -> bool {
+		return self.pages_exceeded;
+	}
+	
+	/// Returns the least and greatest point of all non-space cells ever written, or None if there are none.
+	/// The bounds only shrink to the cells that still aren't spaces in `shrink_bounds`.
+	pub fn bounds(&self) -> Option<(FungeAddress, FungeAddress)> {
+		if self.empty {
+			return None;
+		}
+		return Some((self.least_point, self.greatest_point));
+	}
+	
+	/// Shrinks the bounds to the non-space cells in funge space, if spaces
+	/// were written onto their edges. This searches all pages, so it's
+	/// only done when the exact bounds are needed (by `y`).
+	pub fn shrink_bounds(&mut self) {
+		if !self.bounds_loose {
+			return;
+		}
+		self.bounds_loose = false;
+		self.bounds_changed = true;
+		self.empty = true;
 		
-		let page = map.entry(*page_address).or_insert_with(|| {
-			let page = FungeSpacePage::<N, V, A>::new();
-			return page;
+		let page_width = A::get_page_width() as usize;
+		let space = V::from(EMPTY_CELL_VALUE);
+		let pages = std::mem::take(&mut self.pages);
+		for (page_index, page) in pages.iter().enumerate() {
+			let page_address = self.page_addresses[page_index];
+			for (linear_index, value) in page.data.iter().enumerate() {
+				if *value == space {
+					continue;
+				}
+				
+				// Invert the linear index of the cell and add the origin of the page
+				let local = [linear_index % page_width, (linear_index / page_width) % page_width, linear_index / (page_width * page_width)];
+				let mut address = FungeAddress::new_value(0);
+				for (axis, local_coord) in local.iter().enumerate().take(A::dimensionality() as usize) {
+					address.elements[axis] = page_address.elements[axis] * page_width as i32 + *local_coord as i32;
+				}
+				self.extend_bounds(&address);
+			}
+		}
+		self.pages = pages;
+	}
+	
+	/// Returns whether the address lies on a face of the bounding box.
+	fn is_on_edge(&self, address: &FungeAddress) -> bool {
+		return (0..3).any(|axis| {
+			address.elements[axis] == self.least_point.elements[axis] || address.elements[axis] == self.greatest_point.elements[axis]
 		});
-		return page;
+	}
+	
+	fn extend_bounds(&mut self, address: &FungeAddress) {
+		if self.empty {
+			self.least_point = *address;
+			self.greatest_point = *address;
+			self.empty = false;
+			self.bounds_changed = true;
+			return;
+		}
+		
+		for axis in 0..3 {
+			let coord = address.elements[axis];
+			if coord < self.least_point.elements[axis] {
+				self.least_point.elements[axis] = coord;
+				self.bounds_changed = true;
+			}
+			if coord > self.greatest_point.elements[axis] {
+				self.greatest_point.elements[axis] = coord;
+				self.bounds_changed = true;
+			}
+		}
+	}
+	
+	/// Returns the index of the given page in the page table, or None
+	/// if the page lies outside of the area covered by the table.
+	#[inline]
+	fn page_table_index(page_address: &FungePageAddress) -> Option<usize> {
+		let mut index = 0usize;
+		for axis in (0..A::dimensionality() as usize).rev() {
+			let coord = page_address.elements[axis] as i64 - PAGE_TABLE_ORIGIN as i64;
+			if (coord < 0) || (coord >= PAGE_TABLE_WIDTH as i64) {
+				return None;
+			}
+			index = index * (PAGE_TABLE_WIDTH as usize) + (coord as usize);
+		}
+		return Some(index);
+	}
+	
+	#[inline]
+	fn find_page(&self, page_address: &FungePageAddress) -> Option<u32> {
+		let page_index = match Self::page_table_index(page_address) {
+			Some(table_index) => self.page_table[table_index],
+			None => *self.page_map.get(page_address)?,
+		};
+		
+		if page_index == NO_PAGE {
+			return None;
+		}
+		return Some(page_index);
 	}
 	
 	#[inline]
-	pub fn get_page_maybe(&mut self, page_address: &FungePageAddress) -> Option<&mut FungeSpacePage<'s, N, V, A>> {
-		let map = &mut self.page_map;
+	fn get_page_or_create(&mut self, page_address: &FungePageAddress) -> Option<u32> {
+		if let Some(page_index) = self.find_page(page_address) {
+			return Some(page_index);
+		}
+		if self.num_pages() >= self.max_pages {
+			return None;
+		}
+		
+		// Allocate new page
+		let page_index = self.pages.len() as u32;
+		self.pages.push(FungeSpacePage::<N, V, A>::new());
+		self.page_addresses.push(*page_address);
 		
-		let page = map.get_mut(&page_address);
-		return page;
+		match Self::page_table_index(page_address) {
+			Some(table_index) => self.page_table[table_index] = page_index,
+			None => {
+				self.page_map.insert(*page_address, page_index);
+			}
+		}
+		return Some(page_index);
+	}
+}
+
+impl<'s, N, V, A> Default for FungeSpace<'s, N, V, A> where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
+
+/// Remembers the last page a thread read from, so reading
+/// cells within the same page skips the page lookup.
+#[derive(Copy, Clone)]
+pub struct FungePageCache {
+	page_address: FungePageAddress,
+	page_index: u32,
+}
+
+impl FungePageCache {
+	pub fn new() -> Self {
+		FungePageCache {
+			page_address: FungePageAddress::new_value(0),
+			page_index: NO_PAGE,
+		}
+	}
+}
+
+impl Default for FungePageCache {
+	fn default() -> Self {
+		return Self::new();
 	}
 }
 
@@ -84,7 +405,7 @@ impl<'s, N, V, A> FungeSpacePage<'s, N, V, A> where N: FungeDimension, V: FungeV
 	
 	pub fn read_cell(&self, local_address: &FungeAddress) -> V {
 		let linear_index = A::address_to_page_linear_index(local_address);
-		return self.data[linear_index];
+		return self.data[linear_index].clone();
 	}
 	
 	pub fn write_cell(&mut self, local_address: &FungeAddress, value: V) {
@@ -93,6 +414,12 @@ impl<'s, N, V, A> FungeSpacePage<'s, N, V, A> where N: FungeDimension, V: FungeV
 	}
 }
 
+impl<'s, N, V, A> Default for FungeSpacePage<'s, N, V, A> where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
+
 //pub trait SpacePageAccess<N, V> where N: FungeDimension, V: FungeValue {
 //	fn get_page_capacity() -> u32;
 //}
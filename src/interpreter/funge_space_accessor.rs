@@ -93,7 +93,7 @@ impl<V> FungeSpaceAccessor<FungeDim3, V> for SpaceAccessorDim3<V> where V: Funge
 		let mut local = FungeAddress::new();
 		local.set_x(address.x().rem_euclid(page_width));
 		local.set_y(address.y().rem_euclid(page_width));
-		local.set_y(address.z().rem_euclid(page_width));
+		local.set_z(address.z().rem_euclid(page_width));
 		return local;
 	}
 	
@@ -1,13 +1,33 @@
 use crate::interpreter::FungeValue;
 
+#[derive(Clone)]
 pub struct FungeStack<V: FungeValue = i32> {
 	data: Vec<V>,
+	/// Maximum number of cells, pushes onto a full stack are dropped
+	max_depth: usize,
+	/// A push was dropped because the stack was full
+	overflowed: bool,
 }
 
 impl<V: FungeValue> FungeStack<V> {
 	#[inline]
 	pub fn push(&mut self, value: V) {
-		self.data.push(value);
+		if self.data.len() < self.max_depth {
+			self.data.push(value);
+		}
+		else {
+			self.overflowed = true;
+		}
+	}
+	
+	/// Pushes |count| zeroes. If they don't fit, none are pushed
+	/// and the stack is marked as overflowed.
+	pub fn push_zeroes(&mut self, count: u32) {
+		if count as usize > self.max_depth - self.data.len() {
+			self.overflowed = true;
+			return;
+		}
+		self.data.resize(self.data.len() + count as usize, V::default());
 	}
 	
 	#[inline]
@@ -43,6 +63,18 @@ impl<V: FungeValue> FungeStack<V> {
 		self.data.len() as u32
 	}
 	
+	/// Limits the number of cells on this stack. Pushes beyond
+	/// the limit are dropped and reported by `overflowed`.
+	pub fn set_max_depth(&mut self, max_depth: usize) {
+		self.max_depth = max_depth;
+	}
+	
+	/// Returns whether a push was dropped because the stack was full.
+	#[inline]
+	pub fn overflowed(&self) -> bool {
+		return self.overflowed;
+	}
+	
 	/// Transfers |count| cells from this stacks top in non-reverse order
 	/// to the given other stack. If this stack has less elements that |count|
 	/// then the transferred cells will be at the top of the other stack and
@@ -52,13 +84,17 @@ impl<V: FungeValue> FungeStack<V> {
 		let real_transfer_count = std::cmp::min(self.depth(), count);
 		let zeroes_count = count - real_transfer_count;
 		
+		// Don't allocate anything if the cells don't fit into the other stack
+		if count as usize > other.max_depth - other.data.len() {
+			other.overflowed = true;
+			return;
+		}
+		
 		// Reserve capacity in other stack
 		other.data.reserve(count as usize);
 		
 		// Push zeroes if needed
-		for _ in 0..zeroes_count {
-			other.push(V::default());
-		}
+		other.push_zeroes(zeroes_count);
 		
 		// Copy cells from this stack to other
 		let transfer_start_pos = self.data.len() - real_transfer_count as usize;
@@ -71,6 +107,14 @@ impl<V: FungeValue> FungeStack<V> {
 	pub fn new() -> Self {
 		return FungeStack {
 			data: Vec::new(),
+			max_depth: usize::MAX,
+			overflowed: false,
 		}
 	}
 }
+
+impl<V: FungeValue> Default for FungeStack<V> {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
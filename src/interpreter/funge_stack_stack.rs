@@ -3,8 +3,11 @@ use crate::interpreter::{FungeValue, FungeStack};
 /// A stack of funge stacks, making it the stack stack.
 /// A stack stack always contains atleast one stack.
 /// NOTE: The above is important!
+#[derive(Clone)]
 pub struct FungeStackStack<V: FungeValue = i32> {
 	data: Vec<FungeStack<V>>,
+	/// Maximum number of cells on each stack
+	max_depth: usize,
 }
 
 impl<V: FungeValue> FungeStackStack<V> {
@@ -84,12 +87,45 @@ impl<V: FungeValue> FungeStackStack<V> {
 		self.data.len() as u32
 	}
 	
+	/// Returns a new empty stack with the cell limit of this stack stack.
+	pub fn new_stack(&self) -> FungeStack<V> {
+		let mut stack = FungeStack::new();
+		stack.set_max_depth(self.max_depth);
+		return stack;
+	}
+	
+	/// Limits the number of cells on each stack of this stack stack,
+	/// including the stacks created later by `new_stack`.
+	pub fn set_max_depth(&mut self, max_depth: usize) {
+		self.max_depth = max_depth;
+		for stack in self.data.iter_mut() {
+			stack.set_max_depth(max_depth);
+		}
+	}
+	
+	/// Returns whether a push onto any of the stacks was dropped because it was full.
+	pub fn overflowed(&self) -> bool {
+		return self.data.iter().any(|s| s.overflowed());
+	}
+	
+	/// Returns the number of cells on all stacks of this stack stack.
+	pub fn total_cells(&self) -> u64 {
+		return self.data.iter().map(|s| s.depth() as u64).sum();
+	}
+	
 	pub fn new() -> Self {
 		let mut data = Vec::with_capacity(8);
 		data.push(FungeStack::new());
 		
 		return FungeStackStack {
 			data,
+			max_depth: usize::MAX,
 		};
 	}
 }
+
+impl<V: FungeValue> Default for FungeStackStack<V> {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
@@ -1,32 +1,46 @@
-use crate::interpreter::{InstructionPointer, InstructionDelta, FungeAddress, FungeStackStack};
+use crate::interpreter::{InstructionPointer, InstructionDelta, FungeAddress, FungeStackStack, FungeValue, FungePageCache, TraceCursor};
 use std::marker::PhantomData;
 use crate::interpreter::instruction::AlphabetInstructionTable;
 
-pub struct FungeThread<'s, 'f> {
+#[derive(Clone)]
+pub struct FungeThread<'s, 'f, V: FungeValue = i32> {
+	/// Locally unique id of this thread
+	pub id: u32,
+	
 	pub ip: InstructionPointer,
 	pub delta: InstructionDelta,
 	
-	pub stack_stack: FungeStackStack,
+	pub stack_stack: FungeStackStack<V>,
 	
-	pub alphabet_inst_table: AlphabetInstructionTable<'f>,
+	pub alphabet_inst_table: AlphabetInstructionTable<'f, V>,
 	
 	pub stroage_offset: FungeAddress,
 	pub string_mode: bool,
 	
-	_phantom: PhantomData<(&'s u8)>,
+	/// The number of stack cells this thread had after its last tick
+	pub last_stack_cells: u64,
 	
-//	pub page_key_cache: FungeSpacePage,
+	/// The funge space page this thread last read an instruction from
+	pub page_cache: FungePageCache,
+	/// The position within the precompiled run this thread is executing, if any
+	pub trace_cursor: Option<TraceCursor>,
+	
+	_phantom: PhantomData<(&'s u8)>,
 }
 
-impl<'s, 'f> FungeThread<'s, 'f> {
-	pub fn new(ip: InstructionPointer, delta: InstructionDelta) -> Self {
+impl<'s, 'f, V: FungeValue> FungeThread<'s, 'f, V> {
+	pub fn new(id: u32, ip: InstructionPointer, delta: InstructionDelta) -> Self {
 		FungeThread {
+			id,
 			ip,
 			delta,
 			stack_stack: FungeStackStack::new(),
 			alphabet_inst_table: AlphabetInstructionTable::new(),
 			stroage_offset: FungeAddress::new_value(0),
 			string_mode: false,
+			last_stack_cells: 0,
+			page_cache: FungePageCache::new(),
+			trace_cursor: None,
 			_phantom: PhantomData,
 		}
 	}
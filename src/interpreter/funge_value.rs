@@ -1,5 +1,250 @@
+use std::fmt::Display;
+use num_bigint::BigInt;
+use num_traits::{Zero, ToPrimitive, FromPrimitive};
+use crate::interpreter::instruction::semantics::CellDivision;
 
 /// A value that can be stored in a funge cell.
-pub trait FungeValue: Copy + Clone + Default + From<i32> {}
+/// 
+/// Funge space coordinates are always 32-bit, so cells used as
+/// vector components are truncated (wrapping) to 32 bits.
+pub trait FungeValue: Clone + Default + PartialEq + PartialOrd + Display + From<i32> + 'static {
+	/// The number of bytes per cell as reported by sysinfo (`y`),
+	/// or 0 if the cells have arbitrary precision.
+	fn cell_bytes() -> i32;
+	
+	fn wrapping_add(&self, other: &Self) -> Self;
+	
+	fn wrapping_sub(&self, other: &Self) -> Self;
+	
+	fn wrapping_mul(&self, other: &Self) -> Self;
+	
+	/// Divides this value by the other value, truncating towards zero.
+	/// As per spec a division by zero results in zero.
+	fn wrapping_div(&self, other: &Self) -> Self;
+	
+	/// Returns the remainder of the division of this value by the other value.
+	/// As per spec a division by zero results in zero.
+	fn wrapping_rem(&self, other: &Self) -> Self;
+	
+	/// Bitwise and, on the two's complement representation.
+	fn bit_and(&self, other: &Self) -> Self;
+	
+	/// Bitwise or, on the two's complement representation.
+	fn bit_or(&self, other: &Self) -> Self;
+	
+	/// Bitwise exclusive or, on the two's complement representation.
+	fn bit_xor(&self, other: &Self) -> Self;
+	
+	/// Bitwise not, on the two's complement representation.
+	fn bit_not(&self) -> Self;
+	
+	/// Arithmetic shift by the given number of bits, to the left if
+	/// positive and to the right (rounding down) if negative.
+	fn shift(&self, count: i32) -> Self;
+	
+	fn is_zero(&self) -> bool;
+	
+	/// Converts this value to an i32 or returns None if it is out of range.
+	fn to_i32(&self) -> Option<i32>;
+	
+	/// Converts this value to an i32, keeping only the lowest 32 bits.
+	fn to_i32_wrapping(&self) -> i32;
+	
+	/// Converts an i64 to a value, saturating at the limits of the cell.
+	fn from_i64_saturating(value: i64) -> Self;
+	
+	/// Converts a float to a value, truncating towards zero and saturating
+	/// at the limits of the cell (NaN is 0).
+	fn from_f64_saturating(value: f64) -> Self;
+	
+	/// Appends a decimal digit to this value (`self * 10 + digit`) or
+	/// returns None if the result would overflow.
+	fn checked_push_digit(&self, digit: u32) -> Option<Self>;
+}
 
-impl<T> FungeValue for T where T: Copy + Clone + Default + From<i32> {}
+macro_rules! impl_primitive_funge_value {
+	($t:ty) => {
+		impl FungeValue for $t {
+			#[inline(always)]
+			fn cell_bytes() -> i32 {
+				std::mem::size_of::<$t>() as i32
+			}
+			
+			#[inline(always)]
+			fn wrapping_add(&self, other: &Self) -> Self {
+				<$t>::wrapping_add(*self, *other)
+			}
+			
+			#[inline(always)]
+			fn wrapping_sub(&self, other: &Self) -> Self {
+				<$t>::wrapping_sub(*self, *other)
+			}
+			
+			#[inline(always)]
+			fn wrapping_mul(&self, other: &Self) -> Self {
+				<$t>::wrapping_mul(*self, *other)
+			}
+			
+			#[inline(always)]
+			fn wrapping_div(&self, other: &Self) -> Self {
+				self.div_or_zero(*other)
+			}
+			
+			#[inline(always)]
+			fn wrapping_rem(&self, other: &Self) -> Self {
+				self.rem_or_zero(*other)
+			}
+			
+			#[inline(always)]
+			fn bit_and(&self, other: &Self) -> Self {
+				*self & *other
+			}
+			
+			#[inline(always)]
+			fn bit_or(&self, other: &Self) -> Self {
+				*self | *other
+			}
+			
+			#[inline(always)]
+			fn bit_xor(&self, other: &Self) -> Self {
+				*self ^ *other
+			}
+			
+			#[inline(always)]
+			fn bit_not(&self) -> Self {
+				!*self
+			}
+			
+			#[inline]
+			fn shift(&self, count: i32) -> Self {
+				// Bits shifted out are lost, the sign fills up from the left
+				let bits = <$t>::BITS;
+				if count >= 0 {
+					if count as u32 >= bits { 0 } else { *self << count }
+				}
+				else {
+					*self >> count.unsigned_abs().min(bits - 1)
+				}
+			}
+			
+			#[inline(always)]
+			fn is_zero(&self) -> bool {
+				*self == 0
+			}
+			
+			#[inline(always)]
+			fn to_i32(&self) -> Option<i32> {
+				std::convert::TryFrom::try_from(*self).ok()
+			}
+			
+			#[inline(always)]
+			fn to_i32_wrapping(&self) -> i32 {
+				*self as i32
+			}
+			
+			#[inline]
+			fn from_i64_saturating(value: i64) -> Self {
+				std::convert::TryFrom::try_from(value).unwrap_or(if value < 0 { <$t>::MIN } else { <$t>::MAX })
+			}
+			
+			#[inline(always)]
+			fn from_f64_saturating(value: f64) -> Self {
+				value as $t
+			}
+			
+			#[inline]
+			fn checked_push_digit(&self, digit: u32) -> Option<Self> {
+				self.checked_mul(10)?.checked_add(digit as $t)
+			}
+		}
+	};
+}
+
+impl_primitive_funge_value!(i32);
+impl_primitive_funge_value!(i64);
+impl_primitive_funge_value!(i128);
+
+/// Arbitrary precision cells
+impl FungeValue for BigInt {
+	#[inline(always)]
+	fn cell_bytes() -> i32 {
+		0
+	}
+	
+	fn wrapping_add(&self, other: &Self) -> Self {
+		self + other
+	}
+	
+	fn wrapping_sub(&self, other: &Self) -> Self {
+		self - other
+	}
+	
+	fn wrapping_mul(&self, other: &Self) -> Self {
+		self * other
+	}
+	
+	fn wrapping_div(&self, other: &Self) -> Self {
+		if Zero::is_zero(other) { BigInt::zero() } else { self / other }
+	}
+	
+	fn wrapping_rem(&self, other: &Self) -> Self {
+		if Zero::is_zero(other) { BigInt::zero() } else { self % other }
+	}
+	
+	fn bit_and(&self, other: &Self) -> Self {
+		self & other
+	}
+	
+	fn bit_or(&self, other: &Self) -> Self {
+		self | other
+	}
+	
+	fn bit_xor(&self, other: &Self) -> Self {
+		self ^ other
+	}
+	
+	fn bit_not(&self) -> Self {
+		!self
+	}
+	
+	fn shift(&self, count: i32) -> Self {
+		if count >= 0 {
+			return self << count as usize;
+		}
+		return self >> count.unsigned_abs() as usize;
+	}
+	
+	fn is_zero(&self) -> bool {
+		Zero::is_zero(self)
+	}
+	
+	fn to_i32(&self) -> Option<i32> {
+		ToPrimitive::to_i32(self)
+	}
+	
+	fn to_i32_wrapping(&self) -> i32 {
+		// Take the lowest 32 bits of the two's complement representation
+		let modulus = BigInt::from(1u64 << 32);
+		let mut low = self % &modulus;
+		if low < BigInt::zero() {
+			low += &modulus;
+		}
+		return low.to_u32().unwrap() as i32;
+	}
+	
+	fn from_i64_saturating(value: i64) -> Self {
+		BigInt::from(value)
+	}
+	
+	fn from_f64_saturating(value: f64) -> Self {
+		// Big cells have no limits, infinity saturates at the largest finite floats
+		if value.is_nan() {
+			return BigInt::zero();
+		}
+		return BigInt::from_f64(value.clamp(f64::MIN, f64::MAX)).unwrap();
+	}
+	
+	fn checked_push_digit(&self, digit: u32) -> Option<Self> {
+		Some(self * 10 + digit)
+	}
+}
@@ -1,76 +1,59 @@
 use crate::interpreter::fingerprint::{Fingerprint, FingerprintInstFunction};
 use std::rc::Rc;
+use crate::interpreter::FungeValue;
 
-pub struct AlphabetInstructionTable<'f> {
-	entries: Vec<AlphabetInstructionTableEntry<'f>>,
-	
-	// TODO: Change this whole inst table cache logic to make it actually good
-	cached_inst_table: [Option<&'f FingerprintInstFunction>; 26],
+/// The semantics of the instructions A to Z of a thread.
+///
+/// Each letter has its own stack of semantics, so loading a fingerprint
+/// pushes onto the stacks of the letters it defines and unloading
+/// a fingerprint pops from them, uncovering earlier semantics.
+#[derive(Clone)]
+pub struct AlphabetInstructionTable<'f, V: FungeValue = i32> {
+	stacks: [Vec<&'f FingerprintInstFunction<V>>; 26],
 }
 
-impl<'f> AlphabetInstructionTable<'f> {
+impl<'f, V: FungeValue> AlphabetInstructionTable<'f, V> {
 	pub fn new() -> Self {
 		AlphabetInstructionTable {
-			entries: Vec::with_capacity(16),
-			cached_inst_table: [None; 26],
+			stacks: Default::default(),
 		}
 	}
 	
-	pub fn push_fingerprint(&mut self, fingerprint: &'_ Rc<dyn Fingerprint<'f>>) {
-		// Get instructions
-		let mut inst_array: [Option<&FingerprintInstFunction>; 26] = [None; 26];
+	/// Overloads the letters the fingerprint defines with its instructions.
+	pub fn push_fingerprint(&mut self, fingerprint: &'_ Rc<dyn Fingerprint<'f, V>>) {
+		let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
 		fingerprint.get_alphabet_instructions(&mut inst_array);
 		
-		// Make entry
-		let entry = AlphabetInstructionTableEntry {
-			fingerprint: Rc::clone(fingerprint),
-			inst_array,
-		};
-		
-		// Update inst table cache
-		for (i, f) in inst_array.iter().enumerate() {
-			match f {
-				Some(inst) => {
-					self.cached_inst_table[i] = Some(*inst);
-				}
-				None => {}
+		for (stack, inst) in self.stacks.iter_mut().zip(inst_array.iter()) {
+			if let Some(inst) = inst {
+				stack.push(*inst);
 			}
 		}
-		
-		// Push fingerprint onto fingerprint stack
-		self.entries.push(entry);
 	}
 	
-	pub fn pop_fingerprint(&mut self) -> Option<Rc<dyn Fingerprint<'f>>> {
-		// Pop fingerprint // TODO: Improve this
-		if let Some(e) = self.entries.pop() {
-			// Unoverload fingerprint instructions
-			for i in 0..26 {
-				if e.inst_array[i].is_some() {
-					'search_loop:
-					for j in (0..(self.entries.len() - 1)).rev() {
-						if self.entries[j].inst_array[i].is_some() {
-							self.cached_inst_table[i] = self.entries[j].inst_array[i];
-							break 'search_loop;
-						}
-					}
-				}
+	/// Removes the top semantics of each letter the fingerprint defines,
+	/// even if they were loaded by another fingerprint.
+	pub fn pop_fingerprint(&mut self, fingerprint: &'_ Rc<dyn Fingerprint<'f, V>>) {
+		let mut inst_array: [Option<&FingerprintInstFunction<V>>; 26] = [None; 26];
+		fingerprint.get_alphabet_instructions(&mut inst_array);
+		
+		for (stack, inst) in self.stacks.iter_mut().zip(inst_array.iter()) {
+			if inst.is_some() {
+				stack.pop();
 			}
-			
-			return Some(e.fingerprint);
-		}
-		else {
-			return None;
 		}
 	}
 	
-	pub fn find_inst(&self, index: u32) -> Option<&'f FingerprintInstFunction> {
-		*self.cached_inst_table.get(index as usize).unwrap()
+	/// Returns the current semantics of the letter with the given index (0 for A),
+	/// or None if the letter has none and reflects.
+	#[inline]
+	pub fn find_inst(&self, index: u32) -> Option<&'f FingerprintInstFunction<V>> {
+		return self.stacks.get(index as usize).and_then(|stack| stack.last().copied());
 	}
 }
 
-pub struct AlphabetInstructionTableEntry<'f> {
-	pub fingerprint: Rc<dyn Fingerprint<'f>>,
-	pub inst_array: [Option<&'f FingerprintInstFunction>; 26],
-	
+impl<'f, V: FungeValue> Default for AlphabetInstructionTable<'f, V> {
+	fn default() -> Self {
+		return Self::new();
+	}
 }
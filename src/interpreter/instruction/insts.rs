@@ -1,30 +1,32 @@
-use crate::interpreter::{FungeThread, InstructionDelta, FungeSpace, FungeAddress, FungeDimension, FungeSpaceAccessor, FungeStack, InstructionPointer, RSFUNGE_HANDPRINT, RSFUNGE_VERSION};
-use rand::Rng;
-use std::io::{Stdout, Stdin, Read, Write};
-use chrono::{DateTime, Local, Datelike, Timelike};
-use crate::interpreter::fingerprint::FingerprintRegistry;
+use crate::interpreter::{FungeThread, InstructionDelta, FungeSpace, FungeAddress, FungeDimension, FungeSpaceAccessor, FungeStack, FungeValue, FungeRng, FungeClock, InstructionPointer, RSFUNGE_HANDPRINT, RSFUNGE_VERSION};
+use std::io::{Stdout, Stdin, Write};
+use std::path::PathBuf;
+use crate::io::{CharEncoding, CodeLoader, CodeSource, CodeReadError, SourceMap, SourcePlacement};
+use chrono::{NaiveDateTime, Datelike, Timelike};
+use crate::interpreter::fingerprint::{FingerprintRegistry, FingerprintStates};
+use crate::interpreter::instruction::semantics;
 use std::cell::RefCell;
 
 /// 33: Logical not (!)
 #[inline(always)]
-pub fn inst_logical_not(thread: &mut FungeThread) { 
+pub fn inst_logical_not<V: FungeValue>(thread: &mut FungeThread<V>) { 
 	let cell = thread.stack_stack.pop();
-	thread.stack_stack.push(if cell == 0 { 1 } else { 0 });
+	thread.stack_stack.push(V::from(if cell.is_zero() { 1 } else { 0 }));
 }
 
 /// 37: Remainder (%)
 #[inline(always)]
-pub fn inst_remainder(thread: &mut FungeThread) {
+pub fn inst_remainder<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let (a, b) = thread.stack_stack.pop_two();
 	
 	// Calculate the remainder of the division of the values with explicit overflow wrapping
-	let c = b.wrapping_rem(a); // As per spec: Remainder from dividing second by first
+	let c = b.wrapping_rem(&a); // As per spec: Remainder from dividing second by first
 	thread.stack_stack.push(c);
 }
 
 /// 39: Fetch character (')
 #[inline(always)]
-pub fn inst_fetch_character<N, A>(thread: &mut FungeThread, funge_space: &mut FungeSpace<N, i32, A>) where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
+pub fn inst_fetch_character<N, V, A>(thread: &mut FungeThread<V>, funge_space: &mut FungeSpace<N, V, A>) where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
 	// Get next pos
 	let mut pos: FungeAddress = thread.ip;
 	pos.add_wrapping(&thread.delta);
@@ -40,8 +42,9 @@ pub fn inst_fetch_character<N, A>(thread: &mut FungeThread, funge_space: &mut Fu
 }
 
 /// 115: Store character (s)
+/// Returns the address of the written cell.
 #[inline(always)]
-pub fn inst_store_character<N, A>(thread: &mut FungeThread, funge_space: &mut FungeSpace<N, i32, A>) where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
+pub fn inst_store_character<N, V, A>(thread: &mut FungeThread<V>, funge_space: &mut FungeSpace<N, V, A>) -> FungeAddress where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
 	// Get next pos
 	let mut pos: FungeAddress = thread.ip;
 	pos.add_wrapping(&thread.delta);
@@ -51,63 +54,59 @@ pub fn inst_store_character<N, A>(thread: &mut FungeThread, funge_space: &mut Fu
 	
 	// Store in funge space
 	funge_space.write_cell(&pos, value);
+	return pos;
 }
 
 /// 42: Multiply (*)
 #[inline(always)]
-pub fn inst_multiply(thread: &mut FungeThread) {
+pub fn inst_multiply<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let (a, b) = thread.stack_stack.pop_two();
 	
 	// Mutliply the values with explicit overflow wrapping
-	let c = a.wrapping_mul(b);
+	let c = a.wrapping_mul(&b);
 	thread.stack_stack.push(c);
 }
 
 /// 43: Add (+)
 #[inline(always)]
-pub fn inst_add(thread: &mut FungeThread) {
+pub fn inst_add<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let (a, b) = thread.stack_stack.pop_two();
 	
 	// Add the values with explicit overflow wrapping
-	let c = a.wrapping_add(b);
+	let c = a.wrapping_add(&b);
 	thread.stack_stack.push(c);
 }
 
 /// 45: Subtract (-)
 #[inline(always)]
-pub fn inst_subtract(thread: &mut FungeThread) {
+pub fn inst_subtract<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let (a, b) = thread.stack_stack.pop_two();
 	
 	// Subtract the values with explicit overflow wrapping
-	let c = b.wrapping_sub(a); // As per spec: Subtract first from second
+	let c = b.wrapping_sub(&a); // As per spec: Subtract first from second
 	thread.stack_stack.push(c);
 }
 
 /// 47: Divide (/)
 #[inline(always)]
-pub fn inst_divide(thread: &mut FungeThread) {
+pub fn inst_divide<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let (a, b) = thread.stack_stack.pop_two();
 	
-	// Divide the values with explicit overflow wrapping
-	let c = b.wrapping_div(a); // As per spec: Divide second by first
+	// Divide the values with explicit overflow wrapping (division by zero results in zero)
+	let c = b.wrapping_div(&a); // As per spec: Divide second by first
 	thread.stack_stack.push(c);
 }
 
 /// 44: Output char (,)
 #[inline(always)]
-pub fn inst_output_char(thread: &mut FungeThread, charout: &mut Stdout) {
+pub fn inst_output_char<V: FungeValue>(thread: &mut FungeThread<V>, charout: &mut Stdout, encoding: CharEncoding) {
 	let cell = thread.stack_stack.pop();
 	
-	// Print the cell by converting it to a unicode scalar ("char") if possible
-	let mut char = '?';
-	if cell > 0 {
-		if let Some(c) = std::char::from_u32(cell as u32) {
-			char = c;
-		}
-	}
+	// Print the cell in the output encoding, cells that aren't a valid char are printed as '?'
+	let value = cell.to_i32().unwrap_or(-1);
 	
 	// Act as `r` if the write failed
-	if let Err(_) = write!(charout, "{}", char) {
+	if encoding.write_char(charout, value).is_err() {
 		// Reflect delta
 		_reflect_delta(&mut thread.delta);
 	}
@@ -118,11 +117,11 @@ pub fn inst_output_char(thread: &mut FungeThread, charout: &mut Stdout) {
 
 /// 46: Output integer (.)
 #[inline(always)]
-pub fn inst_output_integer(thread: &mut FungeThread, charout: &mut Stdout) {
+pub fn inst_output_integer<V: FungeValue>(thread: &mut FungeThread<V>, charout: &mut Stdout) {
 	let cell = thread.stack_stack.pop();
 	
 	// Act as `r` if the write failed
-	if let Err(_) = write!(charout, "{} ", cell as i32) { // As per spec write a space after the decimal number
+	if write!(charout, "{} ", cell).is_err() { // As per spec write a space after the decimal number
 		// Reflect delta
 		_reflect_delta(&mut thread.delta);
 	}
@@ -133,35 +132,35 @@ pub fn inst_output_integer(thread: &mut FungeThread, charout: &mut Stdout) {
 
 /// 48...57: Push Zero, .., Push Niner (0, .., 9)
 #[inline(always)]
-pub fn inst_push_number(thread: &mut FungeThread, number: i32) {
-	thread.stack_stack.push(number);
+pub fn inst_push_number<V: FungeValue>(thread: &mut FungeThread<V>, number: i32) {
+	thread.stack_stack.push(V::from(number));
 }
 
 /// 58: Duplicate (:)
 #[inline(always)]
-pub fn inst_duplicate(thread: &mut FungeThread) {
+pub fn inst_duplicate<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let cell = thread.stack_stack.pop();
-	thread.stack_stack.push(cell);
+	thread.stack_stack.push(cell.clone());
 	thread.stack_stack.push(cell);
 }
 
 /// 60: Go west (<)
 #[inline(always)]
-pub fn inst_go_west(thread: &mut FungeThread) {
+pub fn inst_go_west<V: FungeValue>(thread: &mut FungeThread<V>) {
 	// Set delta
 	thread.delta = InstructionDelta::new_xyz(-1, 0, 0);
 }
 
 /// 62: Go east (>)
 #[inline(always)]
-pub fn inst_go_east(thread: &mut FungeThread) {
+pub fn inst_go_east<V: FungeValue>(thread: &mut FungeThread<V>) {
 	// Set delta
 	thread.delta = InstructionDelta::new_xyz(1, 0, 0);
 }
 
 /// 94: Go north (^)
 #[inline(always)]
-pub fn inst_go_north(thread: &mut FungeThread, dims: u32) -> bool {
+pub fn inst_go_north<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> bool {
 	match dims {
 		1 => return false,
 		2 | 3 => {
@@ -175,7 +174,7 @@ pub fn inst_go_north(thread: &mut FungeThread, dims: u32) -> bool {
 
 /// 118: Go south (v)
 #[inline(always)]
-pub fn inst_go_south(thread: &mut FungeThread, dims: u32) -> bool {
+pub fn inst_go_south<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> bool {
 	match dims {
 		1 => return false,
 		2 | 3 => {
@@ -189,11 +188,11 @@ pub fn inst_go_south(thread: &mut FungeThread, dims: u32) -> bool {
 
 /// 95: East west if (_)
 #[inline(always)]
-pub fn inst_east_west_if(thread: &mut FungeThread) {
+pub fn inst_east_west_if<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let value = thread.stack_stack.pop();
 	
 	// If zero, go east (>)
-	if value == 0 {
+	if value.is_zero() {
 		thread.delta = InstructionDelta::new_xyz(1, 0, 0);
 	}
 	// If non-zero, go west (<)
@@ -204,14 +203,14 @@ pub fn inst_east_west_if(thread: &mut FungeThread) {
 
 /// 124: North south if (|)
 #[inline(always)]
-pub fn inst_north_south_if(thread: &mut FungeThread, dims: u32) -> bool {
+pub fn inst_north_south_if<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> bool {
 	match dims {
 		1 => return false,
 		2 | 3 => {
 			let value = thread.stack_stack.pop();
 			
 			// If zero, go south (v)
-			if value == 0 {
+			if value.is_zero() {
 				thread.delta = InstructionDelta::new_xyz(0, 1, 0);
 			}
 			// If non-zero, go north (^)
@@ -226,32 +225,22 @@ pub fn inst_north_south_if(thread: &mut FungeThread, dims: u32) -> bool {
 
 /// 63: Go away (?)
 #[inline(always)]
-pub fn inst_go_away(thread: &mut FungeThread, dims: u32) {
-	let new_delta: InstructionDelta;
-	let mut rng = rand::thread_rng();
-	
+pub fn inst_go_away<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32, rng: &mut FungeRng) {
 	// Create new random delta
-	match dims {
+	let new_delta = match dims {
 		1 => {
-			const DIRS: [i32; 2] = [-1, 1];
-			
-			let dir = DIRS[rng.gen_range(0usize, DIRS.len())];
-			new_delta = InstructionDelta::new_xyz(dir, 0, 0);
+			let dir = semantics::RANDOM_DIRECTIONS_1D[rng.below(2) as usize];
+			InstructionDelta::new_xyz(dir, 0, 0)
 		}
 		2 => {
-			const DIRS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
-			
-			let dir = DIRS[rng.gen_range(0usize, DIRS.len())];
-			new_delta = InstructionDelta::new_xyz(dir.0, dir.1, 0);
+			let dir = semantics::RANDOM_DIRECTIONS_2D[rng.below(4) as usize];
+			InstructionDelta::new_xyz(dir.0, dir.1, 0)
 		}
-		3 => {
-			const DIRS: [(i32, i32, i32); 6] = [(-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1)];
-			
-			let dir = DIRS[rng.gen_range(0usize, DIRS.len())];
-			new_delta = InstructionDelta::new_xyz(dir.0, dir.1, dir.2);
+		_ => {
+			let dir = semantics::RANDOM_DIRECTIONS_3D[rng.below(6) as usize];
+			InstructionDelta::new_xyz(dir.0, dir.1, dir.2)
 		}
-		_ => unimplemented!(),
-	}
+	};
 	
 	// Assign delta to thread
 	thread.delta = new_delta;
@@ -259,57 +248,86 @@ pub fn inst_go_away(thread: &mut FungeThread, dims: u32) {
 
 /// 96: Greater than (`)
 #[inline(always)]
-pub fn inst_greater_than(thread: &mut FungeThread) {
+pub fn inst_greater_than<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let (first, second) = thread.stack_stack.pop_two();
-	thread.stack_stack.push(if second > first { 1 } else { 0 });
+	thread.stack_stack.push(V::from(if second > first { 1 } else { 0 }));
 }
 
 /// 36: Pop ($)
 #[inline(always)]
-pub fn inst_pop(thread: &mut FungeThread) {
+pub fn inst_pop<V: FungeValue>(thread: &mut FungeThread<V>) {
 	// Pop value from cell and discard
 	thread.stack_stack.pop();
 }
 
 /// 92: Swap (\)
 #[inline(always)]
-pub fn inst_swap(thread: &mut FungeThread) {
+pub fn inst_swap<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let ss = &mut thread.stack_stack;
 	let (a, b) = ss.pop_two();
 	ss.push(a);
 	ss.push(b);
 }
 
+/// Pops a vector with the given number of components from the stack.
+/// The components are truncated to 32 bits.
 #[inline(always)]
-pub fn _pop_vector(toss: &mut FungeStack, dims: u32) -> FungeAddress {
+pub fn _pop_vector<V: FungeValue>(toss: &mut FungeStack<V>, dims: u32) -> FungeAddress {
 	let vector = match dims {
 		// 1D
 		1 => {
-			let x = toss.pop();
+			let x = toss.pop().to_i32_wrapping();
 			FungeAddress::new_xyz(x, 0, 0)
 		}
 		// 2D
 		2 => {
-			let y = toss.pop();
-			let x = toss.pop();
+			let y = toss.pop().to_i32_wrapping();
+			let x = toss.pop().to_i32_wrapping();
 			FungeAddress::new_xyz(x, y, 0)
 		}
 		// 3D
-		3 => {
-			let z = toss.pop();
-			let y = toss.pop();
-			let x = toss.pop();
+		_ => {
+			let z = toss.pop().to_i32_wrapping();
+			let y = toss.pop().to_i32_wrapping();
+			let x = toss.pop().to_i32_wrapping();
 			FungeAddress::new_xyz(x, y, z)
 		}
-		_ => unimplemented!(),
 	};
 	return vector;
 }
 
+/// Pushes a vector with the given number of components onto the stack.
+#[inline(always)]
+pub fn _push_vector<V: FungeValue>(toss: &mut FungeStack<V>, vector: &FungeAddress, dims: u32) {
+	toss.push(V::from(vector.x()));
+	if dims >= 2 {
+		toss.push(V::from(vector.y()));
+	}
+	if dims >= 3 {
+		toss.push(V::from(vector.z()));
+	}
+}
+
+/// Pops a null-terminated string (0gnirts) from the stack.
+/// Cells that aren't valid chars are replaced by U+FFFD.
+pub fn _pop_string<V: FungeValue>(toss: &mut FungeStack<V>) -> String {
+	let mut string = String::new();
+	loop {
+		let cell = toss.pop();
+		if cell.is_zero() {
+			break;
+		}
+		let char = cell.to_i32().and_then(|c| std::char::from_u32(c as u32));
+		string.push(char.unwrap_or(std::char::REPLACEMENT_CHARACTER));
+	}
+	return string;
+}
+
 /// 103: Get (g)
+/// Returns the address of the read cell.
 #[inline(always)]
-pub fn inst_get<N, A>(thread: &mut FungeThread, funge_space: &mut FungeSpace<N, i32, A>, dims: u32) 
-where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
+pub fn inst_get<N, V, A>(thread: &mut FungeThread<V>, funge_space: &mut FungeSpace<N, V, A>, dims: u32) -> FungeAddress
+where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
 	let storage_offset = thread.get_storage_offset();
 	
 	// Pop vector
@@ -322,27 +340,119 @@ where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
 	// Read and push cell
 	let cell = funge_space.read_cell(&position);
 	toss.push(cell);
+	return position;
+}
+
+/// 105: Input file (i)
+pub fn inst_input_file<N, V, A>(thread: &mut FungeThread<V>, funge_space: &mut FungeSpace<N, V, A>, source_map: &mut SourceMap, dims: u32, encoding: CharEncoding)
+where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
+	let storage_offset = thread.get_storage_offset();
+	
+	// Pop filename, flags and position
+	let toss = thread.stack_stack.top_stack();
+	let filename = _pop_string(toss);
+	let flags = toss.pop().to_i32_wrapping();
+	let position = _pop_vector(toss, dims);
+	
+	let mut origin = position;
+	origin.add_wrapping(&storage_offset);
+	
+	// Load file, in binary mode (flag 1) line and form feeds are not interpreted
+	let source = CodeSource::new(PathBuf::from(&filename), None);
+	let mut loader = CodeLoader::with_encoding(encoding);
+	let code = if (flags & 1) != 0 {
+		loader.load_from_file_binary(source.get_path()).map_err(CodeReadError::from)
+	}
+	else {
+		loader.load_from_file_with_dimensionality(source.get_path(), Some(dims))
+	};
+	
+	// Act as `r` if the file couldn't be loaded or has more dimensions than the funge space
+	let code = match code {
+		Ok(code) => code,
+		_ => {
+			_reflect_delta(&mut thread.delta);
+			return;
+		}
+	};
+	
+	// Record origin of the loaded cells
+	source_map.add_placement(SourcePlacement {
+		source,
+		origin,
+		size: code.bounding_box,
+	}, &code);
+	
+	// Write cells, spaces don't overwrite existing cells
+	for (offset, value) in code.cells() {
+		if value != 32 {
+			let mut address = origin;
+			address.add_wrapping(&offset);
+			funge_space.write_cell(&address, V::from(value as i32));
+		}
+	}
+	
+	// Push size and least point
+	let size = FungeAddress::new_xyz(code.bounding_box.x() as i32, code.bounding_box.y() as i32, code.bounding_box.z() as i32);
+	let toss = thread.stack_stack.top_stack();
+	_push_vector(toss, &size, dims);
+	_push_vector(toss, &position, dims);
 }
 
 /// 106: Jump forward (j)
 #[inline(always)]
-pub fn inst_jump_forward(thread: &mut FungeThread) {
+pub fn inst_jump_forward<V: FungeValue>(thread: &mut FungeThread<V>) {
 	// Note that the ip is moved by this instruction but it is also incremented
 	// normally after the instruction is executed, this is per spec!
 	
-	let count = thread.stack_stack.pop();
+	let count = thread.stack_stack.pop().to_i32_wrapping();
 	
 	// Move ip
 	let d = thread.delta;
-	let ip = &mut thread.delta;
+	let ip = &mut thread.ip;
 	ip.set_x(ip.x().wrapping_add(d.x().wrapping_mul(count)));
 	ip.set_y(ip.y().wrapping_add(d.y().wrapping_mul(count)));
 	ip.set_z(ip.z().wrapping_add(d.z().wrapping_mul(count)));
 }
 
+/// 107: Iterate (k)
+/// Returns the instruction to execute at the ip and how often to execute it.
+#[inline(never)]
+pub fn inst_iterate<N, V, A>(thread: &mut FungeThread<V>, funge_space: &FungeSpace<N, V, A>) -> (i32, i32) where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
+	let count = thread.stack_stack.pop().to_i32_wrapping();
+	
+	// Find the next instruction, skipping spaces and comments
+	let mut pos = funge_space.next_ip(&thread.ip, &thread.delta);
+	loop {
+		match funge_space.read_cell_cached(&pos, &mut thread.page_cache).to_i32() {
+			/* space */ Some(32) => pos = funge_space.next_ip(&pos, &thread.delta),
+			/* ; */ Some(59) => {
+				while {
+					pos = funge_space.next_ip(&pos, &thread.delta);
+					(funge_space.read_cell_cached(&pos, &mut thread.page_cache) != V::from(59))
+				} {}
+				pos = funge_space.next_ip(&pos, &thread.delta);
+			}
+			_ => break,
+		}
+	}
+	
+	if count < 0 {
+		// Act like 'r'
+		return (114, 1);
+	}
+	else if count == 0 {
+		// Skip the next instruction
+		thread.ip = pos;
+		return (122, 0);
+	}
+	let instruction = funge_space.read_cell_cached(&pos, &mut thread.page_cache).to_i32().unwrap_or(-1);
+	return (instruction, count);
+}
+
 /// 104: Go high (h)
 #[inline(always)]
-pub fn inst_go_high(thread: &mut FungeThread, dims: u32) -> bool {
+pub fn inst_go_high<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> bool {
 	return match dims {
 		3 => {
 			// Set delta
@@ -355,7 +465,7 @@ pub fn inst_go_high(thread: &mut FungeThread, dims: u32) -> bool {
 
 /// 108: Go low (l)
 #[inline(always)]
-pub fn inst_go_low(thread: &mut FungeThread, dims: u32) -> bool {
+pub fn inst_go_low<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> bool {
 	match dims {
 		3 => {
 			// Set delta
@@ -368,13 +478,13 @@ pub fn inst_go_low(thread: &mut FungeThread, dims: u32) -> bool {
 
 /// 109: High low if (m)
 #[inline(always)]
-pub fn inst_high_low_if(thread: &mut FungeThread, dims: u32) -> bool {
+pub fn inst_high_low_if<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> bool {
 	match dims {
 		3 => {
 			let value = thread.stack_stack.pop();
 			
 			// If zero, act like go low (l)
-			if value == 0 {
+			if value.is_zero() {
 				thread.delta = InstructionDelta::new_xyz(0, 0, -1);
 			}
 			// If non-zero, act like go high (h)
@@ -389,15 +499,16 @@ pub fn inst_high_low_if(thread: &mut FungeThread, dims: u32) -> bool {
 
 /// 110: Clear stack (n)
 #[inline(always)]
-pub fn inst_clear_stack(thread: &mut FungeThread) {
+pub fn inst_clear_stack<V: FungeValue>(thread: &mut FungeThread<V>) {
 	let toss = thread.stack_stack.top_stack();
 	toss.clear();
 }
 
 /// 112: Put (p)
+/// Returns the address of the written cell.
 #[inline(always)]
-pub fn inst_put<N, A>(thread: &mut FungeThread, funge_space: &mut FungeSpace<N, i32, A>, dims: u32) 
-where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
+pub fn inst_put<N, V, A>(thread: &mut FungeThread<V>, funge_space: &mut FungeSpace<N, V, A>, dims: u32) -> FungeAddress
+where N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V> {
 	let storage_offset = &thread.get_storage_offset();
 	
 	// Pop vector
@@ -410,6 +521,7 @@ where N: FungeDimension, A: FungeSpaceAccessor<N, i32> {
 	// Pop and write value
 	let value = toss.pop();
 	funge_space.write_cell(&position, value);
+	return position;
 }
 
 pub fn _reflect_delta(delta: &mut InstructionPointer) {
@@ -421,7 +533,7 @@ pub fn _reflect_delta(delta: &mut InstructionPointer) {
 
 /// 114: Reflect (r)
 #[inline(always)]
-pub fn inst_reflect(thread: &mut FungeThread) {
+pub fn inst_reflect<V: FungeValue>(thread: &mut FungeThread<V>) {
 	_reflect_delta(&mut thread.delta);
 }
 
@@ -437,7 +549,7 @@ pub fn _rotate_delta_counterclockwise_90(delta: &mut InstructionDelta) {
 
 /// 91: Turn left ([)
 #[inline(always)]
-pub fn inst_turn_left(thread: &mut FungeThread, dims: u32) -> bool {
+pub fn inst_turn_left<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> bool {
 	match dims {
 		2 | 3 => {
 			// Rotate delta 90° counter-clockwise
@@ -450,7 +562,7 @@ pub fn inst_turn_left(thread: &mut FungeThread, dims: u32) -> bool {
 
 /// 93: Turn left (])
 #[inline(always)]
-pub fn inst_turn_right(thread: &mut FungeThread, dims: u32) -> bool {
+pub fn inst_turn_right<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> bool {
 	match dims {
 		2 | 3 => {
 			// Rotate delta 90° clockwise
@@ -463,7 +575,7 @@ pub fn inst_turn_right(thread: &mut FungeThread, dims: u32) -> bool {
 
 /// 119: Compare (w)
 #[inline(always)]
-pub fn inst_compare(thread: &mut FungeThread, dims: u32) -> bool {
+pub fn inst_compare<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) -> bool {
 	match dims {
 		2 | 3 => {
 			let (b, a) = thread.stack_stack.pop_two();
@@ -488,7 +600,7 @@ pub fn inst_compare(thread: &mut FungeThread, dims: u32) -> bool {
 
 /// 120: Absolute delta (x)
 #[inline(always)]
-pub fn inst_absolute_delta(thread: &mut FungeThread, dims: u32) {
+pub fn inst_absolute_delta<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) {
 	// Pop delta vector
 	let new_delta: InstructionDelta = _pop_vector(thread.stack_stack.top_stack(), dims);
 	
@@ -496,119 +608,128 @@ pub fn inst_absolute_delta(thread: &mut FungeThread, dims: u32) {
 	thread.delta = new_delta;
 }
 
+/// Returns the number of sysinfo cells pushed by `y` (without the stack cells picked beyond them).
 #[inline(always)]
-pub fn _get_sysinfo_cell_num(thread: &mut FungeThread, dims: u32, stack_num: u32, env_var_string: &[u8], cli_arg_string: &[u8]) -> u32 {
-	let base_size = [17, 22, 25][dims as usize];
+pub fn _get_sysinfo_cell_num<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32, stack_num: u32, env_var_string: &[u8], cli_arg_string: &[u8]) -> u32 {
+	let base_size = _get_sysinfo_base_cell_num(dims);
 	let size = base_size + stack_num + env_var_string.len() as u32 + cli_arg_string.len() as u32;
 	return size;
 }
 
+/// Returns the number of sysinfo cells before the stack size cells.
+/// These are 9 single cells, then 5 vectors (ip, delta, storage offset, least and greatest point)
+/// and then 3 more single cells (date, time and number of stacks).
 #[inline(always)]
-pub fn _get_sysinfo_cell<N: FungeDimension, A: FungeSpaceAccessor<N, i32>>(thread: &mut FungeThread, index: u32, dims: u32, original_toss_depth: u32, stack_num: u32, env_var_string: &[u8], cli_arg_string: &[u8]) -> i32 {
-	return match (index, dims) {
-		(0, _) => 0, /* flags (env) */
-		(1, _) => 4, /* num bytes per cell (global env) */
-		(2, _) => RSFUNGE_HANDPRINT as i32, /* implementation handprint (env) */
-		(3, _) => RSFUNGE_VERSION as i32, /* implementation version number (env) */
-		(4, _) => 0, /* operating paradigm (for = instruction) (global env) */ // TODO: Return the right code, according to the execute_call_mode
-		(5, _) => '/' as i32, /* path seperator char (global env) */
-		(6, _) => A::dimensionality() as i32, /* dimensionality or number of cells per vector (global env) */
-		(7, _) => 0, /* locally unique id for the current thread (ip) */ // TODO: Implement properly
-		(8, _) => 0, /* unique team number for the current thread (not applicable to rsfunge) (ip) */
-		
-		(i @ 9, 1) |
-		(i @ 9..=10, 2) |
-		(i @ 9..=11, 3) => thread.ip.elements[i as usize], /* ip of the current thread (ip) */
-		
-		(i @ 10, 1) |
-		(i @ 11..=12, 2) |
-		(i @ 12..=14, 3) => thread.delta.elements[i as usize], /* delta of the curren thread (ip) */
-		
-		(i @ 11, 1) |
-		(i @ 13..=14, 2) |
-		(i @ 15..=17, 3) => thread.get_storage_offset().elements[i as usize], /* storage offset of the current thread (ip) */
-		
-		(_i @ 12, 1) |
-		(_i @ 15..=16, 2) |
-		(_i @ 18..=20, 3) => 0, /* least point which contains a non-space cell, relative to origin (env) */
+fn _get_sysinfo_base_cell_num(dims: u32) -> u32 {
+	9 + (5 * dims) + 3
+}
+
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub fn _get_sysinfo_cell<N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V>>(thread: &mut FungeThread<V>, index: u32, dims: u32, original_toss_depth: u32, stack_num: u32, env_var_string: &[u8], cli_arg_string: &[u8], time: &NaiveDateTime, bounds: &(FungeAddress, FungeAddress)) -> V {
+	let vectors_end = 9 + (5 * dims);
+	
+	return match index {
+		0 => V::from(0x01 | 0x02), /* flags (env): concurrent funge (t) and input file (i) are implemented */
+		1 => V::from(V::cell_bytes()), /* num bytes per cell (global env) */
+		2 => V::from(RSFUNGE_HANDPRINT as i32), /* implementation handprint (env) */
+		3 => V::from(RSFUNGE_VERSION as i32), /* implementation version number (env) */
+		4 => V::from(0), /* operating paradigm (for = instruction) (global env) */ // TODO: Return the right code, according to the execute_call_mode
+		5 => V::from('/' as i32), /* path seperator char (global env) */
+		6 => V::from(A::dimensionality() as i32), /* dimensionality or number of cells per vector (global env) */
+		7 => V::from(thread.id as i32), /* locally unique id for the current thread (ip) */
+		8 => V::from(0), /* unique team number for the current thread (not applicable to rsfunge) (ip) */
 		
-		(_i @ 13, 1) |
-		(_i @ 17..=18, 2) |
-		(_i @ 19..=21, 3) => 0, /* greatest point which contains a non-space cell, relative to the least point (env) */ // TODO: Implement calc routine (go through all funge space pages and search locally in them the least, greatest point)s
+		i if i < vectors_end => {
+			// The first cell of a vector is its last component, so the vector can be popped like any other
+			let vector_index = (i - 9) / dims;
+			let component = (dims - 1 - ((i - 9) % dims)) as usize;
+			
+			match vector_index {
+				0 => V::from(thread.ip.elements[component]), /* ip of the current thread (ip) */
+				1 => V::from(thread.delta.elements[component]), /* delta of the curren thread (ip) */
+				2 => V::from(thread.get_storage_offset().elements[component]), /* storage offset of the current thread (ip) */
+				3 => V::from(bounds.0.elements[component]), /* least point which contains a non-space cell, relative to origin (env) */
+				_ => V::from(bounds.1.elements[component].wrapping_sub(bounds.0.elements[component])), /* greatest point which contains a non-space cell, relative to the least point (env) */
+			}
+		}
 		
-		(14, 1) |
-		(19, 2) |
-		(22, 3) => { /* current ((year - 1900) * 256 * 256) + (month * 256) + (day of month) (env) */
+		i if i == vectors_end => { /* current ((year - 1900) * 256 * 256) + (month * 256) + (day of month) (env) */
 			// Calculate timestamp
-			let time: DateTime<Local> = chrono::Local::now();
-			let timestamp: u32 = ((time.year() as u32 - 1900) * 256 * 256) + (time.month() as u32 * 256) + (time.day() as u32); // Use 1-based indexing for month and day
-			timestamp as i32
+			// Wraps for years far from 1900, which only fake clocks can reach
+			let timestamp: i32 = (time.year().wrapping_sub(1900).wrapping_mul(256 * 256)).wrapping_add((time.month() * 256 + time.day()) as i32); // Use 1-based indexing for month and day
+			V::from(timestamp)
 		}
 		
-		(15, 1) |
-		(20, 2) |
-		(23, 3) => { /* current (hour * 256 * 256) + (minute * 256) + (second) (env) */
+		i if i == vectors_end + 1 => { /* current (hour * 256 * 256) + (minute * 256) + (second) (env) */
 			// Get time
-			let time: DateTime<Local> = chrono::Local::now();
-			let result: u32 = (time.hour() as u32 * 256 * 256) + (time.minute() as u32 * 256) + (time.second() as u32); // 0-based "indexing" for hours, minutes, seconds. Makes sense but it somehow feels wrong considering the date uses 1-based indexing
-			result as i32
+			let result: u32 = (time.hour() * 256 * 256) + (time.minute() * 256) + time.second(); // 0-based "indexing" for hours, minutes, seconds. Makes sense but it somehow feels wrong considering the date uses 1-based indexing
+			V::from(result as i32)
 		}
 		
-		(16, 1) |
-		(21, 2) |
-		(24, 3) => {
-			stack_num as i32 /* number of stacks on the stack stack (ip) */
+		i if i == vectors_end + 2 => {
+			V::from(stack_num as i32) /* number of stacks on the stack stack (ip) */
 		}
 		
-		_ => (|| -> i32 {
+		_ => (|| -> V {
 			// Stack size cells
-			let local_index = index - [17, 22, 25][dims as usize];
+			let local_index = index - _get_sysinfo_base_cell_num(dims);
 			if local_index < stack_num {
+				// The toss size is the size before y was executed
+				if local_index == 0 {
+					return V::from(original_toss_depth as i32);
+				}
 				let stack = thread.stack_stack.nth_stack(local_index).unwrap();
-				return stack.depth() as i32;
+				return V::from(stack.depth() as i32);
 			}
 			
 			// Cli arg string
 			let local_index = local_index - stack_num;
 			if local_index < cli_arg_string.len() as u32 {
-				return cli_arg_string[local_index as usize] as i32;
+				return V::from(cli_arg_string[local_index as usize] as i32);
 			}
 			
 			// Env var string
 			let local_index = local_index - cli_arg_string.len() as u32;
 			if local_index < env_var_string.len() as u32 {
-				return env_var_string[local_index as usize] as i32;
+				return V::from(env_var_string[local_index as usize] as i32);
 			}
 			
-			// Else, shouldn't be reached
-			unimplemented!("Should never be reached");
+			// Cells past the end of the sysinfo are zero (they're never requested by y)
+			V::from(0)
 		})(),
 	};
 }
 
 /// 121: Get sysinfo (y)
 #[inline(always)]
-pub fn inst_get_sysinfo<N: FungeDimension, A: FungeSpaceAccessor<N, i32>>(thread: &mut FungeThread, env_var_string: &[u8], cli_arg_string: &[u8]) {
+pub fn inst_get_sysinfo<N: FungeDimension, V: FungeValue, A: FungeSpaceAccessor<N, V>>(thread: &mut FungeThread<V>, funge_space: &mut FungeSpace<N, V, A>, env_var_string: &[u8], cli_arg_string: &[u8], clock: &FungeClock) {
 	// The given index: Zero or negative for everything, else the 1-based cell number
 	let nth_cell = thread.stack_stack.pop();
 	
 	let dims = A::dimensionality();
 	let toss_depth = thread.stack_stack.top_stack().depth();
 	let stack_num = thread.stack_stack.num_stacks();
+	let time = clock.now();
+	
+	// Get the exact bounds, the bounds of an empty funge space are the origin
+	funge_space.shrink_bounds();
+	let bounds = funge_space.bounds().unwrap_or((FungeAddress::new_value(0), FungeAddress::new_value(0)));
 	
 	// Push only specific (one-indexed) cell
-	if nth_cell > 0 {
+	if nth_cell > V::from(0) {
 		let syscell_num = _get_sysinfo_cell_num(thread, dims, stack_num, env_var_string, cli_arg_string);
 		
 		// Use specific sysinfo cell
-		if nth_cell <= syscell_num as i32 {
-			let cell = _get_sysinfo_cell::<N, A>(thread, nth_cell as u32 - 1, dims, toss_depth, stack_num, env_var_string, cli_arg_string);
+		if nth_cell <= V::from(syscell_num as i32) {
+			let nth_cell = nth_cell.to_i32_wrapping() as u32;
+			let cell = _get_sysinfo_cell::<N, V, A>(thread, nth_cell - 1, dims, toss_depth, stack_num, env_var_string, cli_arg_string, &time, &bounds);
 			thread.stack_stack.push(cell);
 		}
 		// If index larger than sysinfo cell num, pick from toss
 		else {
-			let cell = thread.stack_stack.top_stack().peek_nth((nth_cell as u32 - 1) - syscell_num);
-			thread.stack_stack.push(cell.unwrap_or(0));
+			let cell = nth_cell.to_i32().and_then(|n| thread.stack_stack.top_stack().peek_nth((n as u32 - 1) - syscell_num));
+			thread.stack_stack.push(cell.unwrap_or_default());
 		}
 	}
 	// Push all sysinfo cells
@@ -617,7 +738,7 @@ pub fn inst_get_sysinfo<N: FungeDimension, A: FungeSpaceAccessor<N, i32>>(thread
 		
 		// Go through all sysinfo cells in reverse so that they are on the stack in the right order
 		for i in 0..syscell_num {
-			let cell = _get_sysinfo_cell::<N, A>(thread, (syscell_num - 1) - i, dims, toss_depth, stack_num, env_var_string, cli_arg_string);
+			let cell = _get_sysinfo_cell::<N, V, A>(thread, (syscell_num - 1) - i, dims, toss_depth, stack_num, env_var_string, cli_arg_string, &time, &bounds);
 			thread.stack_stack.push(cell);
 		}
 	}
@@ -625,23 +746,28 @@ pub fn inst_get_sysinfo<N: FungeDimension, A: FungeSpaceAccessor<N, i32>>(thread
 
 /// 38: Input integer (&)
 #[inline(always)]
-pub fn inst_input_integer(thread: &mut FungeThread, charin: &mut Stdin) {
-	// TODO: Implement (read into char array with fixed size of the longest possible number, parse with custom implementation that checks for overflow)
+pub fn inst_input_integer<V: FungeValue>(thread: &mut FungeThread<V>, charin: &mut Stdin) {
+	// Stop before the digit that would overflow the cell
+	let value = semantics::read_integer(&mut charin.lock(), V::checked_push_digit);
+	
+	if let Some(v) = value {
+		thread.stack_stack.push(v);
+	}
+	// If no number could be read (end of file or pipe) act as reflect
+	else {
+		_reflect_delta(&mut thread.delta);
+	}
 }
 
 /// 126: Input character (~)
 #[inline(always)]
-pub fn inst_input_character(thread: &mut FungeThread, charin: &mut Stdin) {
-	// TODO: Implement charset support, for now just use ascii
+pub fn inst_input_character<V: FungeValue>(thread: &mut FungeThread<V>, charin: &mut Stdin, encoding: CharEncoding) {
+	// Read one char in the input encoding
+	let read_res = encoding.read_char(&mut charin.lock());
 	
-	// Read one byte (one ascii char)
-	let mut read_buffer: [u8; 1] = [0; 1];
-	let read_res = charin.read_exact(&mut read_buffer);
-	
-	if let Ok(_) = read_res {
+	if let Ok(Some(char)) = read_res {
 		// Push read char onto toss
-		let char: i32 = read_buffer[0] as i32;
-		thread.stack_stack.push(char);
+		thread.stack_stack.push(V::from(char as i32));
 	}
 	// On read error (including end of file/pipe) act as reflect
 	else {
@@ -652,23 +778,34 @@ pub fn inst_input_character(thread: &mut FungeThread, charin: &mut Stdin) {
 
 /// 117: Stack under stack (u)
 #[inline(always)]
-pub fn inst_stack_under_stack(thread: &mut FungeThread) {
+pub fn inst_stack_under_stack<V: FungeValue>(thread: &mut FungeThread<V>) {
 	// Check if we have a soss
-	if let Some(_) = thread.stack_stack.second_stack() {
+	if thread.stack_stack.second_stack().is_some() {
 		// Pop count from toss
-		let count = thread.stack_stack.pop();
+		let count = thread.stack_stack.pop().to_i32_wrapping();
 		
 		// Transfer cells in reverse order via pop-push loop
 		if count >= 0 {
 			for _ in 0..count {
 				let val = thread.stack_stack.second_stack().unwrap().pop();
-				thread.stack_stack.top_stack().push(val);
+				let toss = thread.stack_stack.top_stack();
+				toss.push(val);
+				
+				// Stop once the stack is full instead of spinning through the remaining count
+				if toss.overflowed() {
+					break;
+				}
 			}
 		}
 		else {
-			for _ in 0..count {
+			for _ in 0..count.unsigned_abs() {
 				let val = thread.stack_stack.top_stack().pop();
-				thread.stack_stack.second_stack().unwrap().push(val);
+				let soss = thread.stack_stack.second_stack().unwrap();
+				soss.push(val);
+				
+				if soss.overflowed() {
+					break;
+				}
 			}
 		}
 	}
@@ -680,14 +817,14 @@ pub fn inst_stack_under_stack(thread: &mut FungeThread) {
 
 /// 123: Begin block ({)
 #[inline(always)]
-pub fn inst_begin_block(thread: &mut FungeThread, dims: u32) {
-	let n = thread.stack_stack.pop();
+pub fn inst_begin_block<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) {
+	let n = thread.stack_stack.pop().to_i32_wrapping();
 	
 	// Get storage offset
 	let current_storage_offset: FungeAddress = thread.get_storage_offset();
 	
 	// Allocate new stack
-	let mut new_toss = FungeStack::<i32>::new();
+	let mut new_toss = thread.stack_stack.new_stack();
 	
 	// If n > 0, transfer |n| elements from soss to new toss in non-reversed order
 	if n > 0 {
@@ -697,10 +834,7 @@ pub fn inst_begin_block(thread: &mut FungeThread, dims: u32) {
 	// If n < 0, push |n| zeros onto soss
 	else if n < 0 {
 		let fsoss = thread.stack_stack.top_stack(); // Current toss is future soss
-		
-		for _ in 0..(-n) {
-			fsoss.push(0);
-		}
+		fsoss.push_zeroes(n.unsigned_abs());
 	}
 	// If n == 0, don't transfer any elements
 	else {}
@@ -709,18 +843,17 @@ pub fn inst_begin_block(thread: &mut FungeThread, dims: u32) {
 	let fsoss = thread.stack_stack.top_stack();
 	match dims {
 		1 => {
-			fsoss.push(current_storage_offset.x()); // x
+			fsoss.push(V::from(current_storage_offset.x())); // x
 		}
 		2 => {
-			fsoss.push(current_storage_offset.y()); // y
-			fsoss.push(current_storage_offset.x()); // x
+			fsoss.push(V::from(current_storage_offset.x())); // x
+			fsoss.push(V::from(current_storage_offset.y())); // y
 		}
-		3 => {
-			fsoss.push(current_storage_offset.z()); // z
-			fsoss.push(current_storage_offset.y()); // y
-			fsoss.push(current_storage_offset.x()); // x
+		_ => {
+			fsoss.push(V::from(current_storage_offset.x())); // x
+			fsoss.push(V::from(current_storage_offset.y())); // y
+			fsoss.push(V::from(current_storage_offset.z())); // z
 		}
-		_ => unimplemented!(),
 	}
 	
 	// Push new toss onto the stack stack
@@ -734,8 +867,8 @@ pub fn inst_begin_block(thread: &mut FungeThread, dims: u32) {
 
 /// 125: End block ({)
 #[inline(always)]
-pub fn inst_end_block(thread: &mut FungeThread, dims: u32) {
-	let n = thread.stack_stack.pop();
+pub fn inst_end_block<V: FungeValue>(thread: &mut FungeThread<V>, dims: u32) {
+	let n = thread.stack_stack.pop().to_i32_wrapping();
 	
 	if let Some(psoss) = thread.stack_stack.second_stack() {
 		// Pop storage offset from soss and assign to thread
@@ -769,22 +902,22 @@ pub fn inst_end_block(thread: &mut FungeThread, dims: u32) {
 // 'Load semantics' loads a counted-string, calcs the fingerprint id (fid) and tries to load the fingerprint (from the registry in this case).
 // If it can't it acts like 'r'. If it can, it pushes the fid, then a 1 onto the toss, overloads the given alphabet instructions and
 // and pushes the fingerprint onto the (only internally used) fingerprint stack.
-// 'Unload semantics' pops a counted string (which might just be fingerprint id then a 1) from the stack and completely ignores it.
-// If there is atleast one fingerprint on the fingerprint stack, it pops the top most fingerprint off and un-overloads the alphabet instructions.
-// Else if no fingerprint is on the fingerprint stack, it does nothing.
+// 'Unload semantics' pops a counted string the same way and acts like 'r' if the fingerprint isn't known.
+// Otherwise it pops the top most semantics of each alphabet instruction the fingerprint defines,
+// even if they were overloaded by another fingerprint.
 
 /// 40: Load semantics (()
 #[inline(always)]
-pub fn inst_load_semantics<'f>(thread: &mut FungeThread<'_, 'f>, dims: u32, fingerprint_registry: &'_ RefCell<FingerprintRegistry<'f>>) {
+pub fn inst_load_semantics<'f, V: FungeValue>(thread: &mut FungeThread<'_, 'f, V>, dims: u32, fingerprint_registry: &'_ RefCell<FingerprintRegistry<'f, V>>, fingerprint_states: &mut FingerprintStates) {
 	let toss = &mut thread.stack_stack;
 	
 	// Pop count
-	let count = toss.pop();
+	let count = toss.pop().to_i32_wrapping();
 	
 	// Pop fingerprint id (with explicit overflow wrapping)
 	let mut fid: u32 = 0;
 	for _ in 0..count {
-		fid = fid.wrapping_mul(256u32).wrapping_add(toss.pop() as u32);
+		fid = fid.wrapping_mul(256u32).wrapping_add(toss.pop().to_i32_wrapping() as u32);
 	}
 	
 	// Try to get fingerprint from registry
@@ -792,8 +925,13 @@ pub fn inst_load_semantics<'f>(thread: &mut FungeThread<'_, 'f>, dims: u32, fing
 	let fingerprint = registry.find_fingerprint(fid);
 	
 	if let Some(fp) = fingerprint {
-		// Push fingerprint
+		// Push fingerprint and create its per interpreter data on first load
 		thread.alphabet_inst_table.push_fingerprint(fp);
+		fingerprint_states.init(fp.as_ref());
+		
+		// Push fingerprint id and success flag
+		toss.push(V::from(fid as i32));
+		toss.push(V::from(1));
 	}
 	else {
 		// Act like 'r'
@@ -803,18 +941,28 @@ pub fn inst_load_semantics<'f>(thread: &mut FungeThread<'_, 'f>, dims: u32, fing
 
 /// 41: Unload semantics ())
 #[inline(always)]
-pub fn inst_unload_semantics<'f>(thread: &mut FungeThread<'_, 'f>, dims: u32, fingerprint_registry: &'_ RefCell<FingerprintRegistry<'f>>) {
+pub fn inst_unload_semantics<'f, V: FungeValue>(thread: &mut FungeThread<'_, 'f, V>, dims: u32, fingerprint_registry: &'_ RefCell<FingerprintRegistry<'f, V>>) {
 	let toss = &mut thread.stack_stack;
 	
 	// Pop count
-	let count = toss.pop();
+	let count = toss.pop().to_i32_wrapping();
 	
 	// Pop fingerprint id (with explicit overflow wrapping)
 	let mut fid: u32 = 0;
 	for _ in 0..count {
-		fid = fid.wrapping_mul(256u32).wrapping_add(toss.pop() as u32);
+		fid = fid.wrapping_mul(256u32).wrapping_add(toss.pop().to_i32_wrapping() as u32);
 	}
 	
-	// Try to pop fingerprint
-	let _ = thread.alphabet_inst_table.pop_fingerprint();
+	// Try to get fingerprint from registry
+	let mut registry = fingerprint_registry.borrow_mut();
+	let fingerprint = registry.find_fingerprint(fid);
+	
+	if let Some(fp) = fingerprint {
+		// Pop semantics of the fingerprint's instructions
+		thread.alphabet_inst_table.pop_fingerprint(fp);
+	}
+	else {
+		// Act like 'r'
+		_reflect_delta(&mut thread.delta);
+	}
 }
@@ -1,5 +1,7 @@
 
 pub mod insts;
+/// Instruction semantics shared by the interpreter and the programs emitted by `rsfunge compile`
+pub mod semantics;
 
 mod alphabet_instruction_table;
 
@@ -0,0 +1,147 @@
+// This file is copied into the programs emitted by `rsfunge compile`, so it may only depend on std
+
+use std::io::{self, BufRead, Write};
+
+/// Read in place of malformed UTF-8 sequences
+pub const REPLACEMENT_CHARACTER: u32 = 0xfffd;
+
+/// The directions `?` picks from in Unefunge, Befunge and Trefunge, by the index of a random number
+pub const RANDOM_DIRECTIONS_1D: [i32; 2] = [-1, 1];
+pub const RANDOM_DIRECTIONS_2D: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+pub const RANDOM_DIRECTIONS_3D: [(i32, i32, i32); 6] = [(-1, 0, 0), (1, 0, 0), (0, -1, 0), (0, 1, 0), (0, 0, -1), (0, 0, 1)];
+
+/// Division of the primitive cell types, which wraps around on overflow.
+/// As per spec a division by zero results in zero.
+pub trait CellDivision: Sized {
+	fn div_or_zero(self, other: Self) -> Self;
+	
+	fn rem_or_zero(self, other: Self) -> Self;
+}
+
+macro_rules! impl_cell_division {
+	($($t:ty),*) => {
+		$(
+			impl CellDivision for $t {
+				#[inline(always)]
+				fn div_or_zero(self, other: Self) -> Self {
+					if other == 0 { 0 } else { self.wrapping_div(other) }
+				}
+				
+				#[inline(always)]
+				fn rem_or_zero(self, other: Self) -> Self {
+					if other == 0 { 0 } else { self.wrapping_rem(other) }
+				}
+			}
+		)*
+	};
+}
+
+impl_cell_division!(i32, i64, i128);
+
+/// A PCG32 (XSH RR) random number generator.
+/// Its output only depends on the seed, so a seed replays the same run everywhere.
+#[derive(Clone)]
+pub struct Pcg32 {
+	state: u64,
+}
+
+impl Pcg32 {
+	const MULTIPLIER: u64 = 6364136223846793005;
+	const INCREMENT: u64 = 1442695040888963407;
+	
+	/// Seeds the generator like the reference implementation does.
+	pub fn new(seed: u64) -> Self {
+		let mut rng = Pcg32 {
+			state: 0,
+		};
+		rng.next_u32();
+		rng.state = rng.state.wrapping_add(seed);
+		rng.next_u32();
+		return rng;
+	}
+	
+	pub fn next_u32(&mut self) -> u32 {
+		let old = self.state;
+		self.state = old.wrapping_mul(Self::MULTIPLIER).wrapping_add(Self::INCREMENT);
+		let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+		return xorshifted.rotate_right((old >> 59) as u32);
+	}
+	
+	/// Returns a random number below n (which must not be 0), without modulo bias.
+	pub fn below(&mut self, n: u32) -> u32 {
+		// Reject the values of the incomplete range at the bottom
+		let threshold = n.wrapping_neg() % n;
+		loop {
+			let value = self.next_u32();
+			if value >= threshold {
+				return value % n;
+			}
+		}
+	}
+}
+
+/// Reads a decimal number for `&`, skipping everything before the first digit.
+/// Stops before the digit that would overflow the value, leaving it unread.
+/// Returns None if the input ended (or failed) before a digit.
+pub fn read_integer<R, T, F>(reader: &mut R, push_digit: F) -> Option<T>
+where R: BufRead, T: Clone + Default, F: Fn(&T, u32) -> Option<T> {
+	let mut value: Option<T> = None;
+	loop {
+		// Peek next byte without consuming it
+		let next = match reader.fill_buf() {
+			Ok(buffer) if !buffer.is_empty() => buffer[0],
+			_ => break,
+		};
+		
+		if next.is_ascii_digit() {
+			match push_digit(&value.clone().unwrap_or_default(), (next - b'0') as u32) {
+				Some(v) => value = Some(v),
+				None => break,
+			}
+		}
+		// Stop after the last digit
+		else if value.is_some() {
+			break;
+		}
+		reader.consume(1);
+	}
+	return value;
+}
+
+/// Reads a single UTF-8 encoded character, malformed sequences are read as U+FFFD.
+/// Returns None at the end of the input.
+pub fn read_utf8_char<R: BufRead>(reader: &mut R) -> io::Result<Option<u32>> {
+	let first = match reader.fill_buf()?.first() {
+		Some(b) => *b,
+		None => return Ok(None),
+	};
+	reader.consume(1);
+	
+	// Get length of the sequence from the leading byte
+	let (len, mut value) = match first {
+		0x00..=0x7f => return Ok(Some(first as u32)),
+		0xc0..=0xdf => (2, (first & 0x1f) as u32),
+		0xe0..=0xef => (3, (first & 0x0f) as u32),
+		0xf0..=0xf7 => (4, (first & 0x07) as u32),
+		_ => return Ok(Some(REPLACEMENT_CHARACTER)),
+	};
+	
+	// Read continuation bytes, without consuming a byte that doesn't belong to the sequence
+	for _ in 1..len {
+		let next = match reader.fill_buf()?.first() {
+			Some(b) if (b & 0xc0) == 0x80 => *b,
+			_ => return Ok(Some(REPLACEMENT_CHARACTER)),
+		};
+		reader.consume(1);
+		value = (value << 6) | (next & 0x3f) as u32;
+	}
+	
+	return Ok(Some(std::char::from_u32(value).map(|c| c as u32).unwrap_or(REPLACEMENT_CHARACTER)));
+}
+
+/// Writes a character as UTF-8, values that aren't characters are written as `?`.
+pub fn write_utf8_char<W: Write>(writer: &mut W, value: i32) -> io::Result<()> {
+	let char = if value >= 0 {std::char::from_u32(value as u32)} else {None};
+	let mut buffer = [0u8; 4];
+	return writer.write_all(char.unwrap_or('?').encode_utf8(&mut buffer).as_bytes());
+}
@@ -9,6 +9,10 @@ mod funge_dimension;
 mod funge_space_accessor;
 mod funge_value;
 mod thread_list;
+mod funge_rng;
+mod funge_clock;
+mod resource_limits;
+mod trace_cache;
 
 pub mod instruction;
 pub mod fingerprint;
@@ -23,3 +27,7 @@ pub use funge_dimension::*;
 pub use funge_space_accessor::*;
 pub use funge_value::*;
 pub use thread_list::*;
+pub use funge_rng::*;
+pub use funge_clock::*;
+pub use resource_limits::*;
+pub use trace_cache::*;
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+/// Optional limits on the resources a funge program may use.
+/// A limit of None means unlimited.
+#[derive(Clone, Default)]
+pub struct ResourceLimits {
+	/// Maximum number of executed instructions (summed over all threads)
+	pub max_instructions: Option<u64>,
+	/// Maximum wall clock time of the execution
+	pub max_wall_time: Option<Duration>,
+	/// Maximum number of allocated funge space pages
+	pub max_pages: Option<u32>,
+	/// Maximum number of cells on all stacks of all threads
+	pub max_stack_cells: Option<u64>,
+	/// Maximum number of concurrently running threads
+	pub max_threads: Option<u32>,
+}
+
+impl ResourceLimits {
+	pub fn new() -> Self {
+		return ResourceLimits::default();
+	}
+}
+
+/// A resource limit that was exceeded, stopping the interpreter.
+///
+/// Each limit ends the process with its own exit status:
+///
+/// | Limit        | Exit status |
+/// |--------------|-------------|
+/// | Instructions | 120         |
+/// | Wall time    | 121         |
+/// | Pages        | 122         |
+/// | Stack cells  | 123         |
+/// | Threads      | 124         |
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ResourceLimit {
+	Instructions(u64),
+	WallTime(Duration),
+	Pages(u32),
+	StackCells(u64),
+	Threads(u32),
+}
+
+impl ResourceLimit {
+	pub fn exit_code(&self) -> i32 {
+		return match self {
+			ResourceLimit::Instructions(_) => 120,
+			ResourceLimit::WallTime(_) => 121,
+			ResourceLimit::Pages(_) => 122,
+			ResourceLimit::StackCells(_) => 123,
+			ResourceLimit::Threads(_) => 124,
+		};
+	}
+
+	/// Returns a message describing which limit was exceeded.
+	pub fn describe(&self) -> String {
+		return match self {
+			ResourceLimit::Instructions(n) => format!("instruction limit of {} executed instructions exceeded", n),
+			ResourceLimit::WallTime(d) => format!("wall time limit of {:.3}s exceeded", d.as_secs_f64()),
+			ResourceLimit::Pages(n) => format!("memory limit of {} funge space pages exceeded", n),
+			ResourceLimit::StackCells(n) => format!("stack limit of {} stack cells exceeded", n),
+			ResourceLimit::Threads(n) => format!("thread limit of {} concurrent threads exceeded", n),
+		};
+	}
+}
@@ -1,21 +1,21 @@
-use crate::interpreter::FungeThread;
+use crate::interpreter::{FungeThread, FungeValue};
 
 /// A list of funge threads kept in the right order.
-pub struct ThreadList<'s, 'f> {
-	threads: Vec<FungeThread<'s, 'f>>,
+pub struct ThreadList<'s, 'f, V: FungeValue = i32> {
+	threads: Vec<FungeThread<'s, 'f, V>>,
 }
-impl<'s, 'f> ThreadList<'s, 'f> {
+impl<'s, 'f, V: FungeValue> ThreadList<'s, 'f, V> {
 	pub fn new() -> Self {
 		ThreadList {
 			threads: Vec::with_capacity(8),
 		}
 	}
 	
-	pub fn get_mut(&mut self, index: u32) -> Option<&mut FungeThread<'s, 'f>> {
+	pub fn get_mut(&mut self, index: u32) -> Option<&mut FungeThread<'s, 'f, V>> {
 		return self.threads.get_mut(index as usize);
 	}
 	
-	pub fn get(&self, index: u32) -> Option<&FungeThread<'s, 'f>> {
+	pub fn get(&self, index: u32) -> Option<&FungeThread<'s, 'f, V>> {
 		return self.threads.get(index as usize);
 	}
 	
@@ -23,11 +23,29 @@ impl<'s, 'f> ThreadList<'s, 'f> {
 		return self.threads.len() as u32;
 	}
 	
-	pub fn test_add(&mut self, thread: FungeThread<'s, 'f>) {
+	/// Inserts the thread at the given index, shifting all
+	/// following threads up by one.
+	pub fn insert(&mut self, index: u32, thread: FungeThread<'s, 'f, V>) {
+		self.threads.insert(index as usize, thread);
+	}
+	
+	/// Removes the thread at the given index, shifting all
+	/// following threads down by one to keep them in order.
+	pub fn remove(&mut self, index: u32) -> FungeThread<'s, 'f, V> {
+		return self.threads.remove(index as usize);
+	}
+	
+	pub fn test_add(&mut self, thread: FungeThread<'s, 'f, V>) {
 		self.threads.push(thread);
 	}
 }
 
+impl<'s, 'f, V: FungeValue> Default for ThreadList<'s, 'f, V> {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
+
 //impl<'s> std::ops::Index<usize> for ThreadList<'s> {
 //	type Output = ThreadList<'s>;
 //	
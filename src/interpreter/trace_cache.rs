@@ -0,0 +1,327 @@
+use rustc_hash::FxHashMap;
+use crate::interpreter::{FungeAddress, FungeDimension, FungeSpace, FungeSpaceAccessor, FungeThread, FungeValue, InstructionDelta, InstructionPointer};
+use crate::interpreter::instruction::insts;
+
+/// How often the interpreter has to reach a run start before the run is compiled
+const HOT_THRESHOLD: u32 = 16;
+/// Maximum number of instructions in a single run
+const MAX_RUN_LENGTH: usize = 1024;
+/// Maximum number of spaces and comment cells skipped between two instructions of a run
+const MAX_SKIPPED_CELLS: u32 = 4096;
+
+/// A position and direction an ip can start a run from
+type TraceKey = (InstructionPointer, InstructionDelta);
+
+/// A decoded instruction that only works on the stack,
+/// so it neither moves the ip nor touches anything else.
+#[derive(Copy, Clone)]
+enum TraceOp<V: FungeValue> {
+	Push(i32),
+	Call(fn(&mut FungeThread<V>)),
+	Nop,
+}
+
+impl<V: FungeValue> TraceOp<V> {
+	#[inline]
+	fn decode(instruction: i32) -> Option<Self> {
+		return Some(match instruction {
+			/* 0...9 */ n @ 48..=57 => TraceOp::Push(n - 48),
+			/* a...f */ n @ 97..=102 => TraceOp::Push(n - 97 + 10),
+			/* ! */ 33 => TraceOp::Call(insts::inst_logical_not),
+			/* $ */ 36 => TraceOp::Call(insts::inst_pop),
+			/* % */ 37 => TraceOp::Call(insts::inst_remainder),
+			/* * */ 42 => TraceOp::Call(insts::inst_multiply),
+			/* + */ 43 => TraceOp::Call(insts::inst_add),
+			/* - */ 45 => TraceOp::Call(insts::inst_subtract),
+			/* / */ 47 => TraceOp::Call(insts::inst_divide),
+			/* : */ 58 => TraceOp::Call(insts::inst_duplicate),
+			/* \ */ 92 => TraceOp::Call(insts::inst_swap),
+			/* ` */ 96 => TraceOp::Call(insts::inst_greater_than),
+			/* n */ 110 => TraceOp::Call(insts::inst_clear_stack),
+			/* z */ 122 => TraceOp::Nop,
+			_ => return None,
+		});
+	}
+	
+	#[inline]
+	fn execute(&self, thread: &mut FungeThread<V>) {
+		match self {
+			TraceOp::Push(n) => insts::inst_push_number(thread, *n),
+			TraceOp::Call(f) => f(thread),
+			TraceOp::Nop => {}
+		}
+	}
+}
+
+struct TraceStep<V: FungeValue> {
+	op: TraceOp<V>,
+	/// The ip after executing the op, before skipping any spaces
+	next_ip: InstructionPointer,
+}
+
+/// A straight line of stack instructions along one direction.
+struct TraceRun<V: FungeValue> {
+	/// Unique id of the run, 0 if the run was invalidated and its slot is free
+	id: u64,
+	key: TraceKey,
+	steps: Vec<TraceStep<V>>,
+	/// All cells the run was decoded from, including skipped spaces and comments
+	cells: Vec<FungeAddress>,
+}
+
+/// The position of a thread within a compiled run.
+#[derive(Copy, Clone)]
+pub struct TraceCursor {
+	run: u32,
+	id: u64,
+	step: u32,
+}
+
+/// Cache of decoded instruction runs, used to execute hot loops
+/// without reading funge space, skipping spaces or wrapping the ip.
+///
+/// A run is a sequence of instructions that only work on the stack, as found
+/// along the path of an ip from a start position in a given direction.
+/// A thread executes a single instruction of a run per tick, so concurrent
+/// threads behave exactly as if every instruction was interpreted.
+/// The thread's ip always points to the cell after the executed
+/// instruction, so the thread can leave the run at any time.
+///
+/// Runs are invalidated as soon as one of the cells they were decoded
+/// from is written, and all runs are dropped if the bounds of the
+/// funge space grow, because that changes how ips wrap around.
+pub struct TraceCache<V: FungeValue> {
+	runs: Vec<TraceRun<V>>,
+	free_runs: Vec<u32>,
+	next_run_id: u64,
+	
+	/// Runs by the position and direction they start at
+	run_starts: FxHashMap<TraceKey, u32>,
+	/// How often each run start was reached before it was compiled
+	heat: FxHashMap<TraceKey, u32>,
+	/// The runs that were decoded from each watched cell
+	cell_runs: FxHashMap<FungeAddress, Vec<u32>>,
+}
+
+impl<V: FungeValue> TraceCache<V> {
+	pub fn new() -> Self {
+		TraceCache {
+			runs: Vec::new(),
+			free_runs: Vec::new(),
+			next_run_id: 1,
+			run_starts: FxHashMap::default(),
+			heat: FxHashMap::default(),
+			cell_runs: FxHashMap::default(),
+		}
+	}
+	
+	/// Checks if the given instruction can be part of a run.
+	#[inline]
+	pub fn is_traceable(instruction: i32) -> bool {
+		return TraceOp::<V>::decode(instruction).is_some();
+	}
+	
+	/// Lets the thread enter the run starting at its ip and executes the
+	/// first instruction, compiling the run if the start became hot.
+	/// Returns false if the instruction has to be interpreted instead.
+	pub fn enter<N, A>(&mut self, thread: &mut FungeThread<V>, funge_space: &mut FungeSpace<N, V, A>) -> bool
+		where N: FungeDimension, A: FungeSpaceAccessor<N, V> {
+		self.sync(funge_space);
+		
+		let key = (thread.ip, thread.delta);
+		let run_index = match self.run_starts.get(&key) {
+			Some(index) => *index,
+			None => {
+				let heat = self.heat.entry(key).or_insert(0);
+				*heat += 1;
+				if *heat < HOT_THRESHOLD {
+					return false;
+				}
+				self.heat.remove(&key);
+				self.compile(funge_space, key)
+			}
+		};
+		
+		thread.trace_cursor = Some(TraceCursor {
+			run: run_index,
+			id: self.runs[run_index as usize].id,
+			step: 0,
+		});
+		return self.step(thread, funge_space);
+	}
+	
+	/// Executes the next instruction of the run the thread is in.
+	/// Returns false if the thread isn't in a run (anymore), the
+	/// instruction at its ip has to be interpreted instead.
+	#[inline]
+	pub fn step<N, A>(&mut self, thread: &mut FungeThread<V>, funge_space: &mut FungeSpace<N, V, A>) -> bool
+		where N: FungeDimension, A: FungeSpaceAccessor<N, V> {
+		let cursor = match thread.trace_cursor {
+			Some(cursor) => cursor,
+			None => return false,
+		};
+		
+		self.sync(funge_space);
+		
+		// Leave the run if it was invalidated
+		let run = &self.runs[cursor.run as usize];
+		if run.id != cursor.id {
+			thread.trace_cursor = None;
+			return false;
+		}
+		
+		// Execute instruction and move ip
+		let step = &run.steps[cursor.step as usize];
+		step.op.execute(thread);
+		thread.ip = step.next_ip;
+		
+		// Advance cursor
+		thread.trace_cursor = if (cursor.step as usize + 1) < run.steps.len() {
+			Some(TraceCursor {
+				step: cursor.step + 1,
+				..cursor
+			})
+		}
+		else {
+			None
+		};
+		return true;
+	}
+	
+	/// Invalidates all runs that depend on cells written since the last sync.
+	#[inline]
+	fn sync<N, A>(&mut self, funge_space: &mut FungeSpace<N, V, A>) where N: FungeDimension, A: FungeSpaceAccessor<N, V> {
+		if !funge_space.has_changes() {
+			return;
+		}
+		
+		if funge_space.take_bounds_changed() {
+			for index in 0..self.runs.len() {
+				self.invalidate_run(index as u32, funge_space);
+			}
+			self.heat.clear();
+		}
+		
+		for address in funge_space.take_touched_cells() {
+			if let Some(runs) = self.cell_runs.get(&address).cloned() {
+				for index in runs {
+					self.invalidate_run(index, funge_space);
+				}
+			}
+		}
+	}
+	
+	fn invalidate_run<N, A>(&mut self, index: u32, funge_space: &mut FungeSpace<N, V, A>) where N: FungeDimension, A: FungeSpaceAccessor<N, V> {
+		let run = &mut self.runs[index as usize];
+		if run.id == 0 {
+			return;
+		}
+		run.id = 0;
+		run.steps.clear();
+		
+		self.run_starts.remove(&run.key);
+		
+		// Stop watching cells no other run depends on
+		for address in run.cells.drain(..) {
+			if let Some(runs) = self.cell_runs.get_mut(&address) {
+				runs.retain(|r| *r != index);
+				if runs.is_empty() {
+					self.cell_runs.remove(&address);
+					funge_space.unwatch_cell(&address);
+				}
+			}
+		}
+		
+		self.free_runs.push(index);
+	}
+	
+	/// Decodes the run starting at the given position and direction
+	/// and returns its index. The first instruction must be traceable.
+	fn compile<N, A>(&mut self, funge_space: &mut FungeSpace<N, V, A>, key: TraceKey) -> u32 where N: FungeDimension, A: FungeSpaceAccessor<N, V> {
+		let (start, delta) = key;
+		let read = |funge_space: &FungeSpace<N, V, A>, pos: &FungeAddress| funge_space.read_cell(pos).to_i32().unwrap_or(-1);
+		
+		let mut steps = Vec::new();
+		let mut cells = Vec::new();
+		let mut pos = start;
+		'compile:
+		while let Some(op) = TraceOp::decode(read(funge_space, &pos)) {
+			let next_ip = funge_space.next_ip(&pos, &delta);
+			steps.push(TraceStep {op, next_ip});
+			cells.push(pos);
+			if steps.len() >= MAX_RUN_LENGTH {
+				break;
+			}
+			
+			// Skip spaces and comments to the next instruction, just like the interpreter does
+			pos = next_ip;
+			let mut skipped = 0;
+			loop {
+				match read(funge_space, &pos) {
+					/* space */ 32 => {
+						cells.push(pos);
+						pos = funge_space.next_ip(&pos, &delta);
+					}
+					/* ; */ 59 => {
+						cells.push(pos);
+						while {
+							pos = funge_space.next_ip(&pos, &delta);
+							cells.push(pos);
+							skipped += 1;
+							(read(funge_space, &pos) != 59) && (skipped < MAX_SKIPPED_CELLS)
+						} {}
+						pos = funge_space.next_ip(&pos, &delta);
+					}
+					_ => break,
+				}
+				
+				skipped += 1;
+				if skipped >= MAX_SKIPPED_CELLS {
+					break 'compile;
+				}
+			}
+			
+			// Stop when the run loops back to its start
+			if pos == start {
+				break;
+			}
+		}
+		
+		// Watch all cells the run depends on
+		let index = match self.free_runs.pop() {
+			Some(index) => index,
+			None => {
+				self.runs.push(TraceRun {
+					id: 0,
+					key,
+					steps: Vec::new(),
+					cells: Vec::new(),
+				});
+				(self.runs.len() - 1) as u32
+			}
+		};
+		for address in cells.iter() {
+			let runs = self.cell_runs.entry(*address).or_default();
+			if !runs.contains(&index) {
+				runs.push(index);
+			}
+			funge_space.watch_cell(address);
+		}
+		
+		self.runs[index as usize] = TraceRun {
+			id: self.next_run_id,
+			key,
+			steps,
+			cells,
+		};
+		self.next_run_id += 1;
+		self.run_starts.insert(key, index);
+		return index;
+	}
+}
+
+impl<V: FungeValue> Default for TraceCache<V> {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
@@ -0,0 +1,256 @@
+use std::io::{self, BufRead, Write};
+use crate::io::Codepoint;
+use crate::interpreter::instruction::semantics;
+
+/// Replacement character for malformed input sequences
+const REPLACEMENT_CHARACTER: Codepoint = 0xfffd;
+
+/// The character encoding of source files and of the
+/// character input (`~`) and output (`,`) of funge programs.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CharEncoding {
+	/// UTF-8, a leading byte order mark is skipped. Bytes of invalid
+	/// sequences in files are loaded as one cell each, like in Latin-1
+	Utf8,
+	/// ISO 8859-1, every byte is one character
+	Latin1,
+	/// UTF-16, little endian unless a byte order mark says otherwise
+	Utf16,
+	/// Raw bytes, every byte is one cell and nothing is ever skipped or replaced
+	Binary,
+}
+
+impl CharEncoding {
+	/// Parses the encoding name given via `--encoding`.
+	pub fn from_name(name: &str) -> Option<Self> {
+		return match name.to_ascii_lowercase().as_str() {
+			"utf8" | "utf-8" => Some(CharEncoding::Utf8),
+			"latin1" | "latin-1" | "iso-8859-1" => Some(CharEncoding::Latin1),
+			"utf16" | "utf-16" => Some(CharEncoding::Utf16),
+			"binary" | "raw" => Some(CharEncoding::Binary),
+			_ => None,
+		};
+	}
+	
+	/// Decodes the contents of a whole file.
+	///
+	/// A byte order mark at the start of the data takes precedence over
+	/// the selected text encoding, so a UTF-16 file with a BOM is decoded
+	/// correctly even if UTF-8 was selected. Latin-1 and binary data
+	/// is never checked for a BOM.
+	pub fn decode(&self, bytes: &[u8]) -> io::Result<Vec<Codepoint>> {
+		let is_text = *self == CharEncoding::Utf8 || *self == CharEncoding::Utf16;
+		
+		// Detect byte order mark
+		if is_text {
+			if let Some(data) = bytes.strip_prefix(&[0xef, 0xbb, 0xbf]) {
+				return Ok(Self::decode_utf8(data));
+			}
+			if let Some(data) = bytes.strip_prefix(&[0xff, 0xfe]) {
+				return Ok(Self::decode_utf16(data, false));
+			}
+			if let Some(data) = bytes.strip_prefix(&[0xfe, 0xff]) {
+				return Ok(Self::decode_utf16(data, true));
+			}
+		}
+		
+		return match self {
+			CharEncoding::Utf8 => Ok(Self::decode_utf8(bytes)),
+			CharEncoding::Utf16 => Ok(Self::decode_utf16(bytes, false)),
+			CharEncoding::Latin1 | CharEncoding::Binary => Ok(bytes.iter().map(|b| *b as Codepoint).collect()),
+		};
+	}
+	
+	/// Decodes UTF-8, falling back to one cell per byte for invalid sequences,
+	/// so files in other 8-bit encodings still load like they do in Latin-1.
+	fn decode_utf8(mut bytes: &[u8]) -> Vec<Codepoint> {
+		let mut codepoints = Vec::with_capacity(bytes.len());
+		
+		loop {
+			match std::str::from_utf8(bytes) {
+				Ok(string) => {
+					codepoints.extend(string.chars().map(|c| c as Codepoint));
+					return codepoints;
+				}
+				Err(error) => {
+					// Decode the valid part, then take the bytes of the invalid sequence as they are
+					let (valid, rest) = bytes.split_at(error.valid_up_to());
+					codepoints.extend(std::str::from_utf8(valid).unwrap().chars().map(|c| c as Codepoint));
+					
+					let invalid_len = error.error_len().unwrap_or(rest.len());
+					codepoints.extend(rest[..invalid_len].iter().map(|b| *b as Codepoint));
+					bytes = &rest[invalid_len..];
+				}
+			}
+		}
+	}
+	
+	fn decode_utf16(bytes: &[u8], big_endian: bool) -> Vec<Codepoint> {
+		let units = bytes.chunks(2).map(|pair| {
+			// Pad a trailing odd byte with zero
+			let (a, b) = (pair[0], *pair.get(1).unwrap_or(&0));
+			if big_endian {u16::from_be_bytes([a, b])} else {u16::from_le_bytes([a, b])}
+		});
+		
+		return std::char::decode_utf16(units)
+			.map(|r| r.map(|c| c as Codepoint).unwrap_or(REPLACEMENT_CHARACTER))
+			.collect();
+	}
+	
+	/// Reads a single character from the reader.
+	/// Returns None at the end of the input. Malformed sequences
+	/// are read as U+FFFD.
+	pub fn read_char<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<Codepoint>> {
+		return match self {
+			CharEncoding::Latin1 | CharEncoding::Binary => Ok(Self::read_byte(reader)?.map(|b| b as Codepoint)),
+			CharEncoding::Utf8 => semantics::read_utf8_char(reader),
+			CharEncoding::Utf16 => Self::read_utf16_char(reader),
+		};
+	}
+	
+	fn read_utf16_char<R: BufRead>(reader: &mut R) -> io::Result<Option<Codepoint>> {
+		let first = match Self::read_byte(reader)? {
+			Some(b) => b,
+			None => return Ok(None),
+		};
+		let unit = match Self::read_byte(reader)? {
+			Some(second) => u16::from_le_bytes([first, second]),
+			None => return Ok(Some(REPLACEMENT_CHARACTER)),
+		};
+		if !(0xd800..0xdc00).contains(&unit) {
+			return Ok(Some(std::char::decode_utf16(Some(unit)).next().unwrap().map(|c| c as Codepoint).unwrap_or(REPLACEMENT_CHARACTER)));
+		}
+		
+		// Read low surrogate
+		let mut pair = [0u8; 2];
+		if reader.read_exact(&mut pair).is_err() {
+			return Ok(Some(REPLACEMENT_CHARACTER));
+		}
+		let low = u16::from_le_bytes(pair);
+		return Ok(Some(std::char::decode_utf16([unit, low].iter().cloned()).next().unwrap().map(|c| c as Codepoint).unwrap_or(REPLACEMENT_CHARACTER)));
+	}
+	
+	fn read_byte<R: BufRead>(reader: &mut R) -> io::Result<Option<u8>> {
+		let byte = reader.fill_buf()?.first().cloned();
+		if byte.is_some() {
+			reader.consume(1);
+		}
+		return Ok(byte);
+	}
+	
+	/// Writes a single character. Characters that can't be encoded
+	/// are written as `?`, except in binary mode which writes the low byte.
+	pub fn write_char<W: Write>(&self, writer: &mut W, value: i32) -> io::Result<()> {
+		let char = if value >= 0 {std::char::from_u32(value as u32)} else {None};
+		
+		return match self {
+			CharEncoding::Binary => writer.write_all(&[value as u8]),
+			CharEncoding::Latin1 => writer.write_all(&[if (0..256).contains(&value) {value as u8} else {b'?'}]),
+			CharEncoding::Utf8 => semantics::write_utf8_char(writer, value),
+			CharEncoding::Utf16 => {
+				let mut buffer = [0u16; 2];
+				for unit in char.unwrap_or('?').encode_utf16(&mut buffer).iter() {
+					writer.write_all(&unit.to_le_bytes())?;
+				}
+				Ok(())
+			}
+		};
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	
+	fn codepoints(text: &str) -> Vec<Codepoint> {
+		return text.chars().map(|c| c as Codepoint).collect();
+	}
+	
+	fn read_all(encoding: CharEncoding, bytes: &[u8]) -> Vec<Codepoint> {
+		let mut reader = bytes;
+		let mut result = Vec::new();
+		while let Some(c) = encoding.read_char(&mut reader).unwrap() {
+			result.push(c);
+		}
+		return result;
+	}
+	
+	fn write_all(encoding: CharEncoding, values: &[i32]) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		for value in values {
+			encoding.write_char(&mut bytes, *value).unwrap();
+		}
+		return bytes;
+	}
+	
+	#[test]
+	fn utf8_byte_order_mark_is_skipped() {
+		assert_eq!(CharEncoding::Utf8.decode(b"\xef\xbb\xbfa\xc3\xa9").unwrap(), codepoints("a\u{e9}"));
+		assert_eq!(CharEncoding::Utf16.decode(b"\xef\xbb\xbfa\xc3\xa9").unwrap(), codepoints("a\u{e9}"));
+	}
+	
+	#[test]
+	fn utf16_byte_order_mark_takes_precedence() {
+		assert_eq!(CharEncoding::Utf8.decode(b"\xff\xfea\x00\xac\x20").unwrap(), codepoints("a\u{20ac}"));
+		assert_eq!(CharEncoding::Utf8.decode(b"\xfe\xff\x00a\x20\xac").unwrap(), codepoints("a\u{20ac}"));
+		assert_eq!(CharEncoding::Utf16.decode(b"\xfe\xff\x00a\x20\xac").unwrap(), codepoints("a\u{20ac}"));
+	}
+	
+	#[test]
+	fn byte_order_marks_are_not_detected_in_latin1_and_binary() {
+		assert_eq!(CharEncoding::Latin1.decode(b"\xff\xfea").unwrap(), vec![0xff, 0xfe, 0x61]);
+		assert_eq!(CharEncoding::Binary.decode(b"\xef\xbb\xbfa").unwrap(), vec![0xef, 0xbb, 0xbf, 0x61]);
+	}
+	
+	#[test]
+	fn utf16_without_byte_order_mark_is_little_endian() {
+		assert_eq!(CharEncoding::Utf16.decode(b"a\x00\x3d\xd8\x00\xde").unwrap(), codepoints("a\u{1f600}"));
+		
+		// Unpaired surrogates are replaced, a trailing odd byte is padded with zero
+		assert_eq!(CharEncoding::Utf16.decode(b"\x00\xd8a\x00b").unwrap(), vec![REPLACEMENT_CHARACTER, 0x61, 0x62]);
+	}
+	
+	#[test]
+	fn invalid_utf8_falls_back_to_bytes() {
+		assert_eq!(CharEncoding::Utf8.decode(b"a\xe9b\xc3\xa9").unwrap(), vec![0x61, 0xe9, 0x62, 0xe9]);
+		
+		// A truncated sequence at the end
+		assert_eq!(CharEncoding::Utf8.decode(b"a\xe2\x82").unwrap(), vec![0x61, 0xe2, 0x82]);
+	}
+	
+	#[test]
+	fn binary_keeps_every_byte() {
+		assert_eq!(CharEncoding::Binary.decode(b"\x00\x0c\x80\xff").unwrap(), vec![0x00, 0x0c, 0x80, 0xff]);
+	}
+	
+	#[test]
+	fn read_char_decodes_one_character_at_a_time() {
+		assert_eq!(read_all(CharEncoding::Utf8, "a\u{e9}\u{20ac}\u{1f600}".as_bytes()), codepoints("a\u{e9}\u{20ac}\u{1f600}"));
+		assert_eq!(read_all(CharEncoding::Latin1, b"a\xe9"), vec![0x61, 0xe9]);
+		assert_eq!(read_all(CharEncoding::Binary, b"\x00\xff"), vec![0x00, 0xff]);
+		assert_eq!(read_all(CharEncoding::Utf16, b"a\x00\x3d\xd8\x00\xde"), codepoints("a\u{1f600}"));
+	}
+	
+	#[test]
+	fn read_char_replaces_malformed_sequences() {
+		// A missing continuation byte doesn't swallow the next character
+		assert_eq!(read_all(CharEncoding::Utf8, b"\xc3a"), vec![REPLACEMENT_CHARACTER, 0x61]);
+		assert_eq!(read_all(CharEncoding::Utf8, b"\x80"), vec![REPLACEMENT_CHARACTER]);
+		assert_eq!(read_all(CharEncoding::Utf16, b"a"), vec![REPLACEMENT_CHARACTER]);
+	}
+	
+	#[test]
+	fn write_char_encodes_each_encoding() {
+		assert_eq!(write_all(CharEncoding::Utf8, &[0x61, 0xe9, 0x20ac]), "a\u{e9}\u{20ac}".as_bytes());
+		assert_eq!(write_all(CharEncoding::Latin1, &[0x61, 0xe9]), b"a\xe9");
+		assert_eq!(write_all(CharEncoding::Utf16, &[0x61, 0x1f600]), b"a\x00\x3d\xd8\x00\xde");
+		assert_eq!(write_all(CharEncoding::Binary, &[0x61, 0x1ff]), b"a\xff");
+	}
+	
+	#[test]
+	fn write_char_replaces_characters_that_cant_be_encoded() {
+		assert_eq!(write_all(CharEncoding::Utf8, &[-1, 0xd800]), b"??");
+		assert_eq!(write_all(CharEncoding::Latin1, &[0x20ac, -1]), b"??");
+		assert_eq!(write_all(CharEncoding::Utf16, &[-1]), b"?\x00");
+	}
+}
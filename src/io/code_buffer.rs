@@ -11,6 +11,8 @@ pub type Codepoint = u32;
 pub struct CodeBuffer {
 	pub lines: Vec<CodeBufferLine>,
 	pub bounding_box: Vector3<u32>,
+	/// The number of dimensions the code actually uses, 3 if it
+	/// contains form feeds, 2 if it has multiple lines and 1 otherwise
 	pub dimensionality: u32,
 	
 	_phantom: PhantomData<()>,
@@ -24,6 +26,73 @@ impl CodeBuffer {
 			_phantom: PhantomData,
 		};
 	}
+	
+	/// Returns the position of the first cell of each line, relative
+	/// to the top left corner of the code's bounding box.
+	/// 
+	/// Lines after an End terminator are not part of the code and
+	/// therefore don't get a position.
+	pub fn line_positions(&self) -> Vec<Vector3<i32>> {
+		let mut positions = Vec::with_capacity(self.lines.len());
+		let mut offset = Vector3::<i32>::new();
+		
+		for line in self.lines.iter() {
+			positions.push(offset);
+			
+			// Process terminator
+			match line.terminator {
+				LineTerminator::FeedY => { offset.set_y(offset.y().wrapping_add(1)); },
+				LineTerminator::FeedZ => {
+					// Each form feed starts a new plane at y = 0
+					offset.set_y(0);
+					offset.set_z(offset.z().wrapping_add(1));
+				},
+				LineTerminator::End => break,
+			}
+		}
+		return positions;
+	}
+	
+	/// Returns the zero based line and column in the source file
+	/// of the first cell of each line.
+	/// 
+	/// A form feed doesn't start a new line in the source file, so the
+	/// line after a FeedZ terminator continues in the same source line.
+	pub fn text_positions(&self) -> Vec<(u32, u32)> {
+		let mut positions = Vec::with_capacity(self.lines.len());
+		let (mut line_num, mut column) = (0u32, 0u32);
+		
+		for line in self.lines.iter() {
+			positions.push((line_num, column));
+			
+			// Process terminator
+			match line.terminator {
+				LineTerminator::FeedY => {
+					line_num += 1;
+					column = 0;
+				},
+				LineTerminator::FeedZ => {
+					// Skip the line's cells and the form feed itself
+					column += line.data.len() as u32 + 1;
+				},
+				LineTerminator::End => break,
+			}
+		}
+		return positions;
+	}
+	
+	/// Returns an iterator over all cells of the code (including spaces)
+	/// together with their position relative to the top left corner
+	/// of the code's bounding box.
+	pub fn cells(&self) -> impl Iterator<Item=(Vector3<i32>, Codepoint)> + '_ {
+		let positions = self.line_positions();
+		
+		return self.lines.iter().zip(positions).flat_map(|(line, pos)| {
+			line.data.iter().enumerate().map(move |(x, value)| {
+				(Vector3::new_xyz(pos.x().wrapping_add(x as i32), pos.y(), pos.z()), *value)
+			})
+		});
+	}
 }
 
 pub struct CodeBufferLine {
@@ -1,22 +1,27 @@
 use std::error::Error;
-use std::marker::PhantomData;
+use std::fmt::{self, Display};
 use std::path::Path;
 use std::fs::{OpenOptions};
-use std::io::Read;
+use std::io::{self, Read};
 use std::iter::Peekable;
-use std::str::Chars;
+use std::slice::Iter;
 use std::cmp;
-use crate::io::{CodeBuffer, CodeSource, CodeBufferLine, LineTerminator};
+use crate::io::{CodeBuffer, CodeSource, CodeBufferLine, LineTerminator, CharEncoding, Codepoint};
 use crate::vector::Vector3;
 
 /// Loads funge source code from file into a code buffer.
 pub struct CodeLoader {
-	_phantom: PhantomData<()>,
+	encoding: CharEncoding,
 }
 impl CodeLoader {
+	/// Creates a code loader for UTF-8 encoded files.
 	pub fn new() -> Self {
+		return CodeLoader::with_encoding(CharEncoding::Utf8);
+	}
+	
+	pub fn with_encoding(encoding: CharEncoding) -> Self {
 		return CodeLoader {
-			_phantom: PhantomData,
+			encoding,
 		};
 	}
 	
@@ -24,38 +29,79 @@ impl CodeLoader {
 	/// Returns either a code buffer with the code if it could be loaded correctly or
 	/// an Err if an error occured.
 	/// 
-	/// The files contents are assumed to be UTF-8 encoded and the resulting
+	/// The files contents are decoded with the encoding of this loader and the resulting
 	/// code buffer will not contain any line feed, carriage return, carriage return + line feed
 	/// or form feed control codes.
-	pub fn load_from_file(&mut self, source: CodeSource) -> Result<CodeBuffer, impl Error> {
-		// Open file
-		let path: &Path = source.get_path();
+	/// 
+	/// If the source has a dialect, the code is loaded for the dimensionality
+	/// of the dialect (see `load_from_file_with_dimensionality`).
+	pub fn load_from_file(&mut self, source: CodeSource) -> Result<CodeBuffer, CodeReadError> {
+		let dimensionality = source.get_dialect().map(|dialect| dialect.dimensionality());
+		return self.load_from_file_with_dimensionality(source.get_path(), dimensionality);
+	}
+	
+	/// Loads funge* source code like `load_from_file`, but for the given dimensionality
+	/// instead of the one of a dialect, as done by `i` in the dimensionality of the running program.
+	/// 
+	/// Form feeds are ignored if the dimensionality is less than three. Code with more
+	/// dimensions than that (like line feeds in Unefunge) is rejected.
+	pub fn load_from_file_with_dimensionality(&mut self, path: &Path, dimensionality: Option<u32>) -> Result<CodeBuffer, CodeReadError> {
+		let mut contents = self.read_file(path)?;
+		if let Some(dimensionality) = dimensionality {
+			if dimensionality < 3 {
+				contents.retain(|c| *c != 0x0c);
+			}
+		}
+		let code_buffer = self.load_from_codepoints(&contents);
+		
+		// Check dimensionality, only line feeds in Unefunge can exceed it after form feeds were removed
+		if let Some(dimensionality) = dimensionality {
+			if code_buffer.dimensionality > dimensionality {
+				return Err(CodeReadError::InvalidDimensions {
+					dialect_dimensionality: dimensionality,
+					code_dimensionality: code_buffer.dimensionality,
+				});
+			}
+		}
+		return Ok(code_buffer);
+	}
+	
+	/// Loads the given file as a single line without interpreting
+	/// any line feed or form feed control codes, as done by `i` in binary mode.
+	pub fn load_from_file_binary(&mut self, path: &Path) -> io::Result<CodeBuffer> {
+		let contents = self.read_file(path)?;
+		
+		let mut line = CodeBufferLine::new(contents.len() as u32);
+		line.data = contents;
 		
+		let bounding_box = Vector3::<u32>::new_xyz(cmp::max(line.data.len() as u32, 1), 1, 1);
+		return Ok(CodeBuffer::new(vec![line], bounding_box, 0));
+	}
+	
+	/// Reads and decodes the contents of the given file
+	fn read_file(&self, path: &Path) -> io::Result<Vec<Codepoint>> {
+		// Open file
 		let mut open_opts = OpenOptions::new();
 		open_opts.read(true);
 		
-		let file = open_opts.open(path);
-		
-		// Handle error opening file
-		if let Err(e) = file {
-			return Err(e);
-		}
-		let mut file = file.unwrap();
+		let mut file = open_opts.open(path)?;
 		
 		// Read file
-		let mut string_contents = String::new();
-		#[allow(dead_code)]
-		let num_read_bytes = file.read_to_string(&mut string_contents)?;
-		
-		// TODO: Support different encodings (maybe via the rust-encoding crate)
+		let mut contents = Vec::<u8>::new();
+		file.read_to_end(&mut contents)?;
 		
+		return self.encoding.decode(&contents);
+	}
+	
+	/// Splits the decoded source code into lines.
+	pub fn load_from_codepoints(&self, contents: &[Codepoint]) -> CodeBuffer {
 		// Allocate line buffer
 		let mut line_buffer = Vec::<CodeBufferLine>::with_capacity(64);
 		
 		let mut bounding_box = Vector3::<u32>::new_value(1);
 		
 		// Consume data
-		let mut iter: Peekable<Chars> = string_contents.chars().peekable();
+		let mut iter: Peekable<Iter<Codepoint>> = contents.iter().peekable();
 		'lineloop:
 		loop {
 			// Allocate new line
@@ -72,22 +118,22 @@ impl CodeLoader {
 //			for char in iter {
 			loop {
 				let char = if let Some(c) = iter.next() {
-					c
+					*c
 				}
 				else {
 					break 'sourceloop;
 				};
 				
 				match char {
-					'\n' => { // Line feed (10)
+					0x0a => { // Line feed (10)
 						// Set terminator
 						current_line.terminator = LineTerminator::FeedY;
 						break 'sourceloop;
 					}
-					'\r' => { // Carriage return (13)
+					0x0d => { // Carriage return (13)
 						// Check if next char is line feed to make it a carriage return, line feed combo
 						if let Some(n) = iter.peek() {
-							if *n == '\n' {
+							if **n == 0x0a {
 								// Consume line feed
 								iter.next();
 							}
@@ -97,14 +143,14 @@ impl CodeLoader {
 						current_line.terminator = LineTerminator::FeedY;
 						break 'sourceloop;
 					}
-					'\u{c}' => { // Form feed (12), increment z coord
+					0x0c => { // Form feed (12), increment z coord
 						// Set terminator
 						current_line.terminator = LineTerminator::FeedZ;
 						break 'sourceloop;
 					}
 					_ => {
 						// Put char into lin
-						current_line.data.push(char as u32);
+						current_line.data.push(char);
 					}
 				}
 			}
@@ -113,34 +159,67 @@ impl CodeLoader {
 			bounding_box.set_x(cmp::max(bounding_box.x(), current_line.data.len() as u32));
 			
 			// Put line into buffer
-			let current_terminator = current_line.terminator;
 			line_buffer.push(current_line);
 			
 			// Check if we still have something left
-			if let None = iter.peek() {
+			if iter.peek().is_none() {
 				break 'lineloop;
 			}
-			else {
-				// Increase bounding box (only if there's actually another line)
-				match current_terminator {
-					LineTerminator::FeedY => {
-						bounding_box.set_y(bounding_box.y() + 1);
-					},
-					LineTerminator::FeedZ => {
-						bounding_box.set_z(bounding_box.z() + 1);
-					},
-					_ => {},
-				}
-			}
 		}
 		
-		// TODO: Actually handle dimensionality properly
 		// Make code buffer instance
-		let code_buffer = CodeBuffer::new(line_buffer, bounding_box, 0);
-		return Ok(code_buffer);
+		let mut code_buffer = CodeBuffer::new(line_buffer, bounding_box, 0);
+		
+		// Extend the bounding box to all lines that actually contain code,
+		// so trailing line feeds and form feeds don't add empty rows or planes
+		let positions = code_buffer.line_positions();
+		for (line, pos) in code_buffer.lines.iter().zip(positions) {
+			if !line.data.is_empty() {
+				bounding_box.set_y(cmp::max(bounding_box.y(), pos.y() as u32 + 1));
+				bounding_box.set_z(cmp::max(bounding_box.z(), pos.z() as u32 + 1));
+			}
+		}
+		code_buffer.bounding_box = bounding_box;
+		
+		// Derive dimensionality from the bounding box
+		code_buffer.dimensionality = if bounding_box.z() > 1 {3}
+		else if bounding_box.y() > 1 {2}
+		else {1};
+		
+		return code_buffer;
 	}
 }
 
+impl Default for CodeLoader {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
+
+#[derive(Debug)]
 pub enum CodeReadError {
-	InvalidDimensions,
+	Io(io::Error),
+	/// The code has more dimensions than the dialect it was loaded for
+	InvalidDimensions {
+		dialect_dimensionality: u32,
+		code_dimensionality: u32,
+	},
+}
+impl Error for CodeReadError {
+	
+}
+impl Display for CodeReadError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+		match self {
+			CodeReadError::Io(e) => write!(f, "{}", e),
+			CodeReadError::InvalidDimensions {dialect_dimensionality, code_dimensionality} => {
+				write!(f, "the code has {} dimensions but the dialect only supports {}", code_dimensionality, dialect_dimensionality)
+			}
+		}
+	}
+}
+impl From<io::Error> for CodeReadError {
+	fn from(e: io::Error) -> Self {
+		CodeReadError::Io(e)
+	}
 }
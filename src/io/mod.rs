@@ -2,7 +2,11 @@
 mod code_source;
 mod code_loader;
 mod code_buffer;
+mod char_encoding;
+mod source_map;
 
 pub use code_source::*;
 pub use code_loader::*;
 pub use code_buffer::*;
+pub use char_encoding::*;
+pub use source_map::*;
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use crate::io::{CodeSource, CodeBuffer};
+use crate::interpreter::FungeAddress;
+use crate::vector::Vector3;
+
+/// A source file that was loaded into funge space.
+#[derive(Clone)]
+pub struct SourcePlacement {
+	pub source: CodeSource,
+	/// The position of the top left corner of the code's bounding box
+	pub origin: FungeAddress,
+	pub size: Vector3<u32>,
+}
+
+impl SourcePlacement {
+	/// Checks if the given address lies within the bounding box of this placement.
+	pub fn contains(&self, address: &FungeAddress) -> bool {
+		let in_range = |value: i32, origin: i32, size: u32| {
+			let offset = (value as i64) - (origin as i64);
+			offset >= 0 && offset < size as i64
+		};
+		return in_range(address.x(), self.origin.x(), self.size.x())
+			&& in_range(address.y(), self.origin.y(), self.size.y())
+			&& in_range(address.z(), self.origin.z(), self.size.z());
+	}
+}
+
+/// The origin of a single loaded cell.
+#[derive(Copy, Clone)]
+struct CellOrigin {
+	/// Index of the placement the cell was loaded by
+	placement: u32,
+	/// Zero based line and column in the source file
+	line: u32,
+	column: u32,
+	/// The cell was overwritten at runtime (by `p` or `s`)
+	dynamic: bool,
+}
+
+/// A position in a source file.
+pub struct SourceLocation<'m> {
+	pub source: &'m CodeSource,
+	/// One based line number
+	pub line: u32,
+	/// One based column number
+	pub column: u32,
+	/// The cell was overwritten at runtime, so it no longer holds the code from the source file
+	pub dynamic: bool,
+}
+
+impl Display for SourceLocation<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+		write!(f, "{}:{}:{}", self.source.get_path().display(), self.line, self.column)?;
+		if self.dynamic {
+			write!(f, " (modified)")?;
+		}
+		return Ok(());
+	}
+}
+
+/// Records where the loaded source files were placed in funge space
+/// and which file, line and column each loaded cell came from, so that
+/// diagnostics can refer to files instead of raw coordinates.
+pub struct SourceMap {
+	placements: Vec<SourcePlacement>,
+	cells: HashMap<FungeAddress, CellOrigin>,
+}
+
+impl SourceMap {
+	pub fn new() -> Self {
+		SourceMap {
+			placements: Vec::new(),
+			cells: HashMap::new(),
+		}
+	}
+	
+	/// Records the placement of the given code and the origin of each of its non-space cells.
+	/// Spaces are transparent when loading, so they don't change the origin of a cell.
+	pub fn add_placement(&mut self, placement: SourcePlacement, code: &CodeBuffer) {
+		let index = self.placements.len() as u32;
+		
+		let positions = code.line_positions();
+		let text_positions = code.text_positions();
+		for ((line, pos), (text_line, text_column)) in code.lines.iter().zip(positions).zip(text_positions) {
+			for (x, value) in line.data.iter().enumerate() {
+				if *value == 32 {
+					continue;
+				}
+				
+				let mut address = placement.origin;
+				address.add_wrapping(&FungeAddress::new_xyz(pos.x().wrapping_add(x as i32), pos.y(), pos.z()));
+				self.cells.insert(address, CellOrigin {
+					placement: index,
+					line: text_line,
+					column: text_column + x as u32,
+					dynamic: false,
+				});
+			}
+		}
+		self.placements.push(placement);
+	}
+	
+	/// Marks the cell at the given address as modified at runtime.
+	#[inline]
+	pub fn mark_dynamic(&mut self, address: &FungeAddress) {
+		if let Some(origin) = self.cells.get_mut(address) {
+			origin.dynamic = true;
+		}
+	}
+	
+	/// Returns all placements in the order they were loaded.
+	pub fn placements(&self) -> &[SourcePlacement] {
+		return &self.placements;
+	}
+	
+	/// Returns the most recently loaded placement whose bounding box contains the given address.
+	pub fn find_placement(&self, address: &FungeAddress) -> Option<&SourcePlacement> {
+		return self.placements.iter().rev().find(|p| p.contains(address));
+	}
+	
+	/// Returns the source location of the cell at the given address,
+	/// or None if the cell wasn't loaded from a file.
+	pub fn lookup(&self, address: &FungeAddress) -> Option<SourceLocation<'_>> {
+		let origin = self.cells.get(address)?;
+		return Some(SourceLocation {
+			source: &self.placements[origin.placement as usize].source,
+			line: origin.line + 1,
+			column: origin.column + 1,
+			dynamic: origin.dynamic,
+		});
+	}
+	
+	/// Describes the cell at the given address for diagnostics, like
+	/// `prog.b98:12:7`, falling back to raw coordinates like `(6, 11, 0)`.
+	pub fn describe(&self, address: &FungeAddress) -> String {
+		return match self.lookup(address) {
+			Some(location) => location.to_string(),
+			None => format!("({}, {}, {})", address.x(), address.y(), address.z()),
+		};
+	}
+}
+
+impl Default for SourceMap {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use crate::FungeDialect;
+use crate::compiler::{FungeCfg, FungeCfgTarget};
+use crate::interpreter::FungeAddress;
+use crate::io::{CodeBuffer, CodeSource, SourceMap, SourcePlacement};
+
+/// Maximum number of characters of unreachable code quoted in a message
+const MAX_QUOTED_CHARS: usize = 16;
+
+/// The kinds of mistakes the linter finds.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum LintKind {
+	/// Non-space cells no ip ever passes over
+	UnreachableCode,
+	/// An instruction that doesn't exist in the dialect, which reflects
+	UnsupportedInstruction,
+	/// A fingerprint instruction (A-Z) on a path without any `(` before it
+	FingerprintNotLoaded,
+	/// A string that isn't closed on its line
+	UnterminatedString,
+	/// A `;` comment that isn't closed on its line
+	UnterminatedComment,
+	/// The control flow depends on runtime values, so unreachable code isn't reported
+	IncompleteAnalysis,
+}
+
+impl LintKind {
+	/// Returns the name used in the text and JSON output.
+	pub fn name(&self) -> &'static str {
+		return match self {
+			LintKind::UnreachableCode => "unreachable-code",
+			LintKind::UnsupportedInstruction => "unsupported-instruction",
+			LintKind::FingerprintNotLoaded => "fingerprint-not-loaded",
+			LintKind::UnterminatedString => "unterminated-string",
+			LintKind::UnterminatedComment => "unterminated-comment",
+			LintKind::IncompleteAnalysis => "incomplete-analysis",
+		};
+	}
+	
+	pub fn severity(&self) -> &'static str {
+		return match self {
+			LintKind::IncompleteAnalysis => "note",
+			_ => "warning",
+		};
+	}
+}
+
+pub struct LintFinding {
+	pub kind: LintKind,
+	pub address: FungeAddress,
+	pub message: String,
+}
+
+/// Common mistakes in a program, found through the static control flow graph.
+pub struct LintReport {
+	source_map: SourceMap,
+	pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+	/// Lints the given code, which was loaded at the origin and analyzed into the given control flow graph.
+	pub fn new(code: &CodeBuffer, source: &CodeSource, cfg: &FungeCfg, dialect: FungeDialect) -> Self {
+		let mut source_map = SourceMap::new();
+		source_map.add_placement(SourcePlacement {
+			source: source.clone(),
+			origin: FungeAddress::new_value(0),
+			size: code.bounding_box,
+		}, code);
+		
+		let mut findings = Vec::new();
+		Self::find_unsupported_instructions(cfg, dialect, &mut findings);
+		if !matches!(dialect, FungeDialect::Befunge93) {
+			Self::find_unloaded_fingerprints(cfg, &mut findings);
+		}
+		for address in cfg.unterminated_strings.iter() {
+			findings.push(LintFinding {
+				kind: LintKind::UnterminatedString,
+				address: *address,
+				message: String::from("string is not closed on its line, so it wraps around to its opening quote"),
+			});
+		}
+		for address in cfg.unterminated_comments.iter() {
+			findings.push(LintFinding {
+				kind: LintKind::UnterminatedComment,
+				address: *address,
+				message: String::from("`;` is not closed on its line, so the comment wraps around to itself"),
+			});
+		}
+		Self::find_unreachable_code(code, cfg, &mut findings);
+		
+		findings.sort_by_key(|f| (f.address.z(), f.address.y(), f.address.x(), f.kind));
+		return LintReport {
+			source_map,
+			findings,
+		};
+	}
+	
+	fn find_unsupported_instructions(cfg: &FungeCfg, dialect: FungeDialect, findings: &mut Vec<LintFinding>) {
+		let mut reported = HashSet::new();
+		for block in cfg.blocks.iter() {
+			for (address, instruction) in block.instructions.iter() {
+				if !dialect.supports_instruction(*instruction) && reported.insert(*address) {
+					findings.push(LintFinding {
+						kind: LintKind::UnsupportedInstruction,
+						address: *address,
+						message: format!("`{}` is not a {} instruction and reflects", Self::printable_char(*instruction), dialect.name()),
+					});
+				}
+			}
+		}
+	}
+	
+	/// Finds fingerprint instructions that no path with a `(` leads to.
+	fn find_unloaded_fingerprints(cfg: &FungeCfg, findings: &mut Vec<LintFinding>) {
+		// Find all blocks that can be reached after loading a fingerprint
+		let mut after_load = vec![false; cfg.blocks.len()];
+		let mut worklist: Vec<usize> = cfg.blocks.iter()
+			.filter(|block| block.instructions.iter().any(|(_, instruction)| *instruction == 40))
+			.flat_map(|block| block.edges.iter())
+			.filter_map(|edge| match edge.target {
+				FungeCfgTarget::Block(index) => Some(index),
+				_ => None,
+			})
+			.collect();
+		while let Some(index) = worklist.pop() {
+			if after_load[index] {
+				continue;
+			}
+			after_load[index] = true;
+			for edge in cfg.blocks[index].edges.iter() {
+				if let FungeCfgTarget::Block(next) = edge.target {
+					worklist.push(next);
+				}
+			}
+		}
+		
+		let mut reported = HashSet::new();
+		for (index, block) in cfg.blocks.iter().enumerate() {
+			let mut loaded = after_load[index];
+			for (address, instruction) in block.instructions.iter() {
+				match instruction {
+					/* ( */ 40 => loaded = true,
+					/* A...Z */ 65..=90 if !loaded && reported.insert(*address) => {
+						findings.push(LintFinding {
+							kind: LintKind::FingerprintNotLoaded,
+							address: *address,
+							message: format!("`{}` is used without loading a fingerprint with `(` first, so it reflects", Self::printable_char(*instruction)),
+						});
+					}
+					_ => {}
+				}
+			}
+		}
+	}
+	
+	/// Reports runs of unreachable non-space cells, or a single note if the control flow isn't fully known.
+	fn find_unreachable_code(code: &CodeBuffer, cfg: &FungeCfg, findings: &mut Vec<LintFinding>) {
+		// Unknown moves could reach any cell
+		for block in cfg.blocks.iter() {
+			if block.edges.iter().any(|edge| edge.target == FungeCfgTarget::Unknown) {
+				let (address, instruction) = block.instructions.last().unwrap();
+				findings.push(LintFinding {
+					kind: LintKind::IncompleteAnalysis,
+					address: *address,
+					message: format!("`{}` moves the ip to a position that's only known at runtime, so unreachable code isn't reported", Self::printable_char(*instruction)),
+				});
+				return;
+			}
+		}
+		
+		let mut unreachable: Vec<(FungeAddress, i32)> = code.cells()
+			.filter(|(offset, value)| *value != 32 && !cfg.visited_cells.contains(offset))
+			.map(|(offset, value)| (offset, value as i32))
+			.collect();
+		unreachable.sort_by_key(|(address, _)| (address.z(), address.y(), address.x()));
+		
+		// Report each horizontal run of cells once
+		let mut i = 0;
+		while i < unreachable.len() {
+			let (start, _) = unreachable[i];
+			let mut end = i + 1;
+			while end < unreachable.len() {
+				let (address, _) = unreachable[end];
+				let (previous, _) = unreachable[end - 1];
+				if address.y() != start.y() || address.z() != start.z() || address.x() != previous.x() + 1 {
+					break;
+				}
+				end += 1;
+			}
+			
+			let mut text: String = unreachable[i..end].iter().take(MAX_QUOTED_CHARS).map(|(_, value)| Self::printable_char(*value)).collect();
+			if end - i > MAX_QUOTED_CHARS {
+				text.push_str("...");
+			}
+			findings.push(LintFinding {
+				kind: LintKind::UnreachableCode,
+				address: start,
+				message: format!("unreachable code `{}`", text),
+			});
+			i = end;
+		}
+	}
+	
+	/// Formats the findings like compiler diagnostics, one per line.
+	pub fn format_text(&self) -> String {
+		let mut text = String::new();
+		for finding in self.findings.iter() {
+			let _ = writeln!(text, "{}: {}: {} [{}]", self.source_map.describe(&finding.address), finding.kind.severity(), finding.message, finding.kind.name());
+		}
+		return text;
+	}
+	
+	/// Formats the findings as a JSON array for editor integration.
+	pub fn format_json(&self) -> String {
+		let mut json = String::from("[");
+		for (i, finding) in self.findings.iter().enumerate() {
+			let (file, line, column) = match self.source_map.lookup(&finding.address) {
+				Some(location) => (location.source.get_path().display().to_string(), location.line, location.column),
+				None => (String::new(), 0, 0),
+			};
+			let _ = write!(json, "{}\n\t{{\"file\": \"{}\", \"line\": {}, \"column\": {}, \"severity\": \"{}\", \"kind\": \"{}\", \"message\": \"{}\"}}",
+				if i == 0 {""} else {","}, Self::escape_json(&file), line, column, finding.kind.severity(), finding.kind.name(), Self::escape_json(&finding.message));
+		}
+		json.push_str("\n]\n");
+		return json;
+	}
+	
+	fn printable_char(value: i32) -> char {
+		match std::char::from_u32(value as u32) {
+			Some(c) if !c.is_control() => c,
+			_ => '?',
+		}
+	}
+	
+	fn escape_json(text: &str) -> String {
+		let mut escaped = String::with_capacity(text.len());
+		for c in text.chars() {
+			match c {
+				'"' => escaped.push_str("\\\""),
+				'\\' => escaped.push_str("\\\\"),
+				c if c.is_control() => { let _ = write!(escaped, "\\u{:04x}", c as u32); }
+				_ => escaped.push(c),
+			}
+		}
+		return escaped;
+	}
+}
@@ -0,0 +1,3 @@
+mod lint_report;
+
+pub use lint_report::*;
@@ -1,19 +1,22 @@
 #![allow(unused_parens)]
 #![allow(unused_variables)]
 
-#![feature(specialization)]
-#![feature(trait_alias)]
+// This codebase uses explicit returns
+#![allow(clippy::needless_return)]
 
 #[macro_use]
 extern crate lazy_static;
 
 pub mod cli;
-//pub mod error;
+pub mod error;
 pub mod utils;
 pub mod interpreter;
 pub mod io;
 pub mod vector;
 pub mod buffer;
+pub mod coverage;
+pub mod lint;
+pub mod compiler;
 //pub mod charout;
 //pub mod charin;
 
@@ -27,6 +30,12 @@ pub mod final_ref;
 
 fn main() {
 	// Start the program
-	let exit_code = crate::cli::start();
+	let exit_code = match crate::cli::start() {
+		Ok(code) => code,
+		Err(e) => {
+			eprintln!("rsfunge: {}", e);
+			e.exit_code()
+		}
+	};
 	std::process::exit(exit_code);
 }
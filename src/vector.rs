@@ -65,3 +65,9 @@ impl<T: Copy + Default> Vector3<T> {
 		return self;
 	}
 }
+
+impl<T: Copy + Default> Default for Vector3<T> {
+	fn default() -> Self {
+		return Self::new();
+	}
+}
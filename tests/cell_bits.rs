@@ -0,0 +1,57 @@
+mod common;
+
+use common::{run_file, run_program, write_temp_file};
+
+/// The fixed cell widths with the largest and smallest value a cell can hold
+const WIDTHS: [(&str, &str, &str); 3] = [
+	("32", "2147483647", "-2147483648"),
+	("64", "9223372036854775807", "-9223372036854775808"),
+	("128", "170141183460469231731687303715884105727", "-170141183460469231731687303715884105728"),
+];
+
+#[test]
+fn input_and_output_use_the_full_cell_width() {
+	// Reads a number and prints it, then prints it plus one, which wraps around
+	let path = write_temp_file("cell_bits_wrap.b98", "&:.1+.@");
+	for (bits, max, min) in WIDTHS.iter() {
+		let result = run_file(&path, &["--cell-bits", bits], max);
+		assert_eq!(result.exit_code, Some(0));
+		assert_eq!(result.stdout, format!("{} {} ", max, min), "{}", bits);
+	}
+	
+	let big = "1701411834604692317316873037158841057270000";
+	let result = run_file(&path, &["--cell-bits", "big"], big);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, format!("{} {}1 ", big, &big[..big.len() - 1]));
+}
+
+#[test]
+fn input_leaves_a_digit_that_would_overflow_unread() {
+	let path = write_temp_file("cell_bits_overflow.b98", "&.&.@");
+	for (bits, max, _) in WIDTHS.iter() {
+		// One more than the largest value, the last digit is read by the second &
+		let input = format!("{}{}", &max[..max.len() - 1], (max.as_bytes()[max.len() - 1] - b'0' + 1));
+		let result = run_file(&path, &["--cell-bits", bits], &input);
+		assert_eq!(result.exit_code, Some(0));
+		assert_eq!(result.stdout, format!("{} {} ", &input[..input.len() - 1], &input[input.len() - 1..]), "{}", bits);
+	}
+}
+
+#[test]
+fn fetch_character_pushes_the_codepoint() {
+	for bits in &["32", "64", "128", "big"] {
+		let result = run_program("cell_bits_fetch.b98", "'\u{20ac}.'A.@", &["--cell-bits", bits]);
+		assert_eq!(result.exit_code, Some(0));
+		assert_eq!(result.stdout, "8364 65 ", "{}", bits);
+	}
+}
+
+#[test]
+fn sysinfo_reports_the_bytes_per_cell() {
+	// Big cells have no fixed size
+	for (bits, bytes) in &[("32", "4"), ("64", "8"), ("128", "16"), ("big", "0")] {
+		let result = run_program("cell_bits_sysinfo.b98", "2y.@", &["--cell-bits", bits]);
+		assert_eq!(result.exit_code, Some(0));
+		assert_eq!(result.stdout, format!("{} ", bytes), "{}", bits);
+	}
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::run_program;
+
+/// Pushes the date and time cells of y (cells 20 and 21 in Befunge-98) and prints them
+const PRINT_DATE_TIME: &str = "45*y.37*y.@";
+
+/// 2020-01-02 as pushed by y
+const DATE: &str = "7864578";
+/// 03:04:05 as pushed by y
+const TIME: i32 = 197637;
+
+#[test]
+fn fake_time_is_fixed() {
+	let result = run_program("clock_fixed.b98", PRINT_DATE_TIME, &["--fake-time", "2020-01-02T03:04:05"]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, format!("{} {} ", DATE, TIME));
+}
+
+#[test]
+fn tick_micros_advances_every_instruction() {
+	// 8 instructions run before the second y
+	let result = run_program("clock_ticking.b98", PRINT_DATE_TIME, &["--fake-time", "2020-01-02T03:04:05", "--tick-micros", "1000000"]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, format!("{} {} ", DATE, TIME + 8));
+}
+
+#[test]
+fn tick_micros_counts_instructions_of_all_threads() {
+	// The child thread wraps around to @ and stops after one instruction,
+	// so 10 instructions run before the second y in 9 scheduler rounds
+	let source = format!("t{}", PRINT_DATE_TIME);
+	let result = run_program("clock_threads.b98", &source, &["--fake-time", "2020-01-02T03:04:05", "--tick-micros", "1000000"]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, format!("{} {} ", DATE, TIME + 10));
+}
+
+#[test]
+fn hrti_uses_the_fake_time() {
+	// G, then T three ticks after M and S after 17 ticks of 3000000123 microseconds
+	let args = ["--fake-time", "2020-01-02T03:04:05", "--tick-micros", "3000000123"];
+	let result = run_program("clock_hrti.b98", "\"ITRH\"4($$G.MzzT.S.@", &[&args[..], &["--cell-bits", "64"]].concat());
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "3000000123 9000000369 2091 ");
+	
+	// Values too large for the cells saturate
+	let result = run_program("clock_hrti_saturating.b98", "\"ITRH\"4($$G.MzzT.S.@", &args);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "2147483647 2147483647 2091 ");
+}
+
+#[test]
+fn ticking_clock_stops_at_the_last_date() {
+	// Each tick is about 3169 years, so the clock leaves the range of dates after 82 ticks
+	let source = format!("{}{}", "z".repeat(100), PRINT_DATE_TIME);
+	let result = run_program("clock_saturating.b98", &source, &["--fake-time", "2020-01-02T03:04:05", "--tick-micros", "100000000000000000"]);
+	assert_eq!(result.exit_code, Some(0));
+	
+	// 262143-12-31 23:59:59, the date wraps around in a 32-bit cell
+	assert_eq!(result.stdout, "-124580833 1522491 ");
+}
+
+#[test]
+fn tick_micros_out_of_range_is_rejected() {
+	for tick_micros in &["--tick-micros=-1", "--tick-micros=9223372036854775807", "--tick-micros=x"] {
+		let result = run_program("clock_invalid.b98", PRINT_DATE_TIME, &["--fake-time", "2020-01-02T03:04:05", tick_micros]);
+		assert_eq!(result.exit_code, Some(2), "{}", tick_micros);
+		assert!(result.stdout.is_empty());
+	}
+}
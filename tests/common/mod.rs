@@ -0,0 +1,96 @@
+// Not every test crate uses every helper
+#![allow(dead_code)]
+// This codebase uses explicit returns
+#![allow(clippy::needless_return)]
+
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Runs longer than this are killed and fail the test.
+const RUN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Output and exit code of an rsfunge run.
+pub struct RunResult {
+	pub stdout: String,
+	pub stderr: String,
+	/// None if the process was killed by a signal
+	pub exit_code: Option<i32>,
+}
+
+/// Writes the source to a file with the given name in the
+/// temporary directory of the tests and runs rsfunge on it.
+pub fn run_program(file_name: &str, source: &str, args: &[&str]) -> RunResult {
+	let path = write_temp_file(file_name, source);
+	return run_file(&path, args, "");
+}
+
+/// Returns the path of a file with the given name in the temporary directory of the tests.
+pub fn temp_path(file_name: &str) -> String {
+	return PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(file_name).to_str().unwrap().to_string();
+}
+
+/// Writes a file with the given name to the temporary directory of the tests and returns its path.
+pub fn write_temp_file(file_name: &str, contents: &str) -> String {
+	let path = temp_path(file_name);
+	std::fs::write(&path, contents).unwrap();
+	return path;
+}
+
+/// Runs rsfunge on the given file with the given options and input.
+pub fn run_file(path: &str, args: &[&str], input: &str) -> RunResult {
+	return run_file_with_env(path, args, input, &[]);
+}
+
+/// Runs rsfunge on the given file with the given options, input and environment variables.
+pub fn run_file_with_env(path: &str, args: &[&str], input: &str, vars: &[(&str, &str)]) -> RunResult {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_rsfunge"))
+		.args(args)
+		.envs(vars.iter().cloned())
+		.arg(path)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+	
+	// Write the whole input, closing stdin so reads hit the end of input afterwards
+	let mut stdin = child.stdin.take().unwrap();
+	let input = input.as_bytes().to_vec();
+	let input_writer = thread::spawn(move || {
+		let _ = stdin.write_all(&input);
+	});
+	
+	// Read output in the background so the process never blocks on a full pipe
+	let stdout_reader = read_in_background(child.stdout.take().unwrap());
+	let stderr_reader = read_in_background(child.stderr.take().unwrap());
+	
+	let start = Instant::now();
+	let status = loop {
+		if let Some(status) = child.try_wait().unwrap() {
+			break status;
+		}
+		if start.elapsed() > RUN_TIMEOUT {
+			let _ = child.kill();
+			panic!("rsfunge {:?} {} did not finish within {:?}", args, path, RUN_TIMEOUT);
+		}
+		thread::sleep(Duration::from_millis(5));
+	};
+	
+	input_writer.join().unwrap();
+	return RunResult {
+		stdout: stdout_reader.join().unwrap(),
+		stderr: stderr_reader.join().unwrap(),
+		exit_code: status.code(),
+	};
+}
+
+fn read_in_background<R: Read + Send + 'static>(mut reader: R) -> thread::JoinHandle<String> {
+	return thread::spawn(move || {
+		let mut buffer = Vec::new();
+		let _ = reader.read_to_end(&mut buffer);
+		return String::from_utf8_lossy(&buffer).into_owned();
+	});
+}
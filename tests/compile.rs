@@ -0,0 +1,67 @@
+// This codebase uses explicit returns
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use common::{run_file, temp_path, write_temp_file, RunResult};
+
+/// Compiles the program to Rust, builds it with rustc and checks that it
+/// behaves like the interpreter with the same seed and input. Returns the compile run.
+fn assert_compiled_like_interpreted(name: &str, source: &str, input: &str) -> RunResult {
+	let path = write_temp_file(&format!("{}.bf", name), source);
+	let rust_file = temp_path(&format!("{}.rs", name));
+	let binary = temp_path(&format!("{}_compiled", name));
+	
+	let compiled = run_file(&path, &["compile", "--seed", "42", "-o", &rust_file], "");
+	assert_eq!(compiled.exit_code, Some(0), "{}", compiled.stderr);
+	let emitted = std::fs::read_to_string(&rust_file).unwrap();
+	assert!(emitted.contains("const RNG_SEED: u64 = 42;"));
+	
+	let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+	let build = Command::new(rustc).args(["-O", "-o", &binary, &rust_file]).output().unwrap();
+	assert!(build.status.success(), "{}", String::from_utf8_lossy(&build.stderr));
+	
+	let mut child = Command::new(&binary)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.spawn()
+		.unwrap();
+	child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+	let output = child.wait_with_output().unwrap();
+	
+	let interpreted = run_file(&path, &["--seed", "42", "--max-ticks", "100000"], input);
+	assert_eq!(interpreted.exit_code, Some(0), "{}", interpreted.stderr);
+	assert_eq!(output.status.code(), Some(0));
+	assert_eq!(String::from_utf8_lossy(&output.stdout), interpreted.stdout);
+	return compiled;
+}
+
+#[test]
+fn static_program_runs_like_interpreted() {
+	let compiled = assert_compiled_like_interpreted("compile_static", "&&+.~,\"!olleH\",,,,,,55+,73%.70/.@", "12 30 xyz");
+	assert!(compiled.stderr.is_empty(), "{}", compiled.stderr);
+}
+
+#[test]
+fn random_program_takes_the_paths_of_the_interpreter() {
+	// Goes away with ? 64 times and prints a number for each path it took
+	let source = concat!(
+		"88*>:#v_@ >  v\n",
+		"      1   .\n",
+		"      -   2\n",
+		"      >   ?1.v\n",
+		"          3\n",
+		"          .\n",
+		"   ^      <  <\n",
+	);
+	assert_compiled_like_interpreted("compile_random", source, "");
+}
+
+#[test]
+fn self_modifying_program_falls_back_to_the_interpreter() {
+	// Only prints 5 if the space written over is executed
+	let compiled = assert_compiled_like_interpreted("compile_self_modifying", "\".\"70p5 10g,@", "");
+	assert!(compiled.stderr.contains("may modify its own code"), "{}", compiled.stderr);
+}
@@ -0,0 +1,83 @@
+// This codebase uses explicit returns
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{run_file, run_program, temp_path, write_temp_file};
+
+/// Reads a number and stops at the left @ if it isn't zero, or prints 1 and stops at the right @
+const BRANCH_SOURCE: &str = "&v\n@_1.@";
+
+/// Runs the branching program with the given input, merging its coverage into the given data file.
+fn run_branch(data_file: &str, input: &str) -> String {
+	let path = write_temp_file("coverage_branch.b98", BRANCH_SOURCE);
+	let report = temp_path("coverage_branch.txt");
+	let result = run_file(&path, &["--coverage", &report, "--coverage-data", data_file], input);
+	assert_eq!(result.exit_code, Some(0), "{}", result.stderr);
+	return std::fs::read_to_string(&report).unwrap();
+}
+
+#[test]
+fn text_report_marks_executed_cells() {
+	let report_file = temp_path("coverage_text.txt");
+	let result = run_program("coverage_text.b98", "1#2.@", &["--coverage", &report_file]);
+	assert_eq!(result.exit_code, Some(0));
+	
+	let report = std::fs::read_to_string(&report_file).unwrap();
+	assert!(report.contains("Executed 4 of 5 non-space cells (80.00%), 0 read by g, 0 overwritten by p"), "{}", report);
+	assert!(report.contains("   0 | 1#2.@\n     | ##.##\n"), "{}", report);
+	assert!(report.contains("coverage_text.b98:1:3 (2, 0, 0) '2'"), "{}", report);
+}
+
+#[test]
+fn text_report_marks_read_and_written_cells() {
+	// g reads the 5 at (0, 1) and p writes to (1, 1)
+	let report_file = temp_path("coverage_read_written.txt");
+	let result = run_program("coverage_read_written.b98", "01g11p@\n56", &["--coverage", &report_file]);
+	assert_eq!(result.exit_code, Some(0));
+	
+	let report = std::fs::read_to_string(&report_file).unwrap();
+	assert!(report.contains("Executed 7 of 9 non-space cells (77.78%), 1 read by g, 1 overwritten by p"), "{}", report);
+	assert!(report.contains("   1 | 56\n     | gp\n"), "{}", report);
+}
+
+#[test]
+fn html_report_marks_executed_cells() {
+	let report_file = temp_path("coverage_html.html");
+	let result = run_program("coverage_html.b98", "1#2.@", &["--coverage", &report_file]);
+	assert_eq!(result.exit_code, Some(0));
+	
+	let report = std::fs::read_to_string(&report_file).unwrap();
+	assert!(report.starts_with("<!DOCTYPE html>"));
+	assert!(report.contains("<span class=\"exec\" title=\"(1, 0, 0)\">#</span><span class=\"miss\" title=\"(2, 0, 0)\">2</span>"), "{}", report);
+	assert!(report.contains("coverage_html.b98:1:3 (2, 0, 0) '2'</code></li>"), "{}", report);
+}
+
+#[test]
+fn coverage_data_is_merged_across_runs() {
+	let data_file = temp_path("coverage_merged.cov");
+	let _ = std::fs::remove_file(&data_file);
+	
+	let report = run_branch(&data_file, "1");
+	assert!(report.contains("Executed 4 of 7 non-space cells"), "{}", report);
+	
+	let report = run_branch(&data_file, "0");
+	assert!(report.contains("Executed 7 of 7 non-space cells"), "{}", report);
+}
+
+#[test]
+fn coverage_data_of_other_sources_is_rejected() {
+	let data_file = temp_path("coverage_changed.cov");
+	let _ = std::fs::remove_file(&data_file);
+	
+	let result = run_program("coverage_changed.b98", "1.@", &["--coverage-data", &data_file]);
+	assert_eq!(result.exit_code, Some(0));
+	let data = std::fs::read_to_string(&data_file).unwrap();
+	
+	// Same file name, different program
+	let result = run_program("coverage_changed.b98", "1.@    \n 12345", &["--coverage-data", &data_file]);
+	assert_eq!(result.exit_code, Some(2));
+	assert!(result.stderr.contains("was recorded for other source files"), "{}", result.stderr);
+	assert_eq!(result.stdout, "", "the program must not run");
+	assert_eq!(std::fs::read_to_string(&data_file).unwrap(), data);
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::{run_file, run_program, temp_path};
+
+#[test]
+fn missing_source_file_exits_with_3() {
+	let path = temp_path("errors_missing.b98");
+	let _ = std::fs::remove_file(&path);
+	
+	let result = run_file(&path, &[], "");
+	assert_eq!(result.exit_code, Some(3));
+	assert!(result.stderr.starts_with("rsfunge: failed to load code from"), "{}", result.stderr);
+}
+
+#[test]
+fn unsupported_dialect_exits_with_4() {
+	let result = run_program("errors_unefunge.u98", "1.@", &[]);
+	assert_eq!(result.exit_code, Some(4));
+	assert!(result.stderr.contains("Unefunge-98 is not supported yet"), "{}", result.stderr);
+	
+	let result = run_program("errors_dialect.b98", "1.@", &["--dialect", "c99"]);
+	assert_eq!(result.exit_code, Some(4));
+	assert!(result.stderr.contains("Unknown funge dialect 'c99'"), "{}", result.stderr);
+}
+
+#[test]
+fn unwritable_report_exits_with_5() {
+	// The program runs, but the report can't be written into a directory that doesn't exist
+	let report_file = temp_path("errors_missing_dir/report.txt");
+	let result = run_program("errors_io.b98", "1.@", &["--coverage", &report_file]);
+	assert_eq!(result.exit_code, Some(5));
+	assert_eq!(result.stdout, "1 ");
+	assert!(result.stderr.contains("I/O error on"), "{}", result.stderr);
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use common::{run_file, run_program};
+
+#[test]
+fn bool_test_program_is_good() {
+	let result = run_file("test/bool_test.b98", &[], "");
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "GOOD\n");
+}
+
+#[test]
+fn bool_instructions_are_dispatched_through_a_to_z() {
+	// ( pushes the fingerprint id and 1, then and, or, xor and not of 6 and 5
+	for cell_bits in &["32", "64", "128", "big"] {
+		let result = run_program("fingerprints_bool.b98", "\"LOOB\"4(..65A.65O.65X.0N.@", &["--cell-bits", cell_bits]);
+		assert_eq!(result.exit_code, Some(0));
+		assert_eq!(result.stdout, "1 1112493900 4 7 3 -1 ", "{}", cell_bits);
+	}
+}
+
+#[test]
+fn bool_instructions_reflect_after_unloading() {
+	// The reflected A runs into the @ that # jumped over, a loaded A would print its result
+	let result = run_program("fingerprints_bool_unloaded.b98", "\"LOOB\"4($$\"LOOB\"4)#@A.@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "");
+	
+	let result = run_program("fingerprints_bool_loaded.b98", "\"LOOB\"4($$#@A.@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "0 ");
+}
+
+#[test]
+fn fixp_integer_instructions() {
+	// and, or, xor, negate and absolute value
+	let result = run_program("fingerprints_fixp.b98", "\"PXIF\"4($$65A.65O.65X.5N.5NV.@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "4 7 3 -5 5 ");
+}
+
+#[test]
+fn fixp_power_saturates_at_the_cell_width() {
+	// 2 to the 60th and 0 to the -2nd, which is infinite
+	let big_max = format!("{:.0}", f64::MAX);
+	let expected = [
+		("32", "2147483647", "2147483647"),
+		("64", "1152921504606846976", "9223372036854775807"),
+		("128", "1152921504606846976", "170141183460469231731687303715884105727"),
+		("big", "1152921504606846976", big_max.as_str()),
+	];
+	for (cell_bits, power, infinite) in &expected {
+		let result = run_program("fingerprints_fixp_power.b98", "\"PXIF\"4($$2\"<\"R.002-R.@", &["--cell-bits", cell_bits]);
+		assert_eq!(result.exit_code, Some(0));
+		assert_eq!(result.stdout, format!("{} {} ", power, infinite), "{}", cell_bits);
+	}
+}
+
+#[test]
+fn float_print_instruction() {
+	// 12, 1/3, 0/0, 1/0, -1/0 and -1
+	for fingerprint in &["FPSP", "FPDP"] {
+		let source = format!("\"{}\"4($$cFP1F3FDP0F0FDP1F0FDP01-F0FDP1FNP@", fingerprint.chars().rev().collect::<String>());
+		let result = run_program("fingerprints_float_print.b98", &source, &[]);
+		assert_eq!(result.exit_code, Some(0));
+		assert_eq!(result.stdout, "12.000000 0.333333 NaN inf -inf -1.000000 ", "{}", fingerprint);
+	}
+}
+
+#[test]
+fn float_cells_hold_the_bits() {
+	// 3 and -1, single precision in one cell and double precision in two cells with the low bits on top
+	let expected = [
+		("FPSP", "1077936128 0 -1082130432 0 "),
+		("FPDP", "0 1074266112 0 -1074790400 "),
+	];
+	for (fingerprint, output) in &expected {
+		for cell_bits in &["32", "64", "big"] {
+			let source = format!("\"{}\"4($$3F..01-F..@", fingerprint.chars().rev().collect::<String>());
+			let result = run_program("fingerprints_float_bits.b98", &source, &["--cell-bits", cell_bits]);
+			assert_eq!(result.exit_code, Some(0));
+			assert_eq!(result.stdout, *output, "{} {}", fingerprint, cell_bits);
+		}
+	}
+}
+
+#[test]
+fn orth_bitwise_instructions() {
+	// and, or and exclusive or
+	let result = run_program("fingerprints_orth.b98", "\"HTRO\"4($$65A.65O.65E.@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "4 7 3 ");
+}
+
+#[test]
+fn roma_pushes_the_numerals() {
+	let result = run_program("fingerprints_roma.b98", "\"AMOR\"4($$IVXLCDM.......@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "1000 500 100 50 10 5 1 ");
+}
+
+#[test]
+fn toys_stack_instructions() {
+	// Negate 5, then two copies of 7 with A
+	let result = run_program("fingerprints_toys_stack.b98", "\"SYOT\"4($$5N.72A...@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "-5 7 7 0 ");
+}
+
+#[test]
+fn toys_shift_instruction() {
+	// 1 shifted left by 100 and back, 1 shifted left by 63, -5 and -1 shifted right by 1 and 100
+	let expected = [
+		("32", "0 0 0 -3 -1 "),
+		("64", "0 0 -9223372036854775808 -3 -1 "),
+		("128", "1267650600228229401496703205376 1 9223372036854775808 -3 -1 "),
+		("big", "1267650600228229401496703205376 1 9223372036854775808 -3 -1 "),
+	];
+	for (cell_bits, output) in &expected {
+		let result = run_program("fingerprints_toys_shift.b98", "\"SYOT\"4($$1'dH:.0'd-H.1'?H.05-01-H.01-0'd-H.@", &["--cell-bits", cell_bits]);
+		assert_eq!(result.exit_code, Some(0));
+		assert_eq!(result.stdout, *output, "{}", cell_bits);
+	}
+}
+
+#[test]
+fn toys_box_instructions() {
+	// Fill a 2x2 box at (0, 5) with 9 and push it back with G, copy its top row to (2, 5),
+	// then write a 2x2 matrix at (0, 7) with F
+	let source = "\"SYOT\"4($$922 05S 22 05G.... 05 21 25C 25g.35g.45g. 1234 22 07F 07g.17g.08g.18g.@";
+	let result = run_program("fingerprints_toys_box.b98", source, &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "9 9 9 9 9 9 32 4 3 2 1 ");
+}
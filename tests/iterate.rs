@@ -0,0 +1,37 @@
+mod common;
+
+use common::run_program;
+
+#[test]
+fn iterate_executes_the_next_instruction_once_more() {
+	// 3k+ adds three times before the + itself adds a fourth time
+	let result = run_program("iterate_add.b98", "11112 3k+.@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "6 ");
+	
+	let result = run_program("iterate_push.b98", "3k5....@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "5 5 5 5 ");
+}
+
+#[test]
+fn iterate_zero_times_skips_the_next_instruction() {
+	let result = run_program("iterate_zero.b98", "10k..@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "1 ");
+}
+
+#[test]
+fn iterate_negative_times_reflects() {
+	// The reflected ip wraps around to the @ without printing
+	let result = run_program("iterate_negative.b98", "01-k 5.@", &["--max-ticks", "100"]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "");
+}
+
+#[test]
+fn iterate_skips_spaces_and_comments() {
+	let result = run_program("iterate_comments.b98", "2k ;x; 7...@", &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "7 7 7 ");
+}
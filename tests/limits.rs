@@ -0,0 +1,85 @@
+mod common;
+
+use common::run_program;
+
+/// Pushes 64^5, a count far beyond all limits
+const HUGE_COUNT: &str = "\"@@@@@\"****";
+
+#[test]
+fn max_ticks_exits_with_120() {
+	let result = run_program("limits_ticks.b98", ">#<", &["--max-ticks", "100"]);
+	assert_eq!(result.exit_code, Some(120));
+	assert!(result.stderr.contains("instruction limit of 100"));
+}
+
+#[test]
+fn max_ticks_counts_iterations_of_k() {
+	let source = format!("{}kz@", HUGE_COUNT);
+	let result = run_program("limits_ticks_k.b98", &source, &["--max-ticks", "100"]);
+	assert_eq!(result.exit_code, Some(120));
+}
+
+#[test]
+fn max_time_exits_with_121() {
+	let result = run_program("limits_time.b98", ">#<", &["--max-time", "1"]);
+	assert_eq!(result.exit_code, Some(121));
+}
+
+#[test]
+fn max_pages_exits_with_122() {
+	// Writes to two cells far away from the code and each other
+	let result = run_program("limits_pages.b98", "1\"d\"::*p1\"d\":*:p@", &["--max-pages", "2"]);
+	assert_eq!(result.exit_code, Some(122));
+	assert!(result.stderr.contains("limits_pages.b98:1:17"));
+}
+
+#[test]
+fn max_stack_exits_with_123() {
+	let result = run_program("limits_stack.b98", ">1<", &["--max-stack", "10"]);
+	assert_eq!(result.exit_code, Some(123));
+}
+
+#[test]
+fn max_stack_stops_a_single_instruction() {
+	// Each of these would allocate gigabytes in one instruction
+	let sources = [
+		format!("{}k1@", HUGE_COUNT),
+		format!("{}{{@", HUGE_COUNT),
+		format!("0{{{}u@", HUGE_COUNT),
+		format!("0{{{}}}@", HUGE_COUNT),
+	];
+	for source in sources.iter() {
+		let result = run_program("limits_stack_single.b98", source, &["--max-stack", "10", "--max-ticks", "100", "--max-time", "1"]);
+		assert_eq!(result.exit_code, Some(123), "{}", source);
+	}
+}
+
+#[test]
+fn toys_boxes_stop_at_the_limits() {
+	// Each of these would fill or read billions of cells in one instruction
+	let toys = "\"SYOT\"4(";
+	let cases = [
+		(format!("{}00{}155C@", toys, HUGE_COUNT), 122),
+		(format!("{}00{}155S@", toys, HUGE_COUNT), 122),
+		(format!("{}0{}1 55F@", toys, HUGE_COUNT), 122),
+		(format!("{}{}1 55G@", toys, HUGE_COUNT), 123),
+		(format!("{}1{}A@", toys, HUGE_COUNT), 123),
+	];
+	for (source, exit_code) in cases.iter() {
+		let result = run_program("limits_toys.b98", source, &["--max-pages", "10", "--max-stack", "1000", "--max-time", "2"]);
+		assert_eq!(result.exit_code, Some(*exit_code), "{}", source);
+	}
+}
+
+#[test]
+fn max_threads_exits_with_124() {
+	let result = run_program("limits_threads.b98", "tttt@", &["--max-threads", "3"]);
+	assert_eq!(result.exit_code, Some(124));
+}
+
+#[test]
+fn limits_are_not_exceeded_by_small_programs() {
+	let result = run_program("limits_none.b98", "52{+1}.@", &["--max-ticks", "100", "--max-pages", "1", "--max-stack", "10", "--max-threads", "1"]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "5 ");
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::run_program;
+
+#[test]
+fn semicolons_reflect_in_befunge93() {
+	let result = run_program("lint_semicolons.bf", "1;.@;", &["lint"]);
+	assert_eq!(result.exit_code, Some(1));
+	assert!(result.stdout.contains("lint_semicolons.bf:1:2: warning: `;` is not a Befunge-93 instruction and reflects"));
+	assert!(result.stdout.contains("lint_semicolons.bf:1:3: warning: unreachable code `.@`"));
+}
+
+#[test]
+fn semicolons_are_comments_in_befunge98() {
+	let result = run_program("lint_semicolons.b98", "1;.@;", &["lint"]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "");
+}
+
+#[test]
+fn funge98_instructions_reflect_in_befunge93() {
+	// k reflects, so the ip wraps around to @ without executing the dot
+	let result = run_program("lint_iterate.bf", "1k.@", &["cfg"]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "block 0 at (0, 0) east: 1k1@\n\t-> stop\n");
+}
+
+#[test]
+fn befunge93_wraps_around_the_torus() {
+	// On the 80x25 torus the # at the end of the line jumps over a space and the ip reaches @,
+	// when wrapping around the bounds of the code like Funge-98 it jumps over the @
+	let source = " v\n@>.#";
+	
+	let result = run_program("lint_torus.bf", source, &["cfg"]);
+	assert_eq!(result.exit_code, Some(0));
+	assert!(result.stdout.ends_with("-> stop\n"), "{}", result.stdout);
+	
+	let result = run_program("lint_torus.b98", source, &["cfg"]);
+	assert_eq!(result.exit_code, Some(0));
+	assert!(!result.stdout.contains("-> stop"), "{}", result.stdout);
+}
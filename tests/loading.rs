@@ -0,0 +1,34 @@
+mod common;
+
+use common::run_program;
+use std::path::PathBuf;
+
+/// Prints 1 and 2, with a form feed between the two
+const FORM_FEED_SOURCE: &str = "1.\x0c2.@";
+
+#[test]
+fn form_feeds_are_ignored_in_befunge() {
+	let result = run_program("loading_form_feed.b98", FORM_FEED_SOURCE, &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "1 2 ");
+}
+
+#[test]
+fn form_feeds_are_ignored_by_input_file_in_befunge() {
+	let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("loading_form_feed_input.b98");
+	std::fs::write(&path, FORM_FEED_SOURCE).unwrap();
+	
+	// Load the file at (0, 5) and print the position and size pushed by i
+	let reversed_path: String = path.to_str().unwrap().chars().rev().collect();
+	let source = format!("050 0\"{}\"i....@", reversed_path);
+	let result = run_program("loading_input_file.b98", &source, &[]);
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "5 0 1 5 ");
+}
+
+#[test]
+fn line_feeds_are_rejected_in_unefunge() {
+	let result = run_program("loading_line_feed.u98", "1.\n2.@", &["lint"]);
+	assert_eq!(result.exit_code, Some(3));
+	assert!(result.stderr.contains("the code has 2 dimensions but the dialect only supports 1"));
+}
@@ -0,0 +1,129 @@
+// This codebase uses explicit returns
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{run_file, RunResult};
+
+const MYCOLOGY: &str = "test/mycology/mycology.b98";
+
+/// Runs mycology with the given arguments and checks that it got to the end.
+fn run_mycology(args: &[&str]) -> RunResult {
+	let result = run_file(MYCOLOGY, args, "");
+	assert_eq!(result.exit_code, Some(15), "{}", result.stderr);
+	assert!(result.stdout.contains("The Mycology Befunge-98 test suite is practically done."));
+	return result;
+}
+
+/// Returns the lines mycology printed for the given fingerprint,
+/// from its "Testing fingerprint" line up to the next empty line.
+fn fingerprint_section<'a>(result: &'a RunResult, fingerprint: &str) -> Vec<&'a str> {
+	let header = format!("Testing fingerprint {}... loaded.", fingerprint);
+	return result.stdout.lines()
+		.skip_while(|line| *line != header)
+		.skip(1)
+		.take_while(|line| !line.is_empty())
+		.collect();
+}
+
+#[test]
+fn mycology_loads_as_utf8() {
+	// Mycology is not valid UTF-8, the invalid bytes are loaded as they are
+	let utf8 = run_mycology(&[]);
+	let latin1 = run_mycology(&["--encoding", "latin1"]);
+	
+	let good = |result: &RunResult| result.stdout.lines().filter(|l| l.starts_with("GOOD")).count();
+	assert_eq!(good(&utf8), good(&latin1));
+}
+
+#[test]
+fn mycology_output_does_not_depend_on_the_trace_cache() {
+	// Pin the clock and the rng, so that only the trace cache differs
+	let args = ["--seed", "42", "--fake-time", "2020-01-02T03:04:05", "--tick-micros", "10"];
+	let cached = run_mycology(&args);
+	let interpreted = run_mycology(&[&args[..], &["--no-trace-cache"]].concat());
+	assert_eq!(cached.stdout, interpreted.stdout);
+}
+
+#[test]
+fn mycology_y_reports_the_bounds_of_funge_space() {
+	let result = run_mycology(&[]);
+	let bad: Vec<&str> = result.stdout.lines().filter(|l| l.starts_with("BAD") && l.contains(" point")).collect();
+	assert!(bad.is_empty(), "{:?}", bad);
+}
+
+#[test]
+fn mycology_k_is_good() {
+	let result = run_mycology(&[]);
+	let lines = [
+		"GOOD: 0k^ doesn't execute ^",
+		"GOOD: 1k[ turns left from k",
+		"GOOD: 4k # jumps 4 times from k",
+		"GOOD: 2k ;;;5 executes 5 thrice",
+		"GOOD: 2k# jumps twice from k",
+		"GOOD: ak47k$ leaves 3 fours on stack",
+		"GOOD: 2k6 leaves 3 sixes on stack",
+	];
+	for line in &lines {
+		assert!(result.stdout.lines().any(|l| l == *line), "{}", line);
+	}
+}
+
+#[test]
+fn mycology_modu_is_good() {
+	let result = run_mycology(&[]);
+	let section = fingerprint_section(&result, "MODU");
+	let good: Vec<&str> = section.iter().copied().filter(|line| line.starts_with("GOOD")).collect();
+	assert_eq!(good, ["GOOD: a04-M pushes -2", "GOOD: a04-R pushes 2", "GOOD: 0a-04-R pushes -2", "GOOD: 0a-04-U pushes 2"]);
+	assert!(!section.iter().any(|line| line.starts_with("BAD")), "{:?}", section);
+}
+
+#[test]
+fn mycology_strn_is_good() {
+	let result = run_mycology(&[]);
+	let section = fingerprint_section(&result, "STRN");
+	assert_eq!(section.len(), 24, "{:?}", section);
+	assert!(section.iter().all(|line| line.starts_with("GOOD") || line.starts_with("UNDEF")), "{:?}", section);
+	
+	for line in &["GOOD: A works", "GOOD: F finds 'oba' in 'Foobar'", "GOOD: \"zaBraBooF\"34M is \"BarB\"", "GOOD: \"0987654321\"VS is 1234567890"] {
+		assert!(section.contains(line), "{}", line);
+	}
+}
+
+#[test]
+fn mycology_refc_is_good() {
+	let result = run_mycology(&[]);
+	let section = fingerprint_section(&result, "REFC");
+	assert_eq!(section, [
+		"UNDEF: 12R34R56R pushed the scalars [ 0 1 2 ]",
+		"GOOD: D returned first vector",
+		"GOOD: D returned second vector",
+		"GOOD: D returned third vector",
+		"GOOD: D returned first vector the second time",
+	]);
+}
+
+#[test]
+fn mycology_roma_is_good() {
+	let result = run_mycology(&[]);
+	let section = fingerprint_section(&result, "ROMA");
+	assert_eq!(section.len(), 7, "{:?}", section);
+	assert!(section.iter().all(|line| line.starts_with("GOOD")), "{:?}", section);
+	
+	// Loading and unloading ROMA and MODU on top of each other
+	for line in &["GOOD: M has MODU semantics", "GOOD: M has ROMA semantics", "GOOD: all of CDILVX reflected", "GOOD: R and U reflected"] {
+		assert!(result.stdout.contains(line), "{}", line);
+	}
+}
+
+#[test]
+fn mycology_toys_is_good() {
+	let result = run_mycology(&[]);
+	let section = fingerprint_section(&result, "TOYS");
+	assert_eq!(section.len(), 41, "{:?}", section);
+	assert!(section.iter().all(|line| line.starts_with("GOOD") || line.starts_with("UNDEF")), "{:?}", section);
+	
+	for line in &["GOOD: 882*1-N is -127", "GOOD: overlapping move with V works", "GOOD: S works", "GOOD: F works", "GOOD: G works"] {
+		assert!(section.contains(line), "{}", line);
+	}
+}
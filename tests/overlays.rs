@@ -0,0 +1,87 @@
+mod common;
+
+use common::{run_file, temp_path, write_temp_file};
+
+#[test]
+fn overlay_is_placed_at_its_position() {
+	// The ip runs from the main program into the overlay, which skips the 9
+	let main = write_temp_file("overlays_offset_main.b98", "1.   9");
+	let lib = write_temp_file("overlays_offset_lib.b98", "2.@");
+	let load = format!("{}@2,0", lib);
+	
+	let result = run_file(&main, &["--load", &load], "");
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "1 2 ");
+	assert_eq!(result.stderr, "");
+	
+	// The second line of the overlay starts at the x of the overlay, not at column 0
+	let lib = write_temp_file("overlays_offset_lines.b98", "v\n>3.@");
+	let load = format!("{}@2,0", lib);
+	let result = run_file(&main, &["--load", &load], "");
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "1 3 ");
+}
+
+#[test]
+fn overlapping_overlay_is_warned_about() {
+	let main = write_temp_file("overlays_overlap_main.b98", "1.x9");
+	let lib = write_temp_file("overlays_overlap_lib.b98", "@");
+	let load = format!("{}@2,0", lib);
+	
+	let result = run_file(&main, &["--load", &load], "");
+	assert_eq!(result.exit_code, Some(0));
+	assert_eq!(result.stdout, "1 ");
+	assert!(result.stderr.contains("overwrites 1 non-space cells, the first at"), "{}", result.stderr);
+	
+	// Spaces are transparent, so an overlay that only covers spaces isn't warned about
+	let lib = write_temp_file("overlays_overlap_spaces.b98", "  @");
+	let load = format!("{}@2,0", lib);
+	let main = write_temp_file("overlays_overlap_main_spaces.b98", "1.   9");
+	let result = run_file(&main, &["--load", &load], "");
+	assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn malformed_load_arguments_exit_with_2() {
+	let main = write_temp_file("overlays_malformed.b98", "@");
+	let lib = write_temp_file("overlays_malformed_lib.b98", "@");
+	
+	for value in &[lib.clone(), format!("{}@", lib), format!("{}@1,x", lib), format!("{}@1,2,3,4", lib), String::from("@1,2")] {
+		let result = run_file(&main, &["--load", value], "");
+		assert_eq!(result.exit_code, Some(2), "{}", value);
+		assert!(result.stderr.contains("expected FILE@X,Y[,Z]"), "{}", result.stderr);
+	}
+}
+
+#[test]
+fn load_position_with_too_many_coordinates_exits_with_2() {
+	// Three coordinates are fine in Trefunge but not in Befunge
+	let lib = write_temp_file("overlays_dims_lib.b98", "@");
+	let load = format!("{}@1,2,3", lib);
+	
+	let main = write_temp_file("overlays_dims.b98", "@");
+	let result = run_file(&main, &["--load", &load], "");
+	assert_eq!(result.exit_code, Some(2));
+	assert!(result.stderr.contains("has more coordinates than the dialect has dimensions"), "{}", result.stderr);
+	
+	let main = write_temp_file("overlays_dims.t98", "@");
+	let result = run_file(&main, &["--load", &load], "");
+	assert_eq!(result.exit_code, Some(0), "{}", result.stderr);
+}
+
+#[test]
+fn coverage_report_includes_overlays() {
+	let main = write_temp_file("overlays_coverage_main.b98", "1.   9");
+	let lib = write_temp_file("overlays_coverage_lib.b98", "2.@ 8");
+	let load = format!("{}@2,0", lib);
+	let report_file = temp_path("overlays_coverage.txt");
+	
+	let result = run_file(&main, &["--load", &load, "--coverage", &report_file], "");
+	assert_eq!(result.exit_code, Some(0));
+	
+	// Neither the 9 of the main program nor the 8 of the overlay is executed
+	let report = std::fs::read_to_string(&report_file).unwrap();
+	assert!(report.contains("Executed 5 of 7 non-space cells (71.43%)"), "{}", report);
+	assert!(report.contains(&format!("     -- \"{}\" at (2, 0, 0) --\n   0 | 2.@ 8\n     | ### .\n", lib)), "{}", report);
+	assert!(report.contains("overlays_coverage_lib.b98:1:5 (6, 0, 0) '8'"), "{}", report);
+}
@@ -0,0 +1,64 @@
+// This codebase uses explicit returns
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::{run_file, run_file_with_env, temp_path, write_temp_file, RunResult};
+
+/// Goes away with ? in a loop and prints a number for each path it took
+const RANDOM_WALK: &str = ".2?3.\n  1  \n  .  ";
+
+fn run_random_walk(args: &[&str], vars: &[(&str, &str)]) -> RunResult {
+	let path = write_temp_file("seed_walk.b98", RANDOM_WALK);
+	let mut args = args.to_vec();
+	args.extend_from_slice(&["--max-ticks", "100"]);
+	
+	let result = run_file_with_env(&path, &args, "", vars);
+	assert_eq!(result.exit_code, Some(120));
+	return result;
+}
+
+#[test]
+fn seed_replays_the_same_paths() {
+	let first = run_random_walk(&["--seed", "42"], &[]);
+	let second = run_random_walk(&["--seed", "42"], &[]);
+	assert_eq!(first.stdout, second.stdout);
+	
+	let other = run_random_walk(&["--seed", "43"], &[]);
+	assert_ne!(first.stdout, other.stdout);
+}
+
+#[test]
+fn seed_is_read_from_the_environment() {
+	let from_arg = run_random_walk(&["--seed", "42"], &[]);
+	let from_env = run_random_walk(&[], &[("RSFUNGE_SEED", "42")]);
+	assert_eq!(from_arg.stdout, from_env.stdout);
+	
+	// The argument takes precedence
+	let both = run_random_walk(&["--seed", "42"], &[("RSFUNGE_SEED", "43")]);
+	assert_eq!(from_arg.stdout, both.stdout);
+}
+
+#[test]
+fn errors_tell_the_seed() {
+	let result = run_random_walk(&["--seed", "42"], &[]);
+	assert!(result.stderr.contains("instruction limit of 100 executed instructions exceeded"), "{}", result.stderr);
+	assert!(result.stderr.trim_end().ends_with("(rng seed 42)"), "{}", result.stderr);
+	
+	// A random seed is told as well, and replays the run
+	let result = run_random_walk(&[], &[]);
+	let seed = result.stderr.trim_end().rsplit(' ').next().unwrap().trim_end_matches(')');
+	let replay = run_random_walk(&["--seed", seed], &[]);
+	assert_eq!(result.stdout, replay.stdout);
+}
+
+#[test]
+fn coverage_report_tells_the_seed() {
+	let path = write_temp_file("seed_coverage.b98", "?@");
+	let report_file = temp_path("seed_coverage.txt");
+	let result = run_file(&path, &["--seed", "42", "--coverage", &report_file], "");
+	assert_eq!(result.exit_code, Some(0));
+	
+	let report = std::fs::read_to_string(&report_file).unwrap();
+	assert!(report.contains("Rng seed of the last run: 42"), "{}", report);
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{run_file, run_program, temp_path, write_temp_file};
+
+#[test]
+fn overlap_warning_tells_file_positions() {
+	let main = write_temp_file("source_map_overlap_main.b98", "1.x9");
+	let lib = write_temp_file("source_map_overlap_lib.b98", "@");
+	let load = format!("{}@2,0", lib);
+	
+	let result = run_file(&main, &["--load", &load], "");
+	assert_eq!(result.exit_code, Some(0));
+	let expected = format!("rsfunge: warning: \"{}\" loaded at {}:1:3 overwrites 1 non-space cells, the first at {}:1:3\n", lib, main, main);
+	assert_eq!(result.stderr, expected);
+}
+
+#[test]
+fn cells_of_overlays_are_mapped_to_their_file() {
+	// The ip bounces between the v of the main program and the ^ of the overlay below it
+	let main = write_temp_file("source_map_overlay_main.b98", "v");
+	let lib = write_temp_file("source_map_overlay_lib.b98", "^");
+	let load = format!("{}@0,1", lib);
+	
+	let result = run_file(&main, &["--load", &load, "--max-ticks", "10"], "");
+	assert_eq!(result.exit_code, Some(120));
+	assert!(result.stderr.contains(&format!("exceeded at {}:1:1 ", lib)), "{}", result.stderr);
+	
+	let result = run_file(&main, &["--load", &load, "--max-ticks", "11"], "");
+	assert!(result.stderr.contains(&format!("exceeded at {}:1:1 ", main)), "{}", result.stderr);
+}
+
+#[test]
+fn cells_loaded_by_input_file_are_mapped_to_their_file() {
+	// Loads the file at (0, 1), then bounces between the v and the ^ of the loaded file below it
+	let lib_path = temp_path("source_map_input_lib.b98");
+	let reversed_path: String = lib_path.chars().rev().collect();
+	let main_code = format!("010 0\"{}\"iv", reversed_path);
+	let column = main_code.chars().count();
+	write_temp_file("source_map_input_lib.b98", &format!("{}^", " ".repeat(column - 1)));
+	
+	// The number of instructions before the loop depends on the length of the path,
+	// so one of two runs with one instruction difference stops in the loaded file
+	let location = format!("exceeded at {}:1:{} ", lib_path, column);
+	let stderr: Vec<String> = ["200", "201"].iter().map(|max_ticks| {
+		let result = run_program("source_map_input_main.b98", &main_code, &["--max-ticks", max_ticks]);
+		assert_eq!(result.exit_code, Some(120));
+		result.stderr
+	}).collect();
+	assert!(stderr.iter().any(|s| s.contains(&location)), "{:?}", stderr);
+}
+
+#[test]
+fn cells_written_by_put_are_marked_as_modified() {
+	// p replaces the 9 below the v with <, then the ip bounces between the > and the <
+	let source = "\"<\"61pv\n     >9";
+	let result = run_program("source_map_put.b98", source, &["--max-ticks", "20"]);
+	assert_eq!(result.exit_code, Some(120));
+	assert!(result.stderr.contains("source_map_put.b98:2:7 (modified) "), "{}", result.stderr);
+	
+	// The > next to it still holds the code of the file
+	let result = run_program("source_map_put.b98", source, &["--max-ticks", "21"]);
+	assert!(result.stderr.contains("source_map_put.b98:2:6 "), "{}", result.stderr);
+}
@@ -0,0 +1,22 @@
+// This codebase uses explicit returns
+#![allow(clippy::needless_return)]
+
+mod common;
+
+use common::run_program;
+
+/// Counts up with a hot run of 1+ and makes it count in steps of 2 with p at 20
+const SELF_MODIFYING_COUNTER: &str = "0>1+:.:54*-v\n |   !`*88:_'220p1v\n @         ^      <";
+
+#[test]
+fn put_invalidates_hot_runs() {
+	let mut expected: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+	expected.extend((22..=66).step_by(2).map(|n| n.to_string()));
+	let expected = expected.join(" ") + " ";
+	
+	for args in &[&[][..], &["--no-trace-cache"][..]] {
+		let result = run_program("trace_cache_counter.b98", SELF_MODIFYING_COUNTER, args);
+		assert_eq!(result.exit_code, Some(0), "{}", result.stderr);
+		assert_eq!(result.stdout, expected);
+	}
+}